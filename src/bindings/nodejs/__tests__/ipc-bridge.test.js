@@ -0,0 +1,75 @@
+const fs = require('fs');
+const os = require('os');
+const path = require('path');
+
+const {
+  RustIPCBridge,
+  MEMORY_LAYOUT,
+  MAGIC_NUMBER,
+  VERSION,
+} = require('../src-js/ipc-bridge');
+
+/**
+ * Write a ring file laid out like the daemon's, holding one Modified event
+ * per path
+ */
+function writeRing(file, capacity, paths, shutdown) {
+  const eventSize = MEMORY_LAYOUT.SERIALIZED_EVENT_SIZE;
+  const buffer = Buffer.alloc(MEMORY_LAYOUT.HEADER_SIZE + capacity * eventSize);
+  buffer.writeUInt32LE(MAGIC_NUMBER, MEMORY_LAYOUT.MAGIC_OFFSET);
+  buffer.writeUInt32LE(VERSION, MEMORY_LAYOUT.VERSION_OFFSET);
+  buffer.writeUInt32LE(paths.length, MEMORY_LAYOUT.WRITE_POS_OFFSET);
+  buffer.writeUInt32LE(capacity, MEMORY_LAYOUT.CAPACITY_OFFSET);
+  buffer.writeUInt32LE(eventSize, MEMORY_LAYOUT.SLOT_SIZE_OFFSET);
+  buffer.writeUInt32LE(shutdown ? 1 : 0, MEMORY_LAYOUT.SHUTDOWN_FLAG_OFFSET);
+
+  paths.forEach((eventPath, i) => {
+    const offset = MEMORY_LAYOUT.HEADER_SIZE + i * eventSize;
+    const pathBytes = Buffer.from(eventPath);
+    buffer.writeBigUInt64LE(1n, offset + MEMORY_LAYOUT.EVENT_TIMESTAMP_OFFSET);
+    buffer.writeUInt32LE(1, offset + MEMORY_LAYOUT.EVENT_TYPE_OFFSET);
+    buffer.writeUInt32LE(
+      pathBytes.length,
+      offset + MEMORY_LAYOUT.EVENT_PATH_LEN_OFFSET
+    );
+    pathBytes.copy(buffer, offset + MEMORY_LAYOUT.EVENT_PATH_DATA_OFFSET);
+  });
+
+  fs.writeFileSync(file, buffer);
+}
+
+describe('RustIPCBridge', () => {
+  let dir;
+  let bridge;
+
+  beforeEach(() => {
+    dir = fs.mkdtempSync(path.join(os.tmpdir(), 'retrigger-ipc-'));
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+  });
+
+  afterEach(() => {
+    bridge.disconnect();
+    fs.rmSync(dir, { recursive: true, force: true });
+    jest.restoreAllMocks();
+  });
+
+  test('delivers every pending event before reporting shutdown', async () => {
+    // More than one read's worth, so draining takes several passes
+    const file = path.join(dir, 'ring.mmap');
+    const paths = Array.from({ length: 1500 }, (_, i) => `/project/${i}.rs`);
+    writeRing(file, 2048, paths, true);
+
+    bridge = new RustIPCBridge(file);
+    await bridge.connect();
+    const seen = [];
+    bridge.on('file-event', (event) => seen.push(event.path));
+    bridge.on('shutdown', () => seen.push('shutdown'));
+    bridge.processEvents();
+
+    expect(seen).toEqual([...paths, 'shutdown']);
+
+    // The read position reaches the daemon, so its drain can finish
+    const header = fs.readFileSync(file);
+    expect(header.readUInt32LE(MEMORY_LAYOUT.READ_POS_OFFSET)).toBe(1500);
+  });
+});
@@ -11,13 +11,46 @@ export interface JsFileEvent {
   size: string
   isDirectory: boolean
   hash?: JsHashResult
+  /** Number of events dropped, on `lagged` markers only */
+  missed?: string
 }
 /** Hash result for Node.js */
 export interface JsHashResult {
   hash: string
+  /**
+   * Hex digest, comparable with external tools when the full digest was
+   * computed in this process (see `HashResult::to_hex`)
+   */
+  hex: string
+  size: number
+  isIncremental: boolean
+}
+/** File event with native `BigInt` fields, emitted when `bigint` is enabled */
+export interface JsFileEventBigInt {
+  path: string
+  eventType: string
+  timestamp: bigint
+  size: bigint
+  isDirectory: boolean
+  hash?: JsHashResultBigInt
+  /** Number of events dropped, on `lagged` markers only */
+  missed?: bigint
+}
+/** Hash result with a native `BigInt` hash */
+export interface JsHashResultBigInt {
+  hash: bigint
+  hex: string
   size: number
   isIncremental: boolean
 }
+/** Construction options for `RetriggerWrapper` */
+export interface RetriggerOptions {
+  /**
+   * Emit `timestamp`, `size` and `hash` as `BigInt` instead of decimal
+   * strings, avoiding a string allocation per field (default: false)
+   */
+  bigint?: boolean
+}
 /** Watcher statistics for Node.js */
 export interface JsWatcherStats {
   pendingEvents: number
@@ -45,7 +78,7 @@ export declare function benchmarkHash(testSize: number): Promise<NapiResult>
 /** Main Retrigger wrapper for Node.js */
 export declare class RetriggerWrapper {
   /** Create a new Retrigger instance */
-  constructor()
+  constructor(options?: RetriggerOptions | undefined | null)
   /**
    * Watch a directory for changes
    *
@@ -18,7 +18,7 @@ const MEMORY_LAYOUT = {
   WRITE_POS_OFFSET: 8, // u32: Write position (atomic)
   READ_POS_OFFSET: 12, // u32: Read position (atomic)
   CAPACITY_OFFSET: 16, // u32: Ring capacity
-  EVENT_SIZE_OFFSET: 20, // u32: Event size in bytes
+  SLOT_SIZE_OFFSET: 20, // u32: Event size in bytes
   TOTAL_EVENTS_OFFSET: 24, // u64: Total events processed
   DROPPED_EVENTS_OFFSET: 32, // u64: Dropped events
   LAST_WRITE_TS_OFFSET: 40, // u64: Last write timestamp
@@ -29,7 +29,7 @@ const MEMORY_LAYOUT = {
   MAX_UTILIZATION_OFFSET: 68, // u32: Max utilization
   AVG_LATENCY_OFFSET: 72, // u64: Average latency
 
-  HEADER_SIZE: 128, // Total header size (aligned)
+  HEADER_SIZE: 80, // size_of::<RingHeader>(); event slots follow it
 
   // Event structure constants
   EVENT_TIMESTAMP_OFFSET: 0, // u64: Event timestamp
@@ -39,13 +39,14 @@ const MEMORY_LAYOUT = {
   EVENT_IS_DIR_OFFSET: 24, // u32: Is directory
   EVENT_HASH_PRESENT_OFFSET: 28, // u32: Hash present flag
   EVENT_HASH_VALUE_OFFSET: 32, // u64: Hash value
-  EVENT_PATH_DATA_OFFSET: 40, // [u8; 512]: Path data
+  EVENT_EVENTS_PROCESSED_OFFSET: 40, // u64: Events processed (heartbeats)
+  EVENT_PATH_DATA_OFFSET: 48, // [u8; 512]: Path data
 
-  SERIALIZED_EVENT_SIZE: 552, // Total serialized event size
+  SERIALIZED_EVENT_SIZE: 560, // Total serialized event size
 };
 
 const MAGIC_NUMBER = 0x52545247; // 'RTRG' in little endian
-const VERSION = 1;
+const VERSION = 2; // Must match retrigger_system::WIRE_FORMAT_VERSION
 
 /**
  * Event type mapping (must match Rust enum)
@@ -56,8 +57,24 @@ const EVENT_TYPES = {
   2: 'deleted',
   3: 'moved',
   4: 'metadata_changed',
+  5: 'stabilized_modified',
+  6: 'heartbeat',
 };
 
+/**
+ * Whether a process exists; EPERM means it does but belongs to another user
+ * @param {number} pid
+ * @returns {boolean}
+ */
+function processAlive(pid) {
+  try {
+    process.kill(pid, 0);
+    return true;
+  } catch (error) {
+    return error.code === 'EPERM';
+  }
+}
+
 /**
  * Direct memory-mapped file reader for the Rust daemon IPC
  */
@@ -171,14 +188,34 @@ class RustIPCBridge extends EventEmitter {
 
   /**
    * Register this process as a consumer
+   *
+   * The ring has one read position and one consumer PID slot, so it takes a
+   * single consumer process at a time; fails while another live process is
+   * registered. A PID left by a process that has exited is taken over.
    * @private
    */
   registerConsumer() {
-    // Atomic write to register our PID
-    this.buffer.writeUInt32LE(
-      this.consumerPid,
-      MEMORY_LAYOUT.CONSUMER_PID_OFFSET
-    );
+    const current = this.buffer.readUInt32LE(MEMORY_LAYOUT.CONSUMER_PID_OFFSET);
+    if (
+      current !== 0 &&
+      current !== this.consumerPid &&
+      processAlive(current)
+    ) {
+      throw new Error(
+        `IPC ring ${this.mmapPath} already has a consumer (pid ${current})`
+      );
+    }
+
+    this.writeHeaderU32(MEMORY_LAYOUT.CONSUMER_PID_OFFSET, this.consumerPid);
+  }
+
+  /**
+   * Write a u32 header field to the local buffer and the shared file
+   * @private
+   */
+  writeHeaderU32(offset, value) {
+    this.buffer.writeUInt32LE(value, offset);
+    fs.writeSync(this.fd, this.buffer, offset, 4, offset);
   }
 
   /**
@@ -225,29 +262,40 @@ class RustIPCBridge extends EventEmitter {
       const shutdownFlag = this.buffer.readUInt32LE(
         MEMORY_LAYOUT.SHUTDOWN_FLAG_OFFSET
       );
-      if (shutdownFlag !== 0) {
-        this.emit('shutdown');
-        return;
-      }
 
-      // Read events from ring buffer
-      const events = this.readAvailableEvents();
+      // Read events from ring buffer; on shutdown, keep reading until it is
+      // empty so the events the daemon waited for are not left behind
+      let events;
+      do {
+        events = this.readAvailableEvents();
+        this.emitEvents(events);
+      } while (shutdownFlag !== 0 && events.length > 0);
 
-      // Emit events
-      events.forEach((event) => {
-        this.emit('file-event', event);
-      });
-
-      if (events.length > 0) {
-        this.emit('batch-processed', events.length);
-        this.stats.eventsRead += events.length;
-        this.stats.lastReadTime = Date.now();
+      if (shutdownFlag !== 0) {
+        this.emit('shutdown');
       }
     } catch (error) {
       this.emit('error', error);
     }
   }
 
+  /**
+   * Emit a batch of events read from the ring
+   * @private
+   * @param {Array} events
+   */
+  emitEvents(events) {
+    events.forEach((event) => {
+      this.emit('file-event', event);
+    });
+
+    if (events.length > 0) {
+      this.emit('batch-processed', events.length);
+      this.stats.eventsRead += events.length;
+      this.stats.lastReadTime = Date.now();
+    }
+  }
+
   /**
    * Refresh the buffer from the memory-mapped file
    * @private
@@ -302,17 +350,18 @@ class RustIPCBridge extends EventEmitter {
     }
 
     const capacity = this.buffer.readUInt32LE(MEMORY_LAYOUT.CAPACITY_OFFSET);
-    const eventSize = this.buffer.readUInt32LE(MEMORY_LAYOUT.EVENT_SIZE_OFFSET);
+    const eventSize = this.buffer.readUInt32LE(MEMORY_LAYOUT.SLOT_SIZE_OFFSET);
 
     // Calculate event position
     const eventOffset = MEMORY_LAYOUT.HEADER_SIZE + readPos * eventSize;
 
-    // Read and deserialize event
+    // Read and deserialize event; the slot was written after connect
+    fs.readSync(this.fd, this.buffer, eventOffset, eventSize, eventOffset);
     const event = this.deserializeEvent(eventOffset);
 
-    // Update read position
+    // Update read position, in the file too so the daemon sees it consumed
     const nextReadPos = (readPos + 1) % capacity;
-    this.buffer.writeUInt32LE(nextReadPos, MEMORY_LAYOUT.READ_POS_OFFSET);
+    this.writeHeaderU32(MEMORY_LAYOUT.READ_POS_OFFSET, nextReadPos);
 
     // Update statistics
     if (event) {
@@ -356,8 +405,9 @@ class RustIPCBridge extends EventEmitter {
         offset + MEMORY_LAYOUT.EVENT_HASH_VALUE_OFFSET
       );
 
-      // Read path data
-      if (pathLen === 0 || pathLen > 511) {
+      // Read path data; only heartbeats have an empty path
+      const emptyAllowed = EVENT_TYPES[eventType] === 'heartbeat';
+      if ((pathLen === 0 && !emptyAllowed) || pathLen > 511) {
         console.warn(`Invalid path length: ${pathLen}`);
         return null;
       }
@@ -368,8 +418,7 @@ class RustIPCBridge extends EventEmitter {
       );
       const path = pathBuffer.toString('utf8');
 
-      // Create event object
-      return {
+      const event = {
         path,
         event_type: EVENT_TYPES[eventType] || 'modified',
         timestamp: timestamp.toString(),
@@ -378,11 +427,19 @@ class RustIPCBridge extends EventEmitter {
         hash: hashPresent
           ? {
               hash: hashValue.toString(),
+              // Only the 64-bit hash crosses IPC, not the full digest
+              hex_truncated: hashValue.toString(16).padStart(16, '0'),
               algorithm: 'XXH3',
               is_incremental: false,
             }
           : null,
       };
+      if (event.event_type === 'heartbeat') {
+        event.events_processed = this.buffer
+          .readBigUInt64LE(offset + MEMORY_LAYOUT.EVENT_EVENTS_PROCESSED_OFFSET)
+          .toString();
+      }
+      return event;
     } catch (error) {
       console.error('Failed to deserialize event:', error);
       return null;
@@ -464,6 +521,19 @@ class RustIPCBridge extends EventEmitter {
     this.stopPolling();
 
     if (this.fd !== null) {
+      try {
+        // Deregister, unless another consumer has already taken over
+        this.refreshBuffer();
+        const current = this.buffer.readUInt32LE(
+          MEMORY_LAYOUT.CONSUMER_PID_OFFSET
+        );
+        if (current === this.consumerPid) {
+          this.writeHeaderU32(MEMORY_LAYOUT.CONSUMER_PID_OFFSET, 0);
+        }
+      } catch (error) {
+        console.warn('Error deregistering consumer:', error);
+      }
+
       try {
         fs.closeSync(this.fd);
       } catch (error) {
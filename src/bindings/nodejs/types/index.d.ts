@@ -6,8 +6,11 @@
 export interface FileEvent {
   /** Absolute path to the file that changed */
   path: string;
-  /** Type of file system event */
-  event_type: 'created' | 'modified' | 'deleted' | 'moved' | 'metadata_changed';
+  /**
+   * Type of file system event. `lagged` is not a file event: the consumer fell
+   * behind, `missed` events were dropped and `path` is empty.
+   */
+  event_type: 'created' | 'modified' | 'deleted' | 'moved' | 'metadata_changed' | 'stabilized_modified' | 'heartbeat' | 'lagged';
   /** Timestamp of the event in nanoseconds (as string for BigInt compatibility) */
   timestamp: string;
   /** Size of the file in bytes (as string for BigInt compatibility) */
@@ -16,11 +19,42 @@ export interface FileEvent {
   is_directory: boolean;
   /** Hash information if available */
   hash?: HashResult;
+  /** Number of events dropped, on `lagged` markers only */
+  missed?: string;
+  /** Events the daemon has processed since startup, on `heartbeat` events only */
+  events_processed?: string;
+}
+
+/** File event emitted when the watcher is created with `{ bigint: true }` */
+export interface FileEventBigInt extends Omit<FileEvent, 'timestamp' | 'size' | 'hash' | 'missed'> {
+  /** Timestamp of the event in nanoseconds */
+  timestamp: bigint;
+  /** Size of the file in bytes */
+  size: bigint;
+  /** Number of events dropped, on `lagged` markers only */
+  missed?: bigint;
+  /** Hash information if available */
+  hash?: Omit<HashResult, 'hash'> & { hash: bigint };
+}
+
+export interface RetriggerOptions {
+  /** Emit timestamp, size and hash as BigInt instead of strings (default: false) */
+  bigint?: boolean;
 }
 
 export interface HashResult {
   /** File hash as string (for BigInt compatibility) */
   hash: string;
+  /**
+   * Hash as lowercase hex from the native bindings: the full digest when
+   * available (matching `b3sum`), else 16 chars. Absent on IPC events.
+   */
+  hex?: string;
+  /**
+   * The truncated 64-bit hash as 16 hex chars, set on events read over IPC,
+   * which does not carry the full digest. Not comparable with `b3sum`.
+   */
+  hex_truncated?: string;
   /** Size of hashed content in bytes */
   size: number;
   /** Whether this was computed incrementally */
@@ -67,7 +101,7 @@ export interface BenchmarkResult {
  */
 export class RetriggerWrapper {
   /** Create a new Retrigger instance */
-  constructor();
+  constructor(options?: RetriggerOptions);
   
   /** Watch a directory for changes */
   watch_directory(path: string, options?: WatchOptions): Promise<void>;
@@ -75,11 +109,14 @@ export class RetriggerWrapper {
   /** Start the file watcher */
   start(): Promise<void>;
   
-  /** Poll for the next event (non-blocking) */
-  poll_event(): Promise<FileEvent | null>;
+  /**
+   * Poll for the next event (non-blocking); `FileEventBigInt` when created with `bigint`.
+   * Returns a `lagged` event after dropped events, and `null` once the stream closes.
+   */
+  poll_event(): Promise<FileEvent | FileEventBigInt | null>;
   
   /** Wait for the next event with timeout in milliseconds */
-  wait_event(timeout_ms: number): Promise<FileEvent | null>;
+  wait_event(timeout_ms: number): Promise<FileEvent | FileEventBigInt | null>;
   
   /** Get watcher statistics */
   get_stats(): Promise<WatcherStats>;
@@ -3,6 +3,7 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use napi::{bindgen_prelude::*, tokio::sync::broadcast, Result as NapiResult};
 use napi_derive::napi;
@@ -20,6 +21,8 @@ pub struct JsFileEvent {
     pub size: String,      // Use string for BigInt compatibility
     pub is_directory: bool,
     pub hash: Option<JsHashResult>,
+    /// Number of events dropped, on `lagged` markers only
+    pub missed: Option<String>,
 }
 
 /// Hash result for Node.js
@@ -27,10 +30,68 @@ pub struct JsFileEvent {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsHashResult {
     pub hash: String, // Use string for BigInt compatibility
+    /// Hex digest, comparable with external tools when the full digest was
+    /// computed in this process (see `HashResult::to_hex`)
+    pub hex: String,
     pub size: u32,
     pub is_incremental: bool,
 }
 
+impl From<&retrigger_core::HashResult> for JsHashResult {
+    fn from(result: &retrigger_core::HashResult) -> Self {
+        Self {
+            hash: result.hash.to_string(),
+            hex: result.to_hex(),
+            size: result.size,
+            is_incremental: result.is_incremental,
+        }
+    }
+}
+
+/// File event with native `BigInt` fields, emitted when `bigint` is enabled
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct JsFileEventBigInt {
+    pub path: String,
+    pub event_type: String,
+    pub timestamp: BigInt,
+    pub size: BigInt,
+    pub is_directory: bool,
+    pub hash: Option<JsHashResultBigInt>,
+    /// Number of events dropped, on `lagged` markers only
+    pub missed: Option<BigInt>,
+}
+
+/// Hash result with a native `BigInt` hash
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct JsHashResultBigInt {
+    pub hash: BigInt,
+    pub hex: String,
+    pub size: u32,
+    pub is_incremental: bool,
+}
+
+impl From<&retrigger_core::HashResult> for JsHashResultBigInt {
+    fn from(result: &retrigger_core::HashResult) -> Self {
+        Self {
+            hash: BigInt::from(result.hash),
+            hex: result.to_hex(),
+            size: result.size,
+            is_incremental: result.is_incremental,
+        }
+    }
+}
+
+/// Construction options for `RetriggerWrapper`
+#[napi(object)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetriggerOptions {
+    /// Emit `timestamp`, `size` and `hash` as `BigInt` instead of decimal
+    /// strings, avoiding a string allocation per field (default: false)
+    pub bigint: Option<bool>,
+}
+
 /// Watcher statistics for Node.js
 #[napi(object)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -77,11 +138,12 @@ pub struct RetriggerWrapper {
     #[allow(dead_code)]
     hash_engine: Arc<HashEngine>,
     event_receiver: Option<broadcast::Receiver<SystemEvent>>,
+    bigint: bool,
 }
 
 impl Default for RetriggerWrapper {
     fn default() -> Self {
-        Self::new()
+        Self::new(None)
     }
 }
 
@@ -89,7 +151,7 @@ impl Default for RetriggerWrapper {
 impl RetriggerWrapper {
     /// Create a new Retrigger instance
     #[napi(constructor)]
-    pub fn new() -> Self {
+    pub fn new(options: Option<RetriggerOptions>) -> Self {
         // Use a safe fallback when system watcher creation fails
         let system_watcher = match SystemWatcher::new() {
             Ok(watcher) => Arc::new(watcher),
@@ -107,6 +169,7 @@ impl RetriggerWrapper {
             event_processor,
             hash_engine,
             event_receiver: None,
+            bigint: options.and_then(|o| o.bigint).unwrap_or(false),
         }
     }
 
@@ -158,12 +221,17 @@ impl RetriggerWrapper {
     }
 
     /// Get the next file event (non-blocking)
+    ///
+    /// If the caller fell behind and events were dropped, a `lagged` event
+    /// whose `missed` is the number of dropped events is returned in their
+    /// place; rescan if that matters. Resolves to `null` once the watcher's
+    /// event stream has closed.
     /// 
     /// # Safety
     /// This function is marked unsafe due to napi-rs requirements for async functions.
     /// It's safe to call from Node.js as the underlying operations are memory-safe.
     #[napi]
-    pub async unsafe fn poll_event(&mut self) -> NapiResult<Option<JsFileEvent>> {
+    pub async unsafe fn poll_event(&mut self) -> NapiResult<Option<Either<JsFileEvent, JsFileEventBigInt>>> {
         // First try the event receiver for any cached events
         if let Some(ref mut receiver) = self.event_receiver {
             match receiver.try_recv() {
@@ -179,15 +247,15 @@ impl RetriggerWrapper {
                                 )
                             })?;
 
-                    return Ok(Some(convert_to_js_event(enhanced)));
+                    return Ok(Some(convert_event(enhanced, self.bigint)));
                 }
                 Err(broadcast::error::TryRecvError::Empty) => {
                     // No cached events, try polling for new ones
                 }
-                Err(e) => return Err(Error::new(
-                    Status::GenericFailure,
-                    format!("Event receiver error: {e}"),
-                )),
+                Err(broadcast::error::TryRecvError::Lagged(missed)) => {
+                    return Ok(Some(lagged_event(missed, self.bigint)));
+                }
+                Err(broadcast::error::TryRecvError::Closed) => return Ok(None),
             }
         }
 
@@ -207,19 +275,21 @@ impl RetriggerWrapper {
                 )
             })?;
 
-            Ok(Some(convert_to_js_event(enhanced)))
+            Ok(Some(convert_event(enhanced, self.bigint)))
         } else {
             Ok(None)
         }
     }
 
     /// Wait for the next file event with timeout
+    ///
+    /// Reports missed events and end of stream like `poll_event`.
     /// 
     /// # Safety
     /// This function is marked unsafe due to napi-rs requirements for async functions.
     /// It's safe to call from Node.js as the underlying operations are memory-safe.
     #[napi]
-    pub async unsafe fn wait_event(&mut self, timeout_ms: u32) -> NapiResult<Option<JsFileEvent>> {
+    pub async unsafe fn wait_event(&mut self, timeout_ms: u32) -> NapiResult<Option<Either<JsFileEvent, JsFileEventBigInt>>> {
         if let Some(ref mut receiver) = self.event_receiver {
             let timeout = std::time::Duration::from_millis(timeout_ms as u64);
 
@@ -236,12 +306,12 @@ impl RetriggerWrapper {
                                 )
                             })?;
 
-                    Ok(Some(convert_to_js_event(enhanced)))
+                    Ok(Some(convert_event(enhanced, self.bigint)))
                 }
-                Ok(Err(e)) => Err(Error::new(
-                    Status::GenericFailure,
-                    format!("Event receiver error: {e}"),
-                )),
+                Ok(Err(broadcast::error::RecvError::Lagged(missed))) => {
+                    Ok(Some(lagged_event(missed, self.bigint)))
+                }
+                Ok(Err(broadcast::error::RecvError::Closed)) => Ok(None),
                 Err(_) => Ok(None), // Timeout
             }
         } else {
@@ -274,11 +344,7 @@ impl RetriggerWrapper {
             )
         })?;
 
-        Ok(JsHashResult {
-            hash: result.hash.to_string(),
-            size: result.size,
-            is_incremental: result.is_incremental,
-        })
+        Ok(JsHashResult::from(&result))
     }
 
     /// Hash bytes directly
@@ -292,11 +358,7 @@ impl RetriggerWrapper {
             )
         })?;
 
-        Ok(JsHashResult {
-            hash: result.hash.to_string(),
-            size: result.size,
-            is_incremental: result.is_incremental,
-        })
+        Ok(JsHashResult::from(&result))
     }
 
     /// Get SIMD optimization level
@@ -306,21 +368,24 @@ impl RetriggerWrapper {
     }
 }
 
-/// Convert internal event to JavaScript-friendly event
-fn convert_to_js_event(enhanced: retrigger_system::EnhancedFileEvent) -> JsFileEvent {
-    let event_type = match enhanced.system_event.event_type {
+/// JavaScript name for an event type
+fn event_type_name(event_type: SystemEventType) -> &'static str {
+    match event_type {
         SystemEventType::Created => "created",
         SystemEventType::Modified => "modified",
         SystemEventType::Deleted => "deleted",
         SystemEventType::Moved => "moved",
         SystemEventType::MetadataChanged => "metadata_changed",
-    };
+        SystemEventType::StabilizedModified => "stabilized_modified",
+        SystemEventType::Heartbeat => "heartbeat",
+    }
+}
+
+/// Convert internal event to JavaScript-friendly event
+fn convert_to_js_event(enhanced: retrigger_system::EnhancedFileEvent) -> JsFileEvent {
+    let event_type = event_type_name(enhanced.system_event.event_type);
 
-    let hash = enhanced.hash.map(|h| JsHashResult {
-        hash: h.hash.to_string(),
-        size: h.size,
-        is_incremental: h.is_incremental,
-    });
+    let hash = enhanced.hash.as_ref().map(JsHashResult::from);
 
     JsFileEvent {
         path: enhanced.system_event.path.to_string_lossy().to_string(),
@@ -329,6 +394,65 @@ fn convert_to_js_event(enhanced: retrigger_system::EnhancedFileEvent) -> JsFileE
         size: enhanced.system_event.size.to_string(),
         is_directory: enhanced.system_event.is_directory,
         hash,
+        missed: None,
+    }
+}
+
+/// Convert an event to the representation selected at construction
+fn convert_event(
+    enhanced: retrigger_system::EnhancedFileEvent,
+    bigint: bool,
+) -> Either<JsFileEvent, JsFileEventBigInt> {
+    if bigint {
+        Either::B(convert_to_js_event_bigint(enhanced))
+    } else {
+        Either::A(convert_to_js_event(enhanced))
+    }
+}
+
+/// Convert internal event to an event with `BigInt` fields
+fn convert_to_js_event_bigint(enhanced: retrigger_system::EnhancedFileEvent) -> JsFileEventBigInt {
+    let event = enhanced.system_event;
+
+    JsFileEventBigInt {
+        path: event.path.to_string_lossy().to_string(),
+        event_type: event_type_name(event.event_type).to_string(),
+        timestamp: BigInt::from(event.timestamp),
+        size: BigInt::from(event.size),
+        is_directory: event.is_directory,
+        hash: enhanced.hash.as_ref().map(JsHashResultBigInt::from),
+        missed: None,
+    }
+}
+
+/// Marker event standing in for `missed` events the consumer fell too far
+/// behind to receive
+fn lagged_event(missed: u64, bigint: bool) -> Either<JsFileEvent, JsFileEventBigInt> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+
+    if bigint {
+        Either::B(JsFileEventBigInt {
+            path: String::new(),
+            event_type: "lagged".to_string(),
+            timestamp: BigInt::from(timestamp),
+            size: BigInt::from(0u64),
+            is_directory: false,
+            hash: None,
+            missed: Some(BigInt::from(missed)),
+        })
+    } else {
+        Either::A(JsFileEvent {
+            path: String::new(),
+            event_type: "lagged".to_string(),
+            timestamp: timestamp.to_string(),
+            size: "0".to_string(),
+            is_directory: false,
+            hash: None,
+            missed: Some(missed.to_string()),
+        })
     }
 }
 
@@ -343,11 +467,7 @@ pub fn hash_file_sync(path: String) -> NapiResult<JsHashResult> {
         )
     })?;
 
-    Ok(JsHashResult {
-        hash: result.hash.to_string(),
-        size: result.size,
-        is_incremental: result.is_incremental,
-    })
+    Ok(JsHashResult::from(&result))
 }
 
 /// Simplified direct hash function for bytes
@@ -371,15 +491,12 @@ pub fn hash_bytes_sync(data: Buffer) -> NapiResult<JsHashResult> {
                 hash: hash_u64,
                 size: data.len() as u32,
                 is_incremental: false,
+                digest: Some(*bytes),
             }
         }
     };
 
-    Ok(JsHashResult {
-        hash: result.hash.to_string(),
-        size: result.size,
-        is_incremental: result.is_incremental,
-    })
+    Ok(JsHashResult::from(&result))
 }
 
 /// Get SIMD capabilities
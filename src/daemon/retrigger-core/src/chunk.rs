@@ -0,0 +1,248 @@
+//! Content-defined chunking for delta sync
+//!
+//! A Gear rolling hash over the last 64 bytes picks chunk boundaries from the
+//! content itself, so inserting or deleting bytes only changes the chunks
+//! around the edit; every other chunk keeps its digest even though its offset
+//! moved. Comparing two chunk lists by digest (see [`changed_chunks`]) finds
+//! the regions that actually need transferring.
+
+use std::collections::HashSet;
+use std::io::Read;
+
+use serde::{Deserialize, Serialize};
+
+use crate::HashError;
+
+/// Chunk size bounds, in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkParams {
+    /// No boundary is placed before a chunk reaches this size
+    pub min_size: usize,
+    /// Expected chunk size; rounded down to a power of two
+    pub avg_size: usize,
+    /// A boundary is forced at this size
+    pub max_size: usize,
+}
+
+impl Default for ChunkParams {
+    fn default() -> Self {
+        Self {
+            min_size: 2 * 1024,
+            avg_size: 8 * 1024,
+            max_size: 64 * 1024,
+        }
+    }
+}
+
+impl ChunkParams {
+    fn validate(&self) -> Result<(), HashError> {
+        if self.min_size == 0 || self.min_size > self.avg_size || self.avg_size > self.max_size {
+            return Err(HashError::InvalidChunkParams(format!(
+                "need 0 < min_size <= avg_size <= max_size, got {}/{}/{}",
+                self.min_size, self.avg_size, self.max_size
+            )));
+        }
+        Ok(())
+    }
+
+    /// Boundary mask over the hash's high bits, which depend on the most
+    /// bytes; a boundary is `hash & mask == 0`, about once per `avg_size`
+    fn mask(&self) -> u64 {
+        let bits = usize::BITS - 1 - self.avg_size.leading_zeros();
+        if bits == 0 {
+            0
+        } else {
+            !0u64 << (64 - bits)
+        }
+    }
+}
+
+/// A content-defined region of a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Chunk {
+    pub offset: u64,
+    pub length: usize,
+    /// BLAKE3 digest of the chunk's bytes
+    pub digest: [u8; 32],
+}
+
+/// Split `data` into content-defined chunks covering it exactly, in order
+pub fn chunk_bytes(data: &[u8], params: &ChunkParams) -> Result<Vec<Chunk>, HashError> {
+    params.validate()?;
+
+    let mask = params.mask();
+    let mut chunks = Vec::with_capacity(data.len() / params.avg_size + 1);
+    let mut start = 0;
+    while start < data.len() {
+        let length = next_boundary(&data[start..], params, mask);
+        let region = &data[start..start + length];
+        chunks.push(Chunk {
+            offset: start as u64,
+            length,
+            digest: *blake3::hash(region).as_bytes(),
+        });
+        start += length;
+    }
+    Ok(chunks)
+}
+
+/// Chunk everything `reader` yields, exactly as [`chunk_bytes`] would
+///
+/// A boundary only depends on the `max_size` bytes after the previous one,
+/// so at most two `max_size` windows are held in memory at a time.
+pub fn chunk_reader<R: Read>(mut reader: R, params: &ChunkParams) -> Result<Vec<Chunk>, HashError> {
+    params.validate()?;
+
+    let mask = params.mask();
+    let window = 2 * params.max_size;
+    let mut chunks = Vec::new();
+    let mut buffer = Vec::with_capacity(window);
+    let mut offset = 0u64;
+    let mut eof = false;
+    loop {
+        if !eof && buffer.len() < params.max_size {
+            let wanted = window - buffer.len();
+            let read = (&mut reader).take(wanted as u64).read_to_end(&mut buffer)?;
+            eof = read < wanted;
+        }
+        if buffer.is_empty() {
+            return Ok(chunks);
+        }
+
+        let length = next_boundary(&buffer, params, mask);
+        chunks.push(Chunk {
+            offset,
+            length,
+            digest: *blake3::hash(&buffer[..length]).as_bytes(),
+        });
+        offset += length as u64;
+        buffer.drain(..length);
+    }
+}
+
+/// Chunks of `new` whose content does not appear anywhere in `old`
+pub fn changed_chunks<'a>(old: &[Chunk], new: &'a [Chunk]) -> Vec<&'a Chunk> {
+    let known: HashSet<&[u8; 32]> = old.iter().map(|chunk| &chunk.digest).collect();
+    new.iter()
+        .filter(|chunk| !known.contains(&chunk.digest))
+        .collect()
+}
+
+/// Length of the chunk at the start of `data`
+fn next_boundary(data: &[u8], params: &ChunkParams, mask: u64) -> usize {
+    if data.len() <= params.min_size {
+        return data.len();
+    }
+
+    let end = data.len().min(params.max_size);
+    let mut hash = 0u64;
+    for (i, &byte) in data[..end].iter().enumerate().skip(params.min_size) {
+        hash = (hash << 1).wrapping_add(GEAR[byte as usize]);
+        if hash & mask == 0 {
+            return i + 1;
+        }
+    }
+    end
+}
+
+/// Random per-byte values for the Gear hash. Fixed so that chunk boundaries
+/// are stable across runs and machines.
+static GEAR: [u64; 256] = gear_table();
+
+const fn gear_table() -> [u64; 256] {
+    // splitmix64
+    let mut table = [0u64; 256];
+    let mut state = 0x5265_7472_6967_6765u64;
+    let mut i = 0;
+    while i < 256 {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pseudo_random(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chunks_cover_data_within_bounds() {
+        let data = pseudo_random(512 * 1024, 1);
+        let params = ChunkParams::default();
+        let chunks = chunk_bytes(&data, &params).unwrap();
+
+        let mut expected_offset = 0;
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.offset, expected_offset);
+            assert!(chunk.length <= params.max_size);
+            if i + 1 < chunks.len() {
+                assert!(chunk.length > params.min_size);
+            }
+            expected_offset += chunk.length as u64;
+        }
+        assert_eq!(expected_offset, data.len() as u64);
+
+        // Roughly one chunk per avg_size (min_size skips part of each window)
+        assert!(chunks.len() > 512 / 64 && chunks.len() < 512 / 2);
+    }
+
+    #[test]
+    fn test_reader_matches_in_memory_chunks() {
+        let data = pseudo_random(300 * 1024 + 17, 3);
+        let params = ChunkParams::default();
+
+        let streamed = chunk_reader(std::io::Cursor::new(&data), &params).unwrap();
+        assert_eq!(streamed, chunk_bytes(&data, &params).unwrap());
+        assert!(chunk_reader(std::io::empty(), &params).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_insertion_only_changes_nearby_chunks() {
+        let original = pseudo_random(256 * 1024, 2);
+        let mut edited = original.clone();
+        edited.splice(100_000..100_000, b"inserted bytes".iter().copied());
+
+        let params = ChunkParams::default();
+        let before = chunk_bytes(&original, &params).unwrap();
+        let after = chunk_bytes(&edited, &params).unwrap();
+
+        let changed = changed_chunks(&before, &after);
+        assert!(!changed.is_empty());
+        assert!(changed.len() <= 2, "{} chunks changed", changed.len());
+        assert!(changed
+            .iter()
+            .any(|chunk| chunk.offset <= 100_000 && 100_000 < chunk.offset + chunk.length as u64));
+    }
+
+    #[test]
+    fn test_invalid_params_are_rejected() {
+        let params = ChunkParams {
+            min_size: 4096,
+            avg_size: 1024,
+            max_size: 8192,
+        };
+        assert!(matches!(
+            chunk_bytes(b"data", &params),
+            Err(HashError::InvalidChunkParams(_))
+        ));
+        assert!(chunk_bytes(b"", &ChunkParams::default())
+            .unwrap()
+            .is_empty());
+    }
+}
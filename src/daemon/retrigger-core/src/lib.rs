@@ -5,10 +5,20 @@
 
 use serde::{Deserialize, Serialize};
 use std::ffi::CString;
-use std::path::Path;
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
 use std::ptr;
 use thiserror::Error;
 
+pub mod chunk;
+pub mod walk;
+
+pub use chunk::{changed_chunks, Chunk, ChunkParams};
+pub use walk::{
+    find_mount_points, find_watch_boundaries, walk_directory, walk_tree, BoundaryOptions,
+    DirectoryWalk, TreeWalk, WalkOptions, WatchBoundaries,
+};
+
 // Include generated C bindings
 #[allow(non_upper_case_globals)]
 #[allow(non_camel_case_types)]
@@ -27,14 +37,98 @@ pub enum HashError {
     ComputationFailed,
     #[error("Incremental hasher not initialized")]
     HasherNotInitialized,
+    #[error("Extendable output requires BLAKE3, but the strategy is {}", .0.as_str())]
+    XofUnsupported(HashStrategy),
+    #[error("Invalid chunk parameters: {0}")]
+    InvalidChunkParams(String),
+    #[error("Failed to read input: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 /// Result of a hash computation
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+///
+/// Equality compares `hash`, `size` and `is_incremental` only: `digest` is
+/// an optional rendering of the same hash, so a result without it (read back
+/// over IPC, say) equals the in-process one with it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HashResult {
     pub hash: u64,
     pub size: u32,
     pub is_incremental: bool,
+    /// Full 256-bit digest, present for BLAKE3 results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<[u8; 32]>,
+}
+
+impl PartialEq for HashResult {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+            && self.size == other.size
+            && self.is_incremental == other.is_incremental
+    }
+}
+
+impl HashResult {
+    /// Lowercase hex rendering for comparison with external tools
+    ///
+    /// Renders the full digest when available (64 chars, matching `b3sum`),
+    /// otherwise the `u64` hash as 16 zero-padded chars.
+    ///
+    /// The digest only exists in the process that computed it: the IPC wire
+    /// format carries the `u64` alone, so results read back from the ring
+    /// always render the 16-char form, which external tools cannot reproduce.
+    pub fn to_hex(&self) -> String {
+        match &self.digest {
+            Some(digest) => digest.iter().map(|b| format!("{b:02x}")).collect(),
+            None => format!("{:016x}", self.hash),
+        }
+    }
+
+    /// Algorithm that produced this result, `"blake3"` or `"xxh3"`
+    ///
+    /// Told apart by the digest, which every BLAKE3 result carries. Results
+    /// decoded from the IPC wire have none and always report `"xxh3"`.
+    pub fn algorithm(&self) -> &'static str {
+        if self.digest.is_some() {
+            "blake3"
+        } else {
+            "xxh3"
+        }
+    }
+
+    /// Classify how this result relates to `other`
+    ///
+    /// Full digests are authoritative when both results carry one, so a
+    /// `u64` match between different contents is reported as
+    /// [`HashComparison::TruncatedCollision`] rather than as identical.
+    /// Without both digests only the `u64` hash can be compared.
+    pub fn compare(&self, other: &HashResult) -> HashComparison {
+        if self.size != other.size {
+            return HashComparison::SizeDiffers;
+        }
+
+        match (&self.digest, &other.digest) {
+            (Some(a), Some(b)) if a == b => HashComparison::Identical,
+            (Some(_), Some(_)) if self.hash == other.hash => HashComparison::TruncatedCollision,
+            _ if self.hash == other.hash => HashComparison::Identical,
+            _ => HashComparison::Different,
+        }
+    }
+}
+
+/// Relationship between two hash results; see [`HashResult::compare`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HashComparison {
+    /// Same size and hash (and same full digest, when both have one)
+    Identical,
+    /// Sizes differ, so the contents do
+    SizeDiffers,
+    /// The `u64` hashes match but the full digests differ: comparing on the
+    /// `u64` alone would wrongly report these as equal
+    TruncatedCollision,
+    /// Same size, different hash
+    Different,
 }
 
 /// SIMD optimization levels available
@@ -48,7 +142,8 @@ pub enum SimdLevel {
 }
 
 /// Hash algorithm selection strategy
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum HashStrategy {
     /// Use BLAKE3 for all files (secure + fast for large files)
     Blake3Only,
@@ -60,6 +155,18 @@ pub enum HashStrategy {
     Auto,
 }
 
+impl HashStrategy {
+    /// Name as used in configuration files (`blake3_only`, `xxh3_only`, ...)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HashStrategy::Blake3Only => "blake3_only",
+            HashStrategy::Xxh3Only => "xxh3_only",
+            HashStrategy::Hybrid => "hybrid",
+            HashStrategy::Auto => "auto",
+        }
+    }
+}
+
 impl From<ffi::rtr_simd_level_t> for SimdLevel {
     fn from(level: ffi::rtr_simd_level_t) -> Self {
         match level {
@@ -78,6 +185,7 @@ impl From<ffi::rtr_hash_result_t> for HashResult {
             hash: result.hash,
             size: result.size,
             is_incremental: result.is_incremental,
+            digest: None,
         }
     }
 }
@@ -150,6 +258,7 @@ impl Blake3FastHash {
             hash: hash_u64,
             size: data.len() as u32,
             is_incremental: false,
+            digest: Some(*bytes),
         })
     }
 }
@@ -157,6 +266,9 @@ impl Blake3FastHash {
 /// SIMD-optimized file size threshold for algorithm selection
 const HYBRID_THRESHOLD: usize = 1024 * 1024; // 1MB
 
+/// Bytes read at a time when streaming input larger than `HYBRID_THRESHOLD`
+const STREAM_READ_SIZE: usize = 64 * 1024;
+
 unsafe impl Send for HashEngine {}
 unsafe impl Sync for HashEngine {}
 
@@ -199,6 +311,119 @@ impl HashEngine {
         let result = unsafe { ffi::rtr_benchmark_hash(test_size) };
         result.into()
     }
+
+    /// Hash every regular file under `root`
+    ///
+    /// `max_depth` bounds how deep the walk descends (root = 0, `None` for
+    /// unlimited); directories beyond it are reported in `skipped_deep_dirs`.
+    /// Symlink cycles are detected and never re-entered.
+    pub fn hash_directory<P: AsRef<Path>>(
+        &self,
+        root: P,
+        max_depth: Option<usize>,
+    ) -> Result<DirectoryHash, HashError> {
+        let root = root.as_ref();
+        let walk = walk_directory(root, &WalkOptions { max_depth })
+            .map_err(|_| HashError::InvalidPath(root.display().to_string()))?;
+
+        let mut result = DirectoryHash {
+            files: Vec::with_capacity(walk.files.len()),
+            failed: Vec::new(),
+            skipped_deep_dirs: walk.skipped_deep_dirs,
+        };
+
+        for path in walk.files {
+            match self.hash_file(&path) {
+                Ok(hash) => result.files.push((path, hash)),
+                Err(_) => result.failed.push(path),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Order-independent fingerprint of the content under `root`
+    ///
+    /// Each file contributes its BLAKE3 content digest and its path relative
+    /// to `root` with `/` separators, so the result is the same wherever the
+    /// tree lives and regardless of walk order or timestamps. Symlinks are
+    /// not followed: each contributes its target path, as written, instead
+    /// of the content it points at. Only entries whose relative path passes
+    /// `filter` are included, and any unreadable file or directory is an
+    /// error rather than silently left out. `size` is the entry count.
+    pub fn fingerprint_directory<P, F>(&self, root: P, filter: F) -> Result<HashResult, HashError>
+    where
+        P: AsRef<Path>,
+        F: Fn(&Path) -> bool,
+    {
+        let root = root.as_ref();
+        let walk = walk_tree(root)?;
+
+        let slash_separated = |path: &Path| {
+            path.components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+        };
+
+        // Tagged so a file and a symlink with the same bytes differ
+        const FILE: u8 = 0;
+        const SYMLINK: u8 = 1;
+
+        let mut entries = Vec::with_capacity(walk.files.len() + walk.symlinks.len());
+        let tagged = walk
+            .files
+            .into_iter()
+            .map(|path| (FILE, path))
+            .chain(walk.symlinks.into_iter().map(|path| (SYMLINK, path)));
+        for (kind, path) in tagged {
+            let relative = path
+                .strip_prefix(root)
+                .map_err(|_| HashError::InvalidPath(path.display().to_string()))?;
+            if !filter(relative) {
+                continue;
+            }
+
+            let digest = if kind == SYMLINK {
+                let target = std::fs::read_link(&path)?;
+                *blake3::hash(slash_separated(&target).as_bytes()).as_bytes()
+            } else {
+                self.hash_file_blake3(&path)?
+                    .digest
+                    .ok_or(HashError::ComputationFailed)?
+            };
+            entries.push((slash_separated(relative), kind, digest));
+        }
+        entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+        let mut hasher = blake3::Hasher::new();
+        for (relative, kind, digest) in &entries {
+            // Length prefix keeps path/digest boundaries unambiguous
+            hasher.update(&(relative.len() as u64).to_le_bytes());
+            hasher.update(relative.as_bytes());
+            hasher.update(&[*kind]);
+            hasher.update(digest);
+        }
+        let digest = *hasher.finalize().as_bytes();
+
+        Ok(HashResult {
+            hash: u64::from_le_bytes(digest[..8].try_into().unwrap()),
+            size: entries.len() as u32,
+            is_incremental: false,
+            digest: Some(digest),
+        })
+    }
+}
+
+/// Per-file hashes of a directory tree
+#[derive(Debug, Clone)]
+pub struct DirectoryHash {
+    /// Hashed files, sorted by path
+    pub files: Vec<(PathBuf, HashResult)>,
+    /// Files that could not be read
+    pub failed: Vec<PathBuf>,
+    /// Directories not entered because they exceed the depth limit
+    pub skipped_deep_dirs: Vec<PathBuf>,
 }
 
 impl Default for HashEngine {
@@ -209,7 +434,22 @@ impl Default for HashEngine {
 
 impl FastHash for HashEngine {
     fn hash_bytes(&self, data: &[u8]) -> Result<HashResult, HashError> {
-        match self.strategy {
+        self.hash_bytes_with_strategy(data, self.strategy)
+    }
+
+    fn hash_file<P: AsRef<Path>>(&self, path: P) -> Result<HashResult, HashError> {
+        self.hash_file_with_strategy(path, self.strategy)
+    }
+}
+
+impl HashEngine {
+    /// Hash bytes with `strategy` instead of the engine's own
+    pub fn hash_bytes_with_strategy(
+        &self,
+        data: &[u8],
+        strategy: HashStrategy,
+    ) -> Result<HashResult, HashError> {
+        match strategy {
             HashStrategy::Blake3Only => self.hash_bytes_blake3(data),
             HashStrategy::Xxh3Only => self.hash_bytes_xxh3(data),
             HashStrategy::Hybrid => {
@@ -227,12 +467,17 @@ impl FastHash for HashEngine {
         }
     }
 
-    fn hash_file<P: AsRef<Path>>(&self, path: P) -> Result<HashResult, HashError> {
+    /// Hash a file with `strategy` instead of the engine's own
+    pub fn hash_file_with_strategy<P: AsRef<Path>>(
+        &self,
+        path: P,
+        strategy: HashStrategy,
+    ) -> Result<HashResult, HashError> {
         // For files, we can check size before reading
         let metadata = std::fs::metadata(&path)
             .map_err(|_| HashError::InvalidPath(path.as_ref().display().to_string()))?;
 
-        match self.strategy {
+        match strategy {
             HashStrategy::Blake3Only => self.hash_file_blake3(&path),
             HashStrategy::Xxh3Only => self.hash_file_xxh3(&path),
             HashStrategy::Hybrid => {
@@ -245,9 +490,146 @@ impl FastHash for HashEngine {
             HashStrategy::Auto => self.hash_file_auto(&path, metadata.len()),
         }
     }
-}
 
-impl HashEngine {
+    /// Hash a file's contents that were already read into memory
+    ///
+    /// Picks the algorithm as [`Self::hash_file_with_strategy`] does for a
+    /// file of `data.len()` bytes (by size only, never entropy), so the
+    /// result matches hashing the file from disk.
+    pub fn hash_file_data_with_strategy(
+        &self,
+        data: &[u8],
+        strategy: HashStrategy,
+    ) -> Result<HashResult, HashError> {
+        match strategy {
+            HashStrategy::Blake3Only => self.hash_bytes_blake3(data),
+            HashStrategy::Xxh3Only => self.hash_bytes_xxh3(data),
+            HashStrategy::Hybrid | HashStrategy::Auto => {
+                if data.len() >= HYBRID_THRESHOLD {
+                    self.hash_bytes_blake3(data)
+                } else {
+                    self.hash_bytes_xxh3(data)
+                }
+            }
+        }
+    }
+
+    /// Hash everything `reader` yields, e.g. stdin, without buffering it all
+    ///
+    /// Input of up to 1MB is hashed exactly as [`FastHash::hash_bytes`] would
+    /// hash it. Anything longer is streamed: `Hybrid` and `Auto` pick BLAKE3
+    /// for it, as they would for the same bytes in memory, so the result is
+    /// still identical. `Xxh3Only` streams through the block-wise
+    /// [`IncrementalHasher`] instead, whose result has `is_incremental` set
+    /// and only matches other incremental hashes. `size` saturates at
+    /// `u32::MAX`.
+    pub fn hash_reader<R: Read>(&self, mut reader: R) -> Result<HashResult, HashError> {
+        // One byte past the threshold tells "exactly 1MB" from "longer"
+        let mut prefix = Vec::new();
+        (&mut reader)
+            .take(HYBRID_THRESHOLD as u64 + 1)
+            .read_to_end(&mut prefix)?;
+        if prefix.len() <= HYBRID_THRESHOLD {
+            return self.hash_bytes(&prefix);
+        }
+
+        if self.strategy == HashStrategy::Xxh3Only {
+            let mut hasher = IncrementalHasher::new(None)?;
+            hasher.update(&prefix)?;
+            let mut total = prefix.len() as u64;
+            drop(prefix);
+
+            let mut buffer = vec![0u8; STREAM_READ_SIZE];
+            loop {
+                let read = match reader.read(&mut buffer) {
+                    Ok(0) => break,
+                    Ok(read) => read,
+                    Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e.into()),
+                };
+                hasher.update(&buffer[..read])?;
+                total += read as u64;
+            }
+
+            let mut result = hasher.finalize()?;
+            result.size = u32::try_from(total).unwrap_or(u32::MAX);
+            return Ok(result);
+        }
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&prefix);
+        drop(prefix);
+        hasher.update_reader(reader)?;
+
+        let digest = *hasher.finalize().as_bytes();
+        Ok(HashResult {
+            hash: u64::from_le_bytes(digest[..8].try_into().unwrap()),
+            size: u32::try_from(hasher.count()).unwrap_or(u32::MAX),
+            is_incremental: false,
+            digest: Some(digest),
+        })
+    }
+
+    /// Split bytes into content-defined chunks, each with its BLAKE3 digest
+    ///
+    /// See [`chunk`] for how boundaries are chosen.
+    pub fn chunk_bytes(&self, data: &[u8], params: &ChunkParams) -> Result<Vec<Chunk>, HashError> {
+        chunk::chunk_bytes(data, params)
+    }
+
+    /// Split a file into content-defined chunks for delta sync
+    ///
+    /// After a change, [`changed_chunks`] between the old and new lists gives
+    /// the regions to transfer; an insertion only affects the chunks around
+    /// it, not everything after it. The file is read through a buffer rather
+    /// than loaded whole (see [`chunk::chunk_reader`]).
+    pub fn chunk_file<P: AsRef<Path>>(
+        &self,
+        path: P,
+        params: &ChunkParams,
+    ) -> Result<Vec<Chunk>, HashError> {
+        let file = std::fs::File::open(path)?;
+        chunk::chunk_reader(std::io::BufReader::new(file), params)
+    }
+
+    /// Hash bytes with BLAKE3 into `out_len` bytes of extendable output
+    ///
+    /// Meant for deriving several keys or IDs from one input, not for change
+    /// detection: the output is not a [`HashResult`] and is not comparable
+    /// with one. Its first 32 bytes are the regular BLAKE3 digest. Fails if
+    /// the engine uses [`HashStrategy::Xxh3Only`].
+    pub fn hash_bytes_xof(&self, data: &[u8], out_len: usize) -> Result<Vec<u8>, HashError> {
+        if self.strategy == HashStrategy::Xxh3Only {
+            return Err(HashError::XofUnsupported(self.strategy));
+        }
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(data);
+        let mut output = vec![0u8; out_len];
+        hasher.finalize_xof().fill(&mut output);
+        Ok(output)
+    }
+
+    /// Hash a file with BLAKE3 into `out_len` bytes of extendable output
+    ///
+    /// The file is streamed, so memory use does not grow with its size. See
+    /// [`HashEngine::hash_bytes_xof`].
+    pub fn hash_file_xof<P: AsRef<Path>>(
+        &self,
+        path: P,
+        out_len: usize,
+    ) -> Result<Vec<u8>, HashError> {
+        if self.strategy == HashStrategy::Xxh3Only {
+            return Err(HashError::XofUnsupported(self.strategy));
+        }
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update_reader(std::fs::File::open(path)?)?;
+        let mut output = vec![0u8; out_len];
+        hasher.finalize_xof().fill(&mut output);
+        Ok(output)
+    }
+
     /// Hash bytes using BLAKE3
     fn hash_bytes_blake3(&self, data: &[u8]) -> Result<HashResult, HashError> {
         let hash = blake3::hash(data);
@@ -260,6 +642,7 @@ impl HashEngine {
             hash: hash_u64,
             size: data.len() as u32,
             is_incremental: false,
+            digest: Some(*bytes),
         })
     }
 
@@ -660,6 +1043,250 @@ mod tests {
         println!("Estimated 100MB hash time: {estimated_100mb:?} (target: <1ms)");
     }
 
+    #[test]
+    fn test_to_hex() {
+        let blake3 = HashEngine::with_strategy(HashStrategy::Blake3Only)
+            .hash_bytes(b"hello")
+            .unwrap();
+        assert_eq!(blake3.to_hex(), blake3::hash(b"hello").to_hex().as_str());
+        assert_eq!(blake3.algorithm(), "blake3");
+
+        let truncated = HashResult {
+            hash: 0xBEEF,
+            size: 5,
+            is_incremental: false,
+            digest: None,
+        };
+        assert_eq!(truncated.to_hex(), "000000000000beef");
+        assert_eq!(truncated.algorithm(), "xxh3");
+
+        // The digest does not take part in equality
+        let without_digest = HashResult {
+            digest: None,
+            ..blake3.clone()
+        };
+        assert_eq!(blake3, without_digest);
+    }
+
+    #[test]
+    fn test_xof_output() {
+        let engine = HashEngine::new();
+        let derived = engine.hash_bytes_xof(b"hello", 96).unwrap();
+        assert_eq!(derived.len(), 96);
+        assert_eq!(&derived[..32], blake3::hash(b"hello").as_bytes());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("key.bin");
+        std::fs::write(&path, b"hello").unwrap();
+        assert_eq!(engine.hash_file_xof(&path, 96).unwrap(), derived);
+        assert!(matches!(
+            engine.hash_file_xof(dir.path().join("missing.bin"), 32),
+            Err(HashError::Io(_))
+        ));
+
+        let xxh3 = HashEngine::with_strategy(HashStrategy::Xxh3Only);
+        assert!(matches!(
+            xxh3.hash_bytes_xof(b"hello", 32),
+            Err(HashError::XofUnsupported(HashStrategy::Xxh3Only))
+        ));
+    }
+
+    #[test]
+    fn test_chunk_file() {
+        let engine = HashEngine::new();
+        let data: Vec<u8> = (0..200 * 1024).map(|i| (i * 31 % 251) as u8).collect();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.bin");
+        std::fs::write(&path, &data).unwrap();
+
+        let params = ChunkParams::default();
+        let chunks = engine.chunk_file(&path, &params).unwrap();
+        assert_eq!(chunks, engine.chunk_bytes(&data, &params).unwrap());
+
+        // The I/O error is kept rather than reported as a bad path
+        let missing = engine.chunk_file(dir.path().join("missing.bin"), &params);
+        assert!(matches!(
+            missing,
+            Err(HashError::Io(e)) if e.kind() == ErrorKind::NotFound
+        ));
+    }
+
+    #[test]
+    fn test_hash_reader_matches_in_memory_hash() {
+        let small = b"piped through stdin".to_vec();
+        let large: Vec<u8> = (0..3 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+
+        for strategy in [HashStrategy::Blake3Only, HashStrategy::Hybrid] {
+            let engine = HashEngine::with_strategy(strategy);
+            for data in [&small, &large] {
+                let streamed = engine.hash_reader(std::io::Cursor::new(data)).unwrap();
+                assert_eq!(streamed, engine.hash_bytes(data).unwrap());
+            }
+        }
+
+        let xxh3 = HashEngine::with_strategy(HashStrategy::Xxh3Only);
+        assert_eq!(
+            xxh3.hash_reader(small.as_slice()).unwrap(),
+            xxh3.hash_bytes(&small).unwrap()
+        );
+        let streamed = xxh3.hash_reader(large.as_slice()).unwrap();
+        assert!(streamed.is_incremental);
+        assert_eq!(streamed.size, large.len() as u32);
+    }
+
+    #[test]
+    fn test_hash_file_data_matches_file_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let small = dir.path().join("small.txt");
+        let large = dir.path().join("large.bin");
+        std::fs::write(&small, b"already read once").unwrap();
+        std::fs::write(&large, vec![7u8; HYBRID_THRESHOLD + 1]).unwrap();
+
+        let engine = HashEngine::new();
+        for strategy in [
+            HashStrategy::Blake3Only,
+            HashStrategy::Hybrid,
+            HashStrategy::Auto,
+        ] {
+            for path in [&small, &large] {
+                let data = std::fs::read(path).unwrap();
+                assert_eq!(
+                    engine.hash_file_data_with_strategy(&data, strategy).unwrap(),
+                    engine.hash_file_with_strategy(path, strategy).unwrap(),
+                    "{strategy:?} {}",
+                    path.display()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let engine = HashEngine::with_strategy(HashStrategy::Blake3Only);
+        let hello = engine.hash_bytes(b"hello").unwrap();
+        let jello = engine.hash_bytes(b"jello").unwrap();
+
+        assert_eq!(hello.compare(&hello.clone()), HashComparison::Identical);
+        assert_eq!(hello.compare(&jello), HashComparison::Different);
+        assert_eq!(
+            hello.compare(&engine.hash_bytes(b"hello!").unwrap()),
+            HashComparison::SizeDiffers
+        );
+
+        // Same low 64 bits, different full digest
+        let collision = HashResult {
+            hash: hello.hash,
+            ..jello.clone()
+        };
+        assert_eq!(
+            hello.compare(&collision),
+            HashComparison::TruncatedCollision
+        );
+
+        // Without a digest on both sides only the u64 can be compared
+        let truncated = HashResult {
+            digest: None,
+            ..hello.clone()
+        };
+        assert_eq!(truncated.compare(&hello), HashComparison::Identical);
+        assert_eq!(truncated.compare(&collision), HashComparison::Identical);
+    }
+
+    #[test]
+    fn test_hash_directory_max_depth() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("nested/deeper")).unwrap();
+        std::fs::write(dir.path().join("a.txt"), b"a").unwrap();
+        std::fs::write(dir.path().join("nested/b.txt"), b"b").unwrap();
+        std::fs::write(dir.path().join("nested/deeper/c.txt"), b"c").unwrap();
+
+        let engine = HashEngine::new();
+        let unlimited = engine.hash_directory(dir.path(), None).unwrap();
+        assert_eq!(unlimited.files.len(), 3);
+
+        let limited = engine.hash_directory(dir.path(), Some(1)).unwrap();
+        assert_eq!(limited.files.len(), 2);
+        assert_eq!(
+            limited.skipped_deep_dirs,
+            vec![dir.path().join("nested/deeper")]
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_directory_is_location_and_order_independent() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+
+        // Same tree, written in a different order and with different mtimes
+        std::fs::create_dir_all(first.path().join("src")).unwrap();
+        std::fs::write(first.path().join("src/lib.rs"), b"lib").unwrap();
+        std::fs::write(first.path().join("README.md"), b"readme").unwrap();
+
+        std::fs::write(second.path().join("README.md"), b"readme").unwrap();
+        std::fs::create_dir_all(second.path().join("src")).unwrap();
+        std::fs::write(second.path().join("src/lib.rs"), b"lib").unwrap();
+        let old = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1);
+        std::fs::File::options()
+            .write(true)
+            .open(second.path().join("README.md"))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        let engine = HashEngine::new();
+        let all = |_: &Path| true;
+        let a = engine.fingerprint_directory(first.path(), all).unwrap();
+        let b = engine.fingerprint_directory(second.path(), all).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.size, 2);
+
+        // Renaming a file changes the fingerprint even with identical content
+        std::fs::rename(
+            second.path().join("README.md"),
+            second.path().join("README.txt"),
+        )
+        .unwrap();
+        let renamed = engine.fingerprint_directory(second.path(), all).unwrap();
+        assert_ne!(a, renamed);
+
+        // Filtered-out files don't contribute
+        let sources_only = |p: &Path| p.starts_with("src");
+        let a = engine
+            .fingerprint_directory(first.path(), sources_only)
+            .unwrap();
+        let b = engine
+            .fingerprint_directory(second.path(), sources_only)
+            .unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.size, 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_fingerprint_directory_hashes_symlink_targets() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("lib.rs"), b"lib").unwrap();
+        std::fs::write(outside.path().join("data.bin"), b"one").unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("vendor")).unwrap();
+
+        let engine = HashEngine::new();
+        let all = |_: &Path| true;
+        let before = engine.fingerprint_directory(dir.path(), all).unwrap();
+        assert_eq!(before.size, 2);
+
+        // The linked directory is not entered
+        std::fs::write(outside.path().join("data.bin"), b"two").unwrap();
+        let after = engine.fingerprint_directory(dir.path(), all).unwrap();
+        assert_eq!(before, after);
+
+        // Retargeting the link changes the fingerprint
+        std::fs::remove_file(dir.path().join("vendor")).unwrap();
+        std::os::unix::fs::symlink("../vendor", dir.path().join("vendor")).unwrap();
+        let retargeted = engine.fingerprint_directory(dir.path(), all).unwrap();
+        assert_ne!(before, retargeted);
+    }
+
     #[test]
     fn test_incremental_hashing() {
         let mut hasher = IncrementalHasher::new(Some(1024)).unwrap();
@@ -0,0 +1,344 @@
+//! Bounded directory traversal shared by the tree-level hashing operations
+//!
+//! Symlinked directories are followed, but each physical directory is entered
+//! at most once, so symlink cycles terminate. An optional depth limit bounds
+//! the cost of pathologically deep trees.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Options controlling a directory walk
+#[derive(Debug, Clone, Default)]
+pub struct WalkOptions {
+    /// Deepest directory level to enter, with the root at depth 0.
+    /// `None` walks the whole tree.
+    pub max_depth: Option<usize>,
+}
+
+/// Result of a directory walk
+#[derive(Debug, Clone, Default)]
+pub struct DirectoryWalk {
+    /// Regular files found, sorted by path
+    pub files: Vec<PathBuf>,
+    /// Directories not entered because they exceed `max_depth`
+    pub skipped_deep_dirs: Vec<PathBuf>,
+    /// Directories not entered because they were already visited (symlink cycles)
+    pub skipped_cycles: Vec<PathBuf>,
+}
+
+/// Identity of a directory used for cycle detection
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum DirKey {
+    #[cfg(unix)]
+    Inode(u64, u64),
+    #[cfg(not(unix))]
+    Canonical(PathBuf),
+}
+
+fn dir_key(path: &Path, metadata: &std::fs::Metadata) -> Option<DirKey> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let _ = path;
+        Some(DirKey::Inode(metadata.dev(), metadata.ino()))
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        std::fs::canonicalize(path).ok().map(DirKey::Canonical)
+    }
+}
+
+/// Walk `root` collecting regular files, honoring `options`
+pub fn walk_directory(root: &Path, options: &WalkOptions) -> std::io::Result<DirectoryWalk> {
+    let root_metadata = std::fs::metadata(root)?;
+    if !root_metadata.is_dir() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Not a directory: {}", root.display()),
+        ));
+    }
+
+    let mut walk = DirectoryWalk::default();
+    let mut visited = HashSet::new();
+    if let Some(key) = dir_key(root, &root_metadata) {
+        visited.insert(key);
+    }
+
+    let mut stack = vec![(root.to_path_buf(), 0usize)];
+    while let Some((dir, depth)) = stack.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue, // Unreadable directories are skipped
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            // Follow symlinks; dangling links are ignored
+            let metadata = match std::fs::metadata(&path) {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
+
+            if metadata.is_file() {
+                walk.files.push(path);
+            } else if metadata.is_dir() {
+                if options.max_depth.is_some_and(|max| depth + 1 > max) {
+                    walk.skipped_deep_dirs.push(path);
+                    continue;
+                }
+
+                let first_visit = dir_key(&path, &metadata).is_none_or(|key| visited.insert(key));
+                if first_visit {
+                    stack.push((path, depth + 1));
+                } else {
+                    walk.skipped_cycles.push(path);
+                }
+            }
+        }
+    }
+
+    walk.files.sort();
+    walk.skipped_deep_dirs.sort();
+    walk.skipped_cycles.sort();
+    Ok(walk)
+}
+
+/// Result of [`walk_tree`]
+#[derive(Debug, Clone, Default)]
+pub struct TreeWalk {
+    /// Regular files found, sorted by path
+    pub files: Vec<PathBuf>,
+    /// Symlinks found, sorted by path; never followed
+    pub symlinks: Vec<PathBuf>,
+}
+
+/// Walk `root` collecting regular files and symlinks, failing on the first
+/// directory or entry that cannot be read
+///
+/// Unlike [`walk_directory`], symlinks are listed rather than followed, so
+/// the result depends only on the tree itself, and nothing is skipped
+/// silently. Other file types (sockets, FIFOs) are left out.
+pub fn walk_tree(root: &Path) -> std::io::Result<TreeWalk> {
+    let root_metadata = std::fs::metadata(root)?;
+    if !root_metadata.is_dir() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Not a directory: {}", root.display()),
+        ));
+    }
+
+    let with_path = |path: &Path, e: std::io::Error| {
+        std::io::Error::new(e.kind(), format!("{}: {e}", path.display()))
+    };
+
+    let mut walk = TreeWalk::default();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        for entry in std::fs::read_dir(&dir).map_err(|e| with_path(&dir, e))? {
+            let entry = entry.map_err(|e| with_path(&dir, e))?;
+            let path = entry.path();
+            let file_type = entry.file_type().map_err(|e| with_path(&path, e))?;
+
+            if file_type.is_symlink() {
+                walk.symlinks.push(path);
+            } else if file_type.is_dir() {
+                stack.push(path);
+            } else if file_type.is_file() {
+                walk.files.push(path);
+            }
+        }
+    }
+
+    walk.files.sort();
+    walk.symlinks.sort();
+    Ok(walk)
+}
+
+/// Options for [`find_watch_boundaries`]
+#[derive(Debug, Clone, Default)]
+pub struct BoundaryOptions {
+    /// Descend into directories on other devices instead of stopping there
+    pub cross_filesystem: bool,
+    /// Do not descend into directories with more entries than this; 0 is
+    /// unlimited
+    pub max_entries_per_dir: usize,
+}
+
+/// Directories a recursive watch of a tree stops at
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WatchBoundaries {
+    /// Mount points not descended into, sorted
+    pub mount_points: Vec<PathBuf>,
+    /// Directories with more than `max_entries_per_dir` entries, sorted.
+    /// They can still be watched themselves, but not their subdirectories.
+    pub large_dirs: Vec<PathBuf>,
+}
+
+/// Directories under `root` that are mount points, like `find -xdev` sees them
+///
+/// A directory on a different device (`st_dev`) than its parent is returned
+/// and not descended into, so nested mounts are not reported. Symlinks are
+/// not followed. Always empty on non-Unix platforms.
+pub fn find_mount_points(root: &Path) -> std::io::Result<Vec<PathBuf>> {
+    find_watch_boundaries(root, &BoundaryOptions::default()).map(|found| found.mount_points)
+}
+
+/// Mount points and oversized directories a recursive watch of `root` should
+/// not descend into
+///
+/// Mount points are found as in [`find_mount_points`] unless
+/// `cross_filesystem` is set. A directory (including `root`) with more than
+/// `max_entries_per_dir` entries is reported without reading the rest of it,
+/// so a huge cache directory costs at most that many entries to detect.
+pub fn find_watch_boundaries(
+    root: &Path,
+    options: &BoundaryOptions,
+) -> std::io::Result<WatchBoundaries> {
+    let root_metadata = std::fs::metadata(root)?;
+    let mut found = WatchBoundaries::default();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue, // Unreadable directories are skipped
+        };
+
+        let entries: Vec<_> = if options.max_entries_per_dir > 0 {
+            let limited: Vec<_> = entries.take(options.max_entries_per_dir + 1).collect();
+            if limited.len() > options.max_entries_per_dir {
+                found.large_dirs.push(dir);
+                continue;
+            }
+            limited
+        } else {
+            entries.collect()
+        };
+
+        for entry in entries.into_iter().flatten() {
+            let metadata = match entry.metadata() {
+                Ok(metadata) if metadata.is_dir() => metadata,
+                _ => continue,
+            };
+            if options.cross_filesystem || same_device(&root_metadata, &metadata) {
+                stack.push(entry.path());
+            } else {
+                found.mount_points.push(entry.path());
+            }
+        }
+    }
+
+    found.mount_points.sort();
+    found.large_dirs.sort();
+    Ok(found)
+}
+
+fn same_device(a: &std::fs::Metadata, b: &std::fs::Metadata) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        a.dev() == b.dev()
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = (a, b);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_tree(root: &Path) {
+        std::fs::create_dir_all(root.join("a/b/c")).unwrap();
+        std::fs::write(root.join("top.txt"), b"0").unwrap();
+        std::fs::write(root.join("a/one.txt"), b"1").unwrap();
+        std::fs::write(root.join("a/b/two.txt"), b"2").unwrap();
+        std::fs::write(root.join("a/b/c/three.txt"), b"3").unwrap();
+    }
+
+    #[test]
+    fn test_unlimited_depth() {
+        let dir = tempfile::tempdir().unwrap();
+        make_tree(dir.path());
+
+        let walk = walk_directory(dir.path(), &WalkOptions::default()).unwrap();
+        assert_eq!(walk.files.len(), 4);
+        assert!(walk.skipped_deep_dirs.is_empty());
+    }
+
+    #[test]
+    fn test_max_depth_records_skipped_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        make_tree(dir.path());
+
+        let options = WalkOptions { max_depth: Some(1) };
+        let walk = walk_directory(dir.path(), &options).unwrap();
+        assert_eq!(
+            walk.files,
+            vec![dir.path().join("a/one.txt"), dir.path().join("top.txt")]
+        );
+        assert_eq!(walk.skipped_deep_dirs, vec![dir.path().join("a/b")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_cycle_terminates() {
+        let dir = tempfile::tempdir().unwrap();
+        make_tree(dir.path());
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("a/b/loop")).unwrap();
+
+        let walk = walk_directory(dir.path(), &WalkOptions::default()).unwrap();
+        assert_eq!(walk.files.len(), 4);
+        assert_eq!(walk.skipped_cycles, vec![dir.path().join("a/b/loop")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_tree_lists_symlinks_without_following() {
+        let dir = tempfile::tempdir().unwrap();
+        make_tree(dir.path());
+        std::os::unix::fs::symlink(dir.path().join("a"), dir.path().join("link")).unwrap();
+        std::os::unix::fs::symlink("missing", dir.path().join("a/dangling")).unwrap();
+
+        let walk = walk_tree(dir.path()).unwrap();
+        assert_eq!(walk.files.len(), 4);
+        assert_eq!(
+            walk.symlinks,
+            vec![dir.path().join("a/dangling"), dir.path().join("link")]
+        );
+        assert!(walk_tree(&dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_find_mount_points() {
+        let dir = tempfile::tempdir().unwrap();
+        make_tree(dir.path());
+        assert!(find_mount_points(dir.path()).unwrap().is_empty());
+        assert!(find_mount_points(&dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_large_dirs_are_not_descended() {
+        let dir = tempfile::tempdir().unwrap();
+        make_tree(dir.path());
+        std::fs::create_dir(dir.path().join("a/b/nested")).unwrap();
+        for i in 0..5 {
+            std::fs::write(dir.path().join(format!("a/b/c/cache-{i}")), b"").unwrap();
+        }
+
+        let options = BoundaryOptions {
+            max_entries_per_dir: 4,
+            ..Default::default()
+        };
+        let found = find_watch_boundaries(dir.path(), &options).unwrap();
+        assert_eq!(found.large_dirs, vec![dir.path().join("a/b/c")]);
+        assert!(found.mount_points.is_empty());
+
+        let unlimited = find_watch_boundaries(dir.path(), &BoundaryOptions::default()).unwrap();
+        assert!(unlimited.large_dirs.is_empty());
+    }
+}
@@ -4,20 +4,65 @@
 //! Follows 2025 best practices: minimal surface area, maximum performance.
 
 // Removed unused PathBuf import
-use std::time::Duration;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock, RwLockReadGuard};
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use retrigger_system::EnhancedFileEvent;
 use tokio::time::timeout;
+use tracing::{info, warn};
 
 use crate::ipc::{RingStats, ZeroCopyConfig, ZeroCopyRing};
 
+/// Bounded retry settings for reconnecting after a daemon restart
+#[derive(Debug, Clone)]
+pub struct ReconnectPolicy {
+    /// Connection attempts before giving up
+    pub max_attempts: u32,
+    /// Delay between attempts of an explicit `reconnect()`
+    pub retry_delay: Duration,
+    /// Minimum time between producer liveness checks while the ring is empty
+    pub check_interval: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 20,
+            retry_delay: Duration::from_millis(100),
+            check_interval: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Reported to the owner after the consumer remapped a new ring
+///
+/// Events published while disconnected are lost, so owners typically
+/// rescan their watched trees when they receive this.
+#[allow(dead_code)] // Read by the consumer's owner
+#[derive(Debug, Clone)]
+pub struct ReconnectEvent {
+    /// Reconnects so far, including this one
+    pub reconnect_count: u64,
+    /// Events left unread in the abandoned ring
+    pub unread_events: usize,
+}
+
+type ReconnectCallback = Box<dyn Fn(&ReconnectEvent) + Send + Sync>;
+
 /// High-level Zero-Copy Event Consumer (2025 API Design)
 /// Follows Single Responsibility: only consumes events
 pub struct ZeroCopyConsumer {
-    ring: ZeroCopyRing,
+    ring: RwLock<ZeroCopyRing>,
     #[allow(dead_code)]
     config: ZeroCopyConfig,
+    policy: ReconnectPolicy,
+    auto_reconnect: bool,
+    on_reconnect: Option<ReconnectCallback>,
+    reconnect_count: AtomicU64,
+    failed_attempts: AtomicU32,
+    last_check: Mutex<Instant>,
 }
 
 impl ZeroCopyConsumer {
@@ -29,12 +74,28 @@ impl ZeroCopyConsumer {
     /// Connect with custom configuration
     pub fn connect_with_config(config: ZeroCopyConfig) -> Result<Self> {
         let ring = ZeroCopyRing::create_consumer(config.clone())?;
-        Ok(Self { ring, config })
+        Ok(Self {
+            ring: RwLock::new(ring),
+            config,
+            policy: ReconnectPolicy::default(),
+            auto_reconnect: false,
+            on_reconnect: None,
+            reconnect_count: AtomicU64::new(0),
+            failed_attempts: AtomicU32::new(0),
+            last_check: Mutex::new(Instant::now()),
+        })
     }
 
     /// Get next event (non-blocking)
     pub fn try_recv(&self) -> Option<EnhancedFileEvent> {
-        self.ring.pop()
+        if let Some(event) = self.ring().pop() {
+            return Some(event);
+        }
+
+        if self.auto_reconnect && self.poll_reconnect() {
+            return self.ring().pop();
+        }
+        None
     }
 
     /// Get next event with timeout
@@ -45,7 +106,7 @@ impl ZeroCopyConsumer {
         let result = timeout(timeout_duration, async {
             // Simple polling approach - could be enhanced with proper async notifications
             loop {
-                if let Some(event) = self.ring.pop() {
+                if let Some(event) = self.try_recv() {
                     return Some(event);
                 }
                 tokio::time::sleep(Duration::from_micros(100)).await; // 0.1ms polling
@@ -61,7 +122,7 @@ impl ZeroCopyConsumer {
 
     /// Get buffer utilization statistics
     pub fn stats(&self) -> RingStats {
-        self.ring.stats()
+        self.ring().stats()
     }
 
     /// Check if more events are available
@@ -70,6 +131,131 @@ impl ZeroCopyConsumer {
     }
 }
 
+#[allow(dead_code)] // Library API; the daemon binary is the producer
+impl ZeroCopyConsumer {
+    /// Transparently remap the daemon's ring when it restarts
+    ///
+    /// While the ring is empty, `try_recv` checks at most every
+    /// `check_interval` whether the producer went away, and if so maps the new
+    /// ring and resumes from its start. After `max_attempts` consecutive
+    /// failures it stops trying until `reconnect()` is called.
+    pub fn with_auto_reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.policy = policy;
+        self.auto_reconnect = true;
+        self
+    }
+
+    /// Call `callback` after every reconnect so the owner can rescan
+    pub fn on_reconnect(
+        mut self,
+        callback: impl Fn(&ReconnectEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_reconnect = Some(Box::new(callback));
+        self
+    }
+
+    /// Remap the daemon's ring, retrying within the reconnect policy's bounds
+    pub fn reconnect(&self) -> Result<()> {
+        let attempts = self.policy.max_attempts.max(1);
+        let mut last_error = None;
+
+        for attempt in 0..attempts {
+            if attempt > 0 {
+                std::thread::sleep(self.policy.retry_delay);
+            }
+            match self.swap_ring() {
+                Ok(()) => return Ok(()),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        let error = last_error.unwrap_or_else(|| anyhow::anyhow!("No connection attempts made"));
+        Err(error.context(format!("Failed to reconnect after {attempts} attempts")))
+    }
+
+    /// Number of times this consumer has remapped a new ring
+    pub fn reconnect_count(&self) -> u64 {
+        self.reconnect_count.load(Ordering::Relaxed)
+    }
+
+    /// Whether the producer this consumer is mapped to is still alive
+    pub fn is_connected(&self) -> bool {
+        !self.ring().producer_gone()
+    }
+
+    fn ring(&self) -> RwLockReadGuard<'_, ZeroCopyRing> {
+        self.ring.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Rate-limited check run by `try_recv`; true if a new ring was mapped
+    fn poll_reconnect(&self) -> bool {
+        {
+            let mut last_check = self.last_check.lock().unwrap_or_else(|e| e.into_inner());
+            if last_check.elapsed() < self.policy.check_interval {
+                return false;
+            }
+            *last_check = Instant::now();
+        }
+
+        if !self.ring().producer_gone() {
+            self.failed_attempts.store(0, Ordering::Relaxed);
+            return false;
+        }
+        if self.failed_attempts.load(Ordering::Relaxed) >= self.policy.max_attempts {
+            return false;
+        }
+
+        match self.swap_ring() {
+            Ok(()) => true,
+            Err(e) => {
+                let failed = self.failed_attempts.fetch_add(1, Ordering::Relaxed) + 1;
+                if failed == self.policy.max_attempts {
+                    warn!("Giving up reconnecting to IPC ring after {failed} attempts: {e}");
+                }
+                false
+            }
+        }
+    }
+
+    /// Map the current ring in place of the old one and notify the owner
+    fn swap_ring(&self) -> Result<()> {
+        let new_ring = ZeroCopyRing::try_create_consumer(self.config.clone())?;
+        if new_ring.producer_gone() {
+            return Err(anyhow::anyhow!(
+                "No live producer for {}",
+                self.config.shared_path.display()
+            ));
+        }
+
+        let unread_events = {
+            let mut ring = self.ring.write().unwrap_or_else(|e| e.into_inner());
+            let unread_events = ring.stats().used;
+            drop(std::mem::replace(&mut *ring, new_ring));
+            // The old mapping may share the new ring's file, in which case
+            // dropping it cleared our consumer registration
+            if let Err(e) = ring.register_consumer() {
+                warn!("Reconnected without a consumer registration: {e:#}");
+            }
+            unread_events
+        };
+
+        self.failed_attempts.store(0, Ordering::Relaxed);
+        let reconnect_count = self.reconnect_count.fetch_add(1, Ordering::Relaxed) + 1;
+        info!(
+            "Reconnected to IPC ring (reconnect #{}, {} unread events lost)",
+            reconnect_count, unread_events
+        );
+
+        if let Some(callback) = &self.on_reconnect {
+            callback(&ReconnectEvent {
+                reconnect_count,
+                unread_events,
+            });
+        }
+        Ok(())
+    }
+}
+
 /// Event iterator for efficient batch processing
 pub struct EventIterator<'a> {
     consumer: &'a ZeroCopyConsumer,
@@ -186,6 +372,9 @@ pub struct SystemStats {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use retrigger_system::{SystemEvent, SystemEventType};
+    use std::path::PathBuf;
+    use std::sync::Arc;
     use tempfile::NamedTempFile;
 
     #[tokio::test]
@@ -211,6 +400,92 @@ mod tests {
         println!("Consumer connection result: {:?}", result.is_ok());
     }
 
+    fn test_event(path: &str) -> EnhancedFileEvent {
+        EnhancedFileEvent {
+            system_event: SystemEvent {
+                path: PathBuf::from(path),
+                event_type: SystemEventType::Modified,
+                timestamp: 0,
+                size: 0,
+                is_directory: false,
+                metadata: None,
+            },
+            hash: None,
+            processing_time_ns: 0,
+            metadata: None,
+            events_processed: None,
+        }
+    }
+
+    #[test]
+    fn test_auto_reconnect_after_producer_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = ZeroCopyConfig {
+            memory_size: 1024 * 1024,
+            ring_capacity: 100,
+            shared_path: dir.path().join("ring.mmap"),
+            consumer_timeout_ms: 100,
+            enable_notifications: false,
+        };
+
+        let reconnects = Arc::new(AtomicU64::new(0));
+        let seen = Arc::clone(&reconnects);
+
+        let producer = ZeroCopyRing::create_producer(config.clone()).unwrap();
+        let consumer = ZeroCopyConsumer::connect_with_config(config.clone())
+            .unwrap()
+            .with_auto_reconnect(ReconnectPolicy {
+                check_interval: Duration::ZERO,
+                ..Default::default()
+            })
+            .on_reconnect(move |event| seen.store(event.reconnect_count, Ordering::SeqCst));
+
+        assert!(producer.push(&test_event("/before")));
+        assert!(consumer.try_recv().is_some());
+        assert!(consumer.is_connected());
+
+        // Restart the daemon: the old file is removed and a new ring created
+        drop(producer);
+        assert!(!consumer.is_connected());
+        let producer = ZeroCopyRing::create_producer(config).unwrap();
+        assert!(producer.push(&test_event("/after")));
+
+        let event = consumer.try_recv().unwrap();
+        assert_eq!(event.system_event.path, PathBuf::from("/after"));
+        assert_eq!(consumer.reconnect_count(), 1);
+        assert_eq!(reconnects.load(Ordering::SeqCst), 1);
+        assert!(consumer.is_connected());
+        assert!(producer.has_consumer());
+    }
+
+    #[test]
+    fn test_reconnect_is_bounded_without_producer() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = ZeroCopyConfig {
+            memory_size: 1024 * 1024,
+            ring_capacity: 100,
+            shared_path: dir.path().join("ring.mmap"),
+            consumer_timeout_ms: 100,
+            enable_notifications: false,
+        };
+
+        let producer = ZeroCopyRing::create_producer(config.clone()).unwrap();
+        let consumer = ZeroCopyConsumer::connect_with_config(config)
+            .unwrap()
+            .with_auto_reconnect(ReconnectPolicy {
+                max_attempts: 3,
+                retry_delay: Duration::from_millis(1),
+                check_interval: Duration::ZERO,
+            });
+        drop(producer);
+
+        assert!(consumer.reconnect().is_err());
+        for _ in 0..5 {
+            assert!(consumer.try_recv().is_none());
+        }
+        assert_eq!(consumer.reconnect_count(), 0);
+    }
+
     #[test]
     fn test_api_connectivity_check() {
         // This will likely fail without a running daemon, which is expected
@@ -6,14 +6,24 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{Context, Result};
-use globset::{Glob, GlobSet, GlobSetBuilder};
+use retrigger_core::HashStrategy;
+use retrigger_system::{
+    EnhancedFileEvent, EventNormalization, PathPatterns, PatternPrecedence, RegistrationThrottle,
+    SymlinkHashMode, SystemEvent, SystemWatcher, WatchSettings, CACHE_ENTRY_SIZE_ESTIMATE,
+    SERIALIZED_EVENT_SIZE,
+};
 use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
 use tokio::sync::{broadcast, RwLock};
 use tracing::{debug, info, warn};
 
 /// Main daemon configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DaemonConfig {
+    /// Directory of `*.toml` fragments merged over this file in lexical
+    /// order, relative to the config file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include: Option<PathBuf>,
     pub server: ServerConfig,
     pub watcher: WatcherConfig,
     pub performance: PerformanceConfig,
@@ -21,6 +31,15 @@ pub struct DaemonConfig {
     pub patterns: PatternConfig,
 }
 
+impl DaemonConfig {
+    /// The `include` directory resolved against the config file's location
+    pub fn include_dir(&self, config_path: &Path) -> Option<PathBuf> {
+        let include = self.include.as_ref()?;
+        let base = config_path.parent().unwrap_or_else(|| Path::new(""));
+        Some(base.join(include))
+    }
+}
+
 /// Server configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
@@ -36,6 +55,15 @@ pub struct ServerConfig {
     pub enable_metrics: bool,
     /// Metrics port
     pub metrics_port: u16,
+    /// Exit when no gRPC stream or IPC consumer has been connected for this
+    /// many seconds (unset = run until stopped)
+    #[serde(default)]
+    pub idle_timeout_secs: Option<u64>,
+    /// Emit a `Heartbeat` event after this many milliseconds without any
+    /// other event, so consumers can tell a quiet tree from a dead daemon
+    /// (unset = no heartbeats)
+    #[serde(default)]
+    pub heartbeat_interval_ms: Option<u64>,
 }
 
 /// File watcher configuration
@@ -51,6 +79,79 @@ pub struct WatcherConfig {
     pub hash_cache_ttl_secs: u64,
     /// Block size for incremental hashing
     pub hash_block_size: u32,
+    /// Capture inode/device and permissions for each event so renames and
+    /// hardlinks keep their cached hash and `chmod`/`chown` report a
+    /// `permission_delta` (one extra `stat` per event)
+    #[serde(default)]
+    pub track_file_identity: bool,
+    /// Map platform-specific event sequences to canonical types (`canonical`)
+    /// or deliver them as reported (`raw`). Canonical stats every created
+    /// path, so `raw` is cheaper on trees that create many files.
+    #[serde(default)]
+    pub event_normalization: EventNormalization,
+    /// Hash symlinks by their target's content (`follow_target`) or by the
+    /// target path itself (`hash_link_path`) to detect repointed links
+    #[serde(default)]
+    pub symlink_hash_mode: SymlinkHashMode,
+    /// Emit a `stabilized_modified` event once a file has gone this many
+    /// milliseconds without changing; 0 disables
+    #[serde(default)]
+    pub stability_window_ms: u64,
+    /// Hold `created` events this many milliseconds and drop them, along
+    /// with the `deleted`, if the file is gone again by then (lock and
+    /// scratch files); 0 disables
+    #[serde(default)]
+    pub transient_window_ms: u64,
+    /// Watch registrations made back to back at startup before pausing
+    #[serde(default = "default_registration_batch_size")]
+    pub registration_batch_size: usize,
+    /// Pause between registration batches, so startup on a large tree ramps
+    /// the watch count up instead of spiking it
+    #[serde(default)]
+    pub registration_batch_delay_ms: u64,
+    /// Watch registrations in progress at once at startup
+    #[serde(default = "default_registration_concurrency")]
+    pub registration_concurrency: usize,
+    /// Recursive watches skip the subdirectories of any directory with more
+    /// entries than this, so a huge cache directory cannot stall startup;
+    /// 0 is unlimited
+    #[serde(default = "default_max_entries_per_dir")]
+    pub max_entries_per_dir: usize,
+    /// Paths remembered for debouncing before the oldest are forgotten, so
+    /// a churning tree cannot grow the table without bound; 0 is unlimited
+    #[serde(default = "default_max_debounce_entries")]
+    pub max_debounce_entries: usize,
+}
+
+fn default_registration_batch_size() -> usize {
+    RegistrationThrottle::default().batch_size
+}
+
+fn default_registration_concurrency() -> usize {
+    RegistrationThrottle::default().max_concurrent
+}
+
+fn default_max_entries_per_dir() -> usize {
+    100_000
+}
+
+fn default_max_debounce_entries() -> usize {
+    100_000
+}
+
+fn default_max_hash_size() -> Option<u64> {
+    Some(100 * 1024 * 1024) // 100MB
+}
+
+impl WatcherConfig {
+    /// Pacing for registering `watch_paths`
+    pub fn registration_throttle(&self) -> RegistrationThrottle {
+        RegistrationThrottle {
+            batch_size: self.registration_batch_size,
+            batch_delay: Duration::from_millis(self.registration_batch_delay_ms),
+            max_concurrent: self.registration_concurrency,
+        }
+    }
 }
 
 /// Watch path configuration
@@ -59,6 +160,25 @@ pub struct WatchPath {
     pub path: PathBuf,
     pub recursive: bool,
     pub enabled: bool,
+    /// Hash algorithm for files under this path (`blake3_only`, `xxh3_only`,
+    /// `hybrid` or `auto`); the engine default when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash_strategy: Option<HashStrategy>,
+    /// Descend into mounted filesystems below a recursive watch (Unix only;
+    /// Windows always does)
+    #[serde(default)]
+    pub cross_filesystem: bool,
+}
+
+impl WatchPath {
+    /// Watcher settings for this path
+    pub fn watch_settings(&self) -> WatchSettings {
+        WatchSettings {
+            hash_strategy: self.hash_strategy,
+            cross_filesystem: self.cross_filesystem,
+            ..WatchSettings::new(self.recursive)
+        }
+    }
 }
 
 /// Performance tuning configuration
@@ -74,6 +194,37 @@ pub struct PerformanceConfig {
     pub poll_interval_us: u64,
     /// Enable zero-copy optimizations
     pub enable_zero_copy: bool,
+    /// Total memory for the event channels, IPC ring and hash cache together;
+    /// each is sized proportionally to fit (unset = size them independently)
+    #[serde(default)]
+    pub memory_budget_bytes: Option<usize>,
+    /// Throttle hashing right after startup, while the cache is cold
+    /// (unset = hash at full speed from the start)
+    #[serde(default)]
+    pub startup_ramp: Option<StartupRampConfig>,
+    /// Hold processed events this long and release them in timestamp order,
+    /// trading latency for chronological delivery (unset = deliver each
+    /// event as soon as it is hashed)
+    #[serde(default)]
+    pub reorder_window_ms: Option<u64>,
+}
+
+/// Startup hashing ramp
+///
+/// Right after startup every first event for a file misses the cache and
+/// triggers a full hash. During the ramp hashes are let through at a rate
+/// starting at `initial_hashes_per_sec` and rising linearly to full speed as
+/// the ramp progresses, so the daemon does not compete for CPU with the
+/// watched application while it boots. Events over that rate are still
+/// delivered, without a hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StartupRampConfig {
+    /// The ramp ends this long after startup...
+    pub duration_secs: u64,
+    /// ...or after this many hashed events, whichever comes first
+    pub events: u64,
+    /// Hashing rate at the start of the ramp
+    pub initial_hashes_per_sec: u32,
 }
 
 /// Logging configuration
@@ -96,10 +247,19 @@ pub struct PatternConfig {
     pub include: Vec<String>,
     /// Patterns to exclude (glob format)  
     pub exclude: Vec<String>,
-    /// File size limits
-    pub max_file_size: u64,
+    /// Files larger than this are reported without a hash. Also read from
+    /// the deprecated `max_file_size` key, which used to drop the events of
+    /// larger files; use `max_event_size` for that now.
+    #[serde(default = "default_max_hash_size", alias = "max_file_size")]
+    pub max_hash_size: Option<u64>,
+    /// Events for files larger than this are dropped entirely
+    #[serde(default)]
+    pub max_event_size: Option<u64>,
     /// Binary file detection
     pub ignore_binary: bool,
+    /// How a path matched by both an include and an exclude is decided
+    #[serde(default)]
+    pub precedence: PatternPrecedence,
 }
 
 
@@ -112,6 +272,8 @@ impl Default for ServerConfig {
             request_timeout_ms: 30000,
             enable_metrics: true,
             metrics_port: 9091,
+            idle_timeout_secs: None,
+            heartbeat_interval_ms: None,
         }
     }
 }
@@ -124,6 +286,16 @@ impl Default for WatcherConfig {
             hash_cache_size: 100000,
             hash_cache_ttl_secs: 3600,
             hash_block_size: 4096,
+            track_file_identity: false,
+            event_normalization: EventNormalization::Canonical,
+            symlink_hash_mode: SymlinkHashMode::FollowTarget,
+            stability_window_ms: 0,
+            transient_window_ms: 0,
+            registration_batch_size: default_registration_batch_size(),
+            registration_batch_delay_ms: 0,
+            registration_concurrency: default_registration_concurrency(),
+            max_entries_per_dir: default_max_entries_per_dir(),
+            max_debounce_entries: default_max_debounce_entries(),
         }
     }
 }
@@ -136,6 +308,9 @@ impl Default for PerformanceConfig {
             event_batch_size: 100,
             poll_interval_us: 1000,
             enable_zero_copy: true,
+            memory_budget_bytes: None,
+            startup_ramp: None,
+            reorder_window_ms: None,
         }
     }
 }
@@ -162,55 +337,119 @@ impl Default for PatternConfig {
                 "**/*.log".to_string(),
                 "**/.*".to_string(),
             ],
-            max_file_size: 100 * 1024 * 1024, // 100MB
+            max_hash_size: default_max_hash_size(),
+            max_event_size: None,
             ignore_binary: true,
+            precedence: PatternPrecedence::default(),
         }
     }
 }
 
-/// Compiled pattern matcher for performance
-#[derive(Debug, Clone)]
-pub struct CompiledPatterns {
-    include: GlobSet,
-    exclude: GlobSet,
+/// Heap bytes assumed for an event's path
+const PATH_SIZE_ESTIMATE: usize = 128;
+/// Per-slot bookkeeping of a broadcast channel
+const BROADCAST_SLOT_OVERHEAD: usize = 4 * std::mem::size_of::<usize>();
+
+const MIN_CHANNEL_CAPACITY: usize = 64;
+const MIN_RING_CAPACITY: usize = 64;
+const MIN_CACHE_ENTRIES: usize = 256;
+
+/// Buffer sizes derived from `performance.memory_budget_bytes`
+///
+/// Each buffer first gets its minimal size; the rest of the budget is split
+/// 1/8 to each event channel, 1/2 to the IPC ring and 1/4 to the hash cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferBudget {
+    /// Capacity of the watcher's raw event channel
+    pub system_channel_capacity: usize,
+    /// Capacity of the enhanced (hashed) event channel
+    pub enhanced_channel_capacity: usize,
+    /// Number of IPC ring slots
+    pub ipc_ring_capacity: usize,
+    /// Size of the IPC shared memory file
+    pub ipc_memory_size: usize,
+    /// Approximate hash cache limit
+    pub cache_max_bytes: usize,
 }
 
-impl CompiledPatterns {
-    pub fn new(config: &PatternConfig) -> Result<Self> {
-        let mut include_builder = GlobSetBuilder::new();
-        for pattern in &config.include {
-            let glob = Glob::new(pattern)
-                .with_context(|| format!("Invalid include pattern: {pattern}"))?;
-            include_builder.add(glob);
-        }
+impl BufferBudget {
+    /// Approximate bytes per buffered raw event
+    pub(crate) fn system_slot_bytes() -> usize {
+        std::mem::size_of::<SystemEvent>() + PATH_SIZE_ESTIMATE + BROADCAST_SLOT_OVERHEAD
+    }
 
-        let mut exclude_builder = GlobSetBuilder::new();
-        for pattern in &config.exclude {
-            let glob = Glob::new(pattern)
-                .with_context(|| format!("Invalid exclude pattern: {pattern}"))?;
-            exclude_builder.add(glob);
+    /// Approximate bytes per buffered enhanced event
+    pub(crate) fn enhanced_slot_bytes() -> usize {
+        std::mem::size_of::<EnhancedFileEvent>() + PATH_SIZE_ESTIMATE + BROADCAST_SLOT_OVERHEAD
+    }
+
+    fn ring_header_bytes() -> usize {
+        std::mem::size_of::<crate::ipc::RingHeader>()
+    }
+
+    /// Smallest budget that holds minimal buffers
+    pub fn minimum_bytes() -> usize {
+        MIN_CHANNEL_CAPACITY * (Self::system_slot_bytes() + Self::enhanced_slot_bytes())
+            + Self::ring_header_bytes()
+            + MIN_RING_CAPACITY * SERIALIZED_EVENT_SIZE
+            + MIN_CACHE_ENTRIES * CACHE_ENTRY_SIZE_ESTIMATE
+    }
+
+    /// Split `budget_bytes` across the daemon's buffers
+    pub fn from_budget(budget_bytes: usize) -> Result<Self> {
+        let minimum = Self::minimum_bytes();
+        if budget_bytes < minimum {
+            anyhow::bail!(
+                "memory_budget_bytes = {budget_bytes} is too small; at least {minimum} bytes are required for minimal buffers"
+            );
         }
 
+        let spare = budget_bytes - minimum;
+        // Broadcast channels round their capacity up to a power of two, so
+        // round down here to stay within the budget
+        let channel_capacity = |slot_bytes: usize| {
+            let capacity = MIN_CHANNEL_CAPACITY + spare / 8 / slot_bytes;
+            1usize << capacity.ilog2()
+        };
+        let ipc_ring_capacity =
+            (MIN_RING_CAPACITY + spare / 2 / SERIALIZED_EVENT_SIZE).min(u32::MAX as usize);
+
         Ok(Self {
-            include: include_builder.build()?,
-            exclude: exclude_builder.build()?,
+            system_channel_capacity: channel_capacity(Self::system_slot_bytes()),
+            enhanced_channel_capacity: channel_capacity(Self::enhanced_slot_bytes()),
+            ipc_ring_capacity,
+            ipc_memory_size: Self::ring_header_bytes() + ipc_ring_capacity * SERIALIZED_EVENT_SIZE,
+            cache_max_bytes: MIN_CACHE_ENTRIES * CACHE_ENTRY_SIZE_ESTIMATE + spare / 4,
         })
     }
+}
+
+/// Compiled pattern matcher for performance
+///
+/// Matches like the watcher's own filter: `*` stays within one path
+/// component, and an empty include list admits everything not excluded.
+#[derive(Debug, Clone)]
+pub struct CompiledPatterns {
+    patterns: PathPatterns,
+}
+
+impl CompiledPatterns {
+    pub fn new(config: &PatternConfig) -> Result<Self> {
+        let patterns =
+            PathPatterns::with_precedence(&config.include, &config.exclude, config.precedence)?;
+        Ok(Self { patterns })
+    }
 
     /// Check if a file should be watched based on patterns
     pub fn should_watch(&self, path: &Path) -> bool {
-        let path_str = path.to_string_lossy();
-
-        // Check include patterns first
-        if !self.include.is_match(&*path_str) {
-            return false;
-        }
-
-        // Check exclude patterns
-        !self.exclude.is_match(&*path_str)
+        self.patterns.admits(path)
     }
 }
 
+/// How long to wait for an editor's burst of writes to settle before
+/// reloading a changed fragment
+const INCLUDE_RELOAD_SETTLE: Duration = Duration::from_millis(100);
+
 /// Configuration manager with hot-reload capability
 pub struct ConfigManager {
     config: Arc<RwLock<DaemonConfig>>,
@@ -239,12 +478,7 @@ impl ConfigManager {
     /// Load configuration from file
     pub async fn load_from_file<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
         let path = path.as_ref();
-        let config_str = tokio::fs::read_to_string(path)
-            .await
-            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
-
-        let new_config: DaemonConfig =
-            toml::from_str(&config_str).with_context(|| "Failed to parse config file")?;
+        let new_config = Self::read_config(path).await?;
 
         // Compile patterns
         let patterns = CompiledPatterns::new(&new_config.patterns)?;
@@ -354,14 +588,143 @@ impl ConfigManager {
         Ok(())
     }
 
+    /// Reload whenever a fragment in the `include` directory changes
+    ///
+    /// The directory is watched with a dedicated [`SystemWatcher`], so edits
+    /// are picked up as soon as the native backend reports them instead of on
+    /// the next poll of the main file. Does nothing if no `include` directory
+    /// is configured.
+    pub async fn watch_include_dir(&self) -> Result<()> {
+        let config_path = self
+            .config_path
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("No config file loaded"))?;
+        let Some(include_dir) = self.config.read().await.include_dir(&config_path) else {
+            return Ok(());
+        };
+
+        let mut watcher = SystemWatcher::new()?;
+        watcher.update_event_filter(vec!["**/*.toml".to_string()], Vec::new())?;
+        watcher
+            .watch_directory(&include_dir, false)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to watch config directory: {}",
+                    include_dir.display()
+                )
+            })?;
+        watcher.start().await?;
+
+        let mut events = watcher.subscribe();
+        let config = Arc::clone(&self.config);
+        let patterns = Arc::clone(&self.patterns);
+        let change_sender = self.change_sender.clone();
+
+        tokio::spawn(async move {
+            // The watcher stops delivering once dropped, so the task owns it
+            let _watcher = watcher;
+
+            loop {
+                match events.recv().await {
+                    Ok(_) | Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => break,
+                }
+
+                // One save is often several events; reload once for all of them
+                tokio::time::sleep(INCLUDE_RELOAD_SETTLE).await;
+                while !matches!(
+                    events.try_recv(),
+                    Err(TryRecvError::Empty | TryRecvError::Closed)
+                ) {}
+
+                match Self::reload_config(&config_path, &config, &patterns).await {
+                    Ok(new_config) => {
+                        info!("Reloaded configuration after change in config directory");
+                        let _ = change_sender.send(new_config);
+                    }
+                    Err(e) => {
+                        warn!("Failed to reload config directory: {:#}", e);
+                    }
+                }
+            }
+        });
+
+        info!("Watching config directory: {}", include_dir.display());
+        Ok(())
+    }
+
+    /// Read a config file and merge the fragments of its `include` directory
+    ///
+    /// Fragments are applied in lexical file name order over the main file.
+    /// A key set by more than one fragment takes the last fragment's value
+    /// and logs a warning.
+    async fn read_config(path: &Path) -> Result<DaemonConfig> {
+        let config_str = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        let mut table: toml::Table =
+            toml::from_str(&config_str).with_context(|| "Failed to parse config file")?;
+
+        let base: DaemonConfig = table
+            .clone()
+            .try_into()
+            .with_context(|| "Failed to parse config file")?;
+        if let Some(include_dir) = base.include_dir(path) {
+            let fragments = Self::read_fragments(&include_dir).await?;
+            merge_table(&mut table, fragments, None, "");
+        }
+
+        warn_deprecated_keys(&table);
+        table
+            .try_into()
+            .with_context(|| "Failed to parse merged config")
+    }
+
+    /// Merge every `*.toml` fragment in `dir`, in lexical order
+    async fn read_fragments(dir: &Path) -> Result<toml::Table> {
+        let mut paths = Vec::new();
+        let mut entries = tokio::fs::read_dir(dir)
+            .await
+            .with_context(|| format!("Failed to read config directory: {}", dir.display()))?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "toml") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut merged = toml::Table::new();
+        for path in &paths {
+            let fragment_str = tokio::fs::read_to_string(path)
+                .await
+                .with_context(|| format!("Failed to read config fragment: {}", path.display()))?;
+            let fragment: toml::Table = toml::from_str(&fragment_str)
+                .with_context(|| format!("Failed to parse config fragment: {}", path.display()))?;
+            merge_table(&mut merged, fragment, Some(path), "");
+        }
+
+        debug!(
+            "Merged {} config fragments from {}",
+            paths.len(),
+            dir.display()
+        );
+        Ok(merged)
+    }
+
     /// Internal method to reload configuration
+    ///
+    /// The merged config is validated before it replaces the current one; if
+    /// it is invalid, the current config stays in effect.
     async fn reload_config(
         path: &Path,
         config: &Arc<RwLock<DaemonConfig>>,
         patterns: &Arc<RwLock<CompiledPatterns>>,
     ) -> Result<DaemonConfig> {
-        let config_str = tokio::fs::read_to_string(path).await?;
-        let new_config: DaemonConfig = toml::from_str(&config_str)?;
+        let new_config = Self::read_config(path).await?;
+        Self::validate(&new_config)
+            .context("Reloaded config is invalid; keeping the current one")?;
         let new_patterns = CompiledPatterns::new(&new_config.patterns)?;
 
         // Update config atomically
@@ -389,20 +752,47 @@ impl ConfigManager {
             anyhow::bail!("max_connections must be > 0");
         }
 
+        if config.server.idle_timeout_secs == Some(0) {
+            anyhow::bail!("idle_timeout_secs must be > 0 when set");
+        }
+
+        if config.server.heartbeat_interval_ms == Some(0) {
+            anyhow::bail!("heartbeat_interval_ms must be > 0 when set");
+        }
+
         // Validate watcher config
         if config.watcher.event_buffer_size == 0 {
             anyhow::bail!("event_buffer_size must be > 0");
         }
 
-        // Validate patterns
-        for pattern in &config.patterns.include {
-            Glob::new(pattern).with_context(|| format!("Invalid include pattern: {pattern}"))?;
+        if config.watcher.registration_batch_size == 0 {
+            anyhow::bail!("registration_batch_size must be > 0");
+        }
+
+        if config.watcher.registration_concurrency == 0 {
+            anyhow::bail!("registration_concurrency must be > 0");
         }
 
-        for pattern in &config.patterns.exclude {
-            Glob::new(pattern).with_context(|| format!("Invalid exclude pattern: {pattern}"))?;
+        // Validate performance config
+        if let Some(budget) = config.performance.memory_budget_bytes {
+            BufferBudget::from_budget(budget)?;
         }
 
+        if let Some(ramp) = &config.performance.startup_ramp {
+            if ramp.duration_secs == 0 || ramp.events == 0 || ramp.initial_hashes_per_sec == 0 {
+                anyhow::bail!(
+                    "startup_ramp duration_secs, events and initial_hashes_per_sec must be > 0"
+                );
+            }
+        }
+
+        if config.performance.reorder_window_ms == Some(0) {
+            anyhow::bail!("reorder_window_ms must be > 0 when set");
+        }
+
+        // Validate patterns
+        CompiledPatterns::new(&config.patterns)?;
+
         Ok(())
     }
 }
@@ -413,6 +803,50 @@ impl Default for ConfigManager {
     }
 }
 
+/// Recursively merge `overlay` into `base`, replacing leaf values
+///
+/// With a `source`, replacing a different existing value is a conflict
+/// between fragments and is logged.
+fn merge_table(base: &mut toml::Table, overlay: toml::Table, source: Option<&Path>, prefix: &str) {
+    for (key, value) in overlay {
+        let qualified = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(nested)) => {
+                merge_table(existing, nested, source, &qualified);
+            }
+            (Some(existing), value) => {
+                if let Some(source) = source.filter(|_| *existing != value) {
+                    warn!(
+                        "Config key `{}` set by more than one fragment; using {}",
+                        qualified,
+                        source.display()
+                    );
+                }
+                *existing = value;
+            }
+            (None, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Warn about keys that are still read but no longer mean what they did
+fn warn_deprecated_keys(table: &toml::Table) {
+    let patterns = table.get("patterns").and_then(toml::Value::as_table);
+    if patterns.is_some_and(|p| p.contains_key("max_file_size")) {
+        warn!(
+            "patterns.max_file_size is deprecated and now only leaves larger files unhashed; \
+             rename it to max_hash_size, or use max_event_size to drop their events"
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -450,6 +884,136 @@ exclude = ["**/target/**"]
         assert_eq!(config.watcher.event_buffer_size, 32768);
     }
 
+    #[tokio::test]
+    async fn test_include_fragments_merge_last_wins() {
+        let dir = tempfile::tempdir().unwrap();
+        let fragments = dir.path().join("retrigger.d");
+        std::fs::create_dir(&fragments).unwrap();
+
+        let main = DaemonConfig {
+            include: Some(PathBuf::from("retrigger.d")),
+            ..Default::default()
+        };
+        let main_path = dir.path().join("retrigger.toml");
+        std::fs::write(&main_path, toml::to_string(&main).unwrap()).unwrap();
+        std::fs::write(
+            fragments.join("10-watcher.toml"),
+            "[server]\nport = 9000\n\n[watcher]\nevent_buffer_size = 1024\n",
+        )
+        .unwrap();
+        std::fs::write(fragments.join("20-server.toml"), "[server]\nport = 9100\n").unwrap();
+        std::fs::write(fragments.join("notes.txt"), "[server]\nport = 1\n").unwrap();
+
+        let mut manager = ConfigManager::new();
+        manager.load_from_file(&main_path).await.unwrap();
+
+        let config = manager.get_config().await;
+        assert_eq!(config.server.port, 9100);
+        assert_eq!(config.watcher.event_buffer_size, 1024);
+        assert_eq!(config.server.bind_address, main.server.bind_address);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_fragment_keeps_current_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let fragments = dir.path().join("retrigger.d");
+        std::fs::create_dir(&fragments).unwrap();
+
+        let main = DaemonConfig {
+            include: Some(PathBuf::from("retrigger.d")),
+            ..Default::default()
+        };
+        let main_path = dir.path().join("retrigger.toml");
+        std::fs::write(&main_path, toml::to_string(&main).unwrap()).unwrap();
+        std::fs::write(fragments.join("10-server.toml"), "[server]\nport = 9000\n").unwrap();
+
+        let mut manager = ConfigManager::new();
+        manager.load_from_file(&main_path).await.unwrap();
+
+        // Parses and merges fine, but fails validation
+        std::fs::write(fragments.join("20-broken.toml"), "[server]\nport = 0\n").unwrap();
+        let reloaded =
+            ConfigManager::reload_config(&main_path, &manager.config, &manager.patterns).await;
+        assert!(reloaded.is_err());
+        assert_eq!(manager.get_config().await.server.port, 9000);
+    }
+
+    #[test]
+    fn test_memory_budget() {
+        let minimum = BufferBudget::minimum_bytes();
+        let error = BufferBudget::from_budget(minimum - 1).unwrap_err();
+        assert!(error.to_string().contains(&minimum.to_string()));
+
+        let budget = 16 * 1024 * 1024;
+        let sizes = BufferBudget::from_budget(budget).unwrap();
+        assert!(sizes.system_channel_capacity.is_power_of_two());
+        assert!(sizes.enhanced_channel_capacity.is_power_of_two());
+        assert!(sizes.ipc_ring_capacity > MIN_RING_CAPACITY);
+
+        let used = sizes.system_channel_capacity * BufferBudget::system_slot_bytes()
+            + sizes.enhanced_channel_capacity * BufferBudget::enhanced_slot_bytes()
+            + sizes.ipc_memory_size
+            + sizes.cache_max_bytes;
+        assert!(used <= budget);
+
+        // Larger budgets grow every buffer
+        let larger = BufferBudget::from_budget(4 * budget).unwrap();
+        assert!(larger.ipc_ring_capacity > sizes.ipc_ring_capacity);
+        assert!(larger.cache_max_bytes > sizes.cache_max_bytes);
+        assert!(larger.enhanced_channel_capacity > sizes.enhanced_channel_capacity);
+    }
+
+    #[test]
+    fn test_pattern_precedence() {
+        let config = |precedence| PatternConfig {
+            include: vec!["**/*.json".to_string()],
+            exclude: vec!["build/**".to_string(), "!build/important.json".to_string()],
+            precedence,
+            ..Default::default()
+        };
+        let important = Path::new("build/important.json");
+        let other = Path::new("build/other.json");
+
+        let exclude_wins = CompiledPatterns::new(&config(PatternPrecedence::ExcludeWins)).unwrap();
+        assert!(!exclude_wins.should_watch(important));
+
+        let last_match = CompiledPatterns::new(&config(PatternPrecedence::LastMatchWins)).unwrap();
+        assert!(last_match.should_watch(important));
+        assert!(!last_match.should_watch(other));
+        assert!(last_match.should_watch(Path::new("package.json")));
+        assert!(!last_match.should_watch(Path::new("README.md")));
+
+        let include_wins = CompiledPatterns::new(&config(PatternPrecedence::IncludeWins)).unwrap();
+        assert!(include_wins.should_watch(other));
+        assert!(!include_wins.should_watch(Path::new("README.md")));
+
+        let parsed: PatternConfig = toml::from_str(
+            r#"
+            include = ["**/*"]
+            exclude = []
+            max_file_size = 1024
+            ignore_binary = false
+            precedence = "last_match_wins"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(parsed.precedence, PatternPrecedence::LastMatchWins);
+        // The old single limit now only stops hashing
+        assert_eq!(parsed.max_hash_size, Some(1024));
+        assert_eq!(parsed.max_event_size, None);
+
+        // Leaving the limit out keeps the default rather than hashing everything
+        let parsed: PatternConfig = toml::from_str(
+            r#"
+            include = ["**/*"]
+            exclude = []
+            ignore_binary = false
+            "#,
+        )
+        .unwrap();
+        assert_eq!(parsed.max_hash_size, PatternConfig::default().max_hash_size);
+    }
+
     #[tokio::test]
     async fn test_pattern_matching() {
         let config = PatternConfig {
@@ -464,4 +1028,27 @@ exclude = ["**/target/**"]
         assert!(!patterns.should_watch(Path::new("target/debug/main.rs")));
         assert!(!patterns.should_watch(Path::new("README.md")));
     }
+
+    #[test]
+    fn test_patterns_match_like_the_watcher_filter() {
+        // No includes admits everything not excluded
+        let exclude_only = CompiledPatterns::new(&PatternConfig {
+            include: vec![],
+            exclude: vec!["**/*.log".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(exclude_only.should_watch(Path::new("src/main.rs")));
+        assert!(!exclude_only.should_watch(Path::new("logs/daemon.log")));
+
+        // `*` does not cross directories
+        let top_level = CompiledPatterns::new(&PatternConfig {
+            include: vec!["src/*.rs".to_string()],
+            exclude: vec![],
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(top_level.should_watch(Path::new("src/main.rs")));
+        assert!(!top_level.should_watch(Path::new("src/bin/tool.rs")));
+    }
 }
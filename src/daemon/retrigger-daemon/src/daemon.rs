@@ -1,18 +1,34 @@
 //! Core daemon implementation
 //! Orchestrates all Retrigger components following the Dependency Inversion Principle
 
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, Result};
-use retrigger_system::{EnhancedFileEvent, FileEventProcessor, SystemWatcher};
-use tokio::sync::broadcast;
+use retrigger_system::{
+    CacheConfig, DropReason, EnhancedFileEvent, EventFilter, FileEventProcessor, SystemEvent, SystemEventType,
+    SystemWatcher, WatcherOptions, DEFAULT_EVENT_CHANNEL_CAPACITY,
+};
+use tokio::sync::{broadcast, Semaphore, TryAcquireError};
 use tracing::{debug, error, info, warn};
 
-use crate::config::{CompiledPatterns, ConfigManager, DaemonConfig};
-use crate::grpc::GrpcServer;
-use crate::ipc::{ZeroCopyConfig, ZeroCopyRing};
+use crate::config::{
+    BufferBudget, CompiledPatterns, ConfigManager, DaemonConfig, StartupRampConfig,
+};
+use crate::grpc::{ActiveStreams, EventStream, GrpcServer};
+use crate::ipc::{IpcProducer, ZeroCopyConfig, ZeroCopyRing};
+use crate::log_level::LogLevelControl;
 use crate::metrics::MetricsCollector;
+use crate::resources::{ResourceStats, NEAR_LIMIT_FRACTION};
+
+/// How often to retry creating an IPC ring that failed at startup
+const IPC_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long shutdown waits for consumers to read the events left in the ring
+const IPC_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
 
 // Import shutdown signal function
 async fn shutdown_signal() {
@@ -39,6 +55,26 @@ async fn shutdown_signal() {
     }
 }
 
+/// Step the log level on every SIGUSR1, error → warn → info → debug → trace
+#[cfg(unix)]
+fn start_log_level_toggle(log_level: LogLevelControl) -> Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut sigusr1 =
+        signal(SignalKind::user_defined1()).with_context(|| "Failed to install SIGUSR1 handler")?;
+    tokio::spawn(async move {
+        while sigusr1.recv().await.is_some() {
+            match log_level.cycle() {
+                Ok(level) => info!("SIGUSR1: log level is now {}", level),
+                Err(e) => warn!("SIGUSR1: could not change log level: {}", e),
+            }
+        }
+    });
+
+    info!("Send SIGUSR1 to cycle the log level");
+    Ok(())
+}
+
 /// Main daemon orchestrator
 pub struct Daemon {
     config_manager: ConfigManager,
@@ -48,11 +84,16 @@ pub struct Daemon {
     metrics_collector: Arc<MetricsCollector>,
 
     // Zero-copy IPC system (2025 best practice)
-    ipc_ring: Option<Arc<ZeroCopyRing>>,
+    ipc: Arc<IpcProducer>,
 
     // Event channels
     enhanced_event_sender: broadcast::Sender<EnhancedFileEvent>,
     shutdown_sender: broadcast::Sender<()>,
+
+    // Open event streams; the idle watchdog waits while any exist
+    active_streams: ActiveStreams,
+
+    log_level: Option<LogLevelControl>,
 }
 
 impl Daemon {
@@ -60,37 +101,74 @@ impl Daemon {
     pub async fn new(config_manager: ConfigManager) -> Result<Self> {
         let config = config_manager.get_config().await;
 
+        let budget = config
+            .performance
+            .memory_budget_bytes
+            .map(BufferBudget::from_budget)
+            .transpose()?;
+        if let Some(budget) = &budget {
+            info!(
+                "Memory budget: {} system / {} enhanced channel events, {} IPC slots ({} bytes), {} cache bytes",
+                budget.system_channel_capacity,
+                budget.enhanced_channel_capacity,
+                budget.ipc_ring_capacity,
+                budget.ipc_memory_size,
+                budget.cache_max_bytes
+            );
+        }
+
         // Initialize core components
-        let mut system_watcher =
-            SystemWatcher::new().with_context(|| "Failed to create system watcher")?;
+        let channel_capacity =
+            budget.map_or(DEFAULT_EVENT_CHANNEL_CAPACITY, |b| b.system_channel_capacity);
+        let mut system_watcher = SystemWatcher::with_channel_capacity(channel_capacity)
+            .with_context(|| "Failed to create system watcher")?;
 
         // Apply config patterns to system watcher
-        system_watcher.update_event_filter(
-            config.patterns.include.clone(),
-            config.patterns.exclude.clone(),
-        );
+        system_watcher
+            .set_event_filter(EventFilter {
+                include_patterns: config.patterns.include.clone(),
+                exclude_patterns: config.patterns.exclude.clone(),
+                precedence: config.patterns.precedence,
+                max_event_size: config.patterns.max_event_size,
+                ..Default::default()
+            })
+            .with_context(|| "Invalid watch patterns")?;
+        system_watcher.set_options(WatcherOptions {
+            capture_file_ids: config.watcher.track_file_identity,
+            normalization: config.watcher.event_normalization,
+            stability_window_ms: config.watcher.stability_window_ms,
+            transient_window_ms: config.watcher.transient_window_ms,
+            max_entries_per_dir: config.watcher.max_entries_per_dir,
+            max_debounce_entries: config.watcher.max_debounce_entries,
+        });
         let system_watcher = Arc::new(system_watcher);
 
         // Initialize enhanced event processor with hierarchical caching built-in
-        let event_processor = Arc::new(FileEventProcessor::new());
+        let mut event_processor = FileEventProcessor::with_config(CacheConfig {
+            max_bytes: budget.map(|b| b.cache_max_bytes),
+            ..Default::default()
+        });
+        event_processor.set_root_strategies(system_watcher.root_hash_strategies());
+        event_processor.set_symlink_hash_mode(config.watcher.symlink_hash_mode);
+        event_processor.set_max_hash_size(config.patterns.max_hash_size);
+        let event_processor = Arc::new(event_processor);
         let metrics_collector = Arc::new(MetricsCollector::new());
 
         // Initialize zero-copy IPC ring buffer
-        let ipc_config = ZeroCopyConfig::default();
-        let ipc_ring = match ZeroCopyRing::create_producer(ipc_config) {
-            Ok(ring) => Some(Arc::new(ring)),
-            Err(e) => {
-                warn!(
-                    "Failed to create IPC ring buffer: {}, continuing without IPC",
-                    e
-                );
-                None
-            }
-        };
+        let mut ipc_config = ZeroCopyConfig::default();
+        if let Some(budget) = &budget {
+            ipc_config.ring_capacity = budget.ipc_ring_capacity;
+            ipc_config.memory_size = budget.ipc_memory_size;
+        }
+        let ipc = Arc::new(IpcProducer::create(ipc_config));
 
         // Create event channels
-        let (enhanced_event_sender, _) = broadcast::channel(config.watcher.event_buffer_size);
+        let enhanced_capacity = budget.map_or(config.watcher.event_buffer_size, |b| {
+            b.enhanced_channel_capacity
+        });
+        let (enhanced_event_sender, _) = broadcast::channel(enhanced_capacity);
         let (shutdown_sender, _) = broadcast::channel(10);
+        let active_streams = ActiveStreams::new();
 
         // Initialize gRPC server if enabled
         let grpc_server = if config.server.port > 0 {
@@ -100,6 +178,8 @@ impl Daemon {
                     config.server.port,
                     Arc::clone(&system_watcher),
                     enhanced_event_sender.clone(),
+                    Arc::clone(&ipc),
+                    active_streams.clone(),
                 )
                 .await?,
             )
@@ -113,12 +193,35 @@ impl Daemon {
             event_processor,
             grpc_server,
             metrics_collector,
-            ipc_ring,
+            ipc,
             enhanced_event_sender,
             shutdown_sender,
+            active_streams,
+            log_level: None,
         })
     }
 
+    /// Allow changing the log filter at runtime through the SetLogLevel RPC
+    /// and, on Unix, by sending the daemon SIGUSR1
+    pub fn with_log_level(mut self, log_level: LogLevelControl) -> Self {
+        if let Some(grpc_server) = &mut self.grpc_server {
+            grpc_server.set_log_level_control(log_level.clone());
+        }
+        self.log_level = Some(log_level);
+        self
+    }
+
+    /// Stream enhanced events to an in-process consumer
+    ///
+    /// Counts as a connected client for the idle watchdog until dropped, like
+    /// a gRPC StreamEvents stream. IPC consumers are counted through the ring.
+    pub fn subscribe(&self) -> EventStream {
+        EventStream::new(
+            self.enhanced_event_sender.subscribe(),
+            self.active_streams.open(),
+        )
+    }
+
     /// Run the daemon
     pub async fn run(mut self) -> Result<()> {
         info!("Retrigger daemon starting...");
@@ -127,19 +230,24 @@ impl Daemon {
 
         // Setup initial watch directories
         info!("Setting up {} watch directories", config.watcher.watch_paths.len());
-        for watch_path in &config.watcher.watch_paths {
-            if watch_path.enabled {
-                info!("Watching directory: {} (recursive: {})", watch_path.path.display(), watch_path.recursive);
-                self.system_watcher
-                    .watch_directory(&watch_path.path, watch_path.recursive)
-                    .await
-                    .with_context(|| {
-                        format!("Failed to watch directory: {}", watch_path.path.display())
-                    })?;
-                info!("Successfully watching: {}", watch_path.path.display());
-            }
+        let watches = config
+            .watcher
+            .watch_paths
+            .iter()
+            .filter(|watch_path| watch_path.enabled)
+            .map(|watch_path| (watch_path.path.clone(), watch_path.watch_settings()));
+        let failures = self
+            .system_watcher
+            .watch_many(watches, config.watcher.registration_throttle())
+            .await;
+        if let Some((path, e)) = failures.into_iter().next() {
+            return Err(e.context(format!("Failed to watch directory: {}", path.display())));
         }
-        info!("Completed watch directory setup");
+        let progress = self.system_watcher.registration_progress();
+        info!(
+            "Completed watch directory setup ({} of {} registered)",
+            progress.completed, progress.total
+        );
 
         // Start core services
         info!("Starting core services...");
@@ -161,6 +269,19 @@ impl Daemon {
             info!("gRPC server started");
         }
 
+        if !self.ipc.is_available() {
+            self.start_ipc_recovery(IPC_RETRY_INTERVAL);
+        }
+
+        if let Some(idle_timeout_secs) = config.server.idle_timeout_secs {
+            self.start_idle_watchdog(Duration::from_secs(idle_timeout_secs));
+        }
+
+        #[cfg(unix)]
+        if let Some(log_level) = self.log_level.clone() {
+            start_log_level_toggle(log_level)?;
+        }
+
         info!("Retrigger daemon started successfully");
 
         // Wait for shutdown signal
@@ -191,9 +312,22 @@ impl Daemon {
         let enhanced_sender = self.enhanced_event_sender.clone();
         let metrics = Arc::clone(&self.metrics_collector);
         let patterns = self.config_manager.get_patterns().await;
-        let ipc_ring = self.ipc_ring.clone();
+        let ipc = Arc::clone(&self.ipc);
+        let config = self.config_manager.get_config().await;
+        let idle_heartbeat = config
+            .server
+            .heartbeat_interval_ms
+            .map(Duration::from_millis);
+        let startup_ramp = config
+            .performance
+            .startup_ramp
+            .map(|ramp| StartupRamp::start(&ramp));
+        let reorder = config
+            .performance
+            .reorder_window_ms
+            .map(|ms| ReorderBuffer::new(Duration::from_millis(ms)));
         
-        info!("🔄 IPC ring buffer available: {}", ipc_ring.is_some());
+        info!("🔄 IPC ring buffer available: {}", ipc.is_available());
 
         tokio::spawn(async move {
             info!("🔄 Event processing task spawned - starting event loop...");
@@ -203,7 +337,10 @@ impl Daemon {
                 enhanced_sender,
                 metrics,
                 patterns,
-                ipc_ring,
+                ipc,
+                idle_heartbeat,
+                startup_ramp,
+                reorder,
             )
             .await;
             warn!("🔄 Event processing loop ended unexpectedly!");
@@ -214,13 +351,23 @@ impl Daemon {
     }
 
     /// Event processing loop with enhanced cache and IPC
-    async fn event_processing_loop(
+    ///
+    /// With `idle_heartbeat` set, a `Heartbeat` event is delivered whenever
+    /// that long passes without an event being accepted. With `startup_ramp`
+    /// set, events over the ramp's hashing rate are delivered unhashed until
+    /// the ramp ends. With `reorder` set,
+    /// processed events are delivered through it in timestamp order.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn event_processing_loop(
         mut system_events: broadcast::Receiver<retrigger_system::SystemEvent>,
         event_processor: Arc<FileEventProcessor>,
         enhanced_sender: broadcast::Sender<EnhancedFileEvent>,
         metrics: Arc<MetricsCollector>,
         patterns: CompiledPatterns,
-        ipc_ring: Option<Arc<ZeroCopyRing>>,
+        ipc: Arc<IpcProducer>,
+        idle_heartbeat: Option<Duration>,
+        mut startup_ramp: Option<StartupRamp>,
+        mut reorder: Option<ReorderBuffer>,
     ) {
         info!("🔄 Event processing loop started - waiting for SystemWatcher events...");
         let mut batch = Vec::new();
@@ -229,13 +376,37 @@ impl Daemon {
 
         let mut interval = tokio::time::interval(batch_timeout);
         let mut heartbeat_interval = tokio::time::interval(Duration::from_secs(2));
+        let mut last_activity = tokio::time::Instant::now();
 
         loop {
+            let release_at = reorder.as_ref().and_then(ReorderBuffer::next_release);
+
             tokio::select! {
                 // Heartbeat to prove loop is alive
                 _ = heartbeat_interval.tick() => {
                     info!("🔄 Event processing loop: HEARTBEAT - loop is alive and waiting for events");
                 }
+
+                // Tell consumers the daemon is alive during quiet periods
+                _ = tokio::time::sleep_until(last_activity + idle_heartbeat.unwrap_or_default()),
+                    if idle_heartbeat.is_some() =>
+                {
+                    Self::send_heartbeat(&enhanced_sender, &metrics, &ipc);
+                    last_activity = tokio::time::Instant::now();
+                }
+
+                // Release held events once they are due, oldest first
+                _ = tokio::time::sleep_until(
+                    tokio::time::Instant::from_std(release_at.unwrap_or_else(Instant::now)),
+                ), if release_at.is_some() =>
+                {
+                    if let Some(buffer) = reorder.as_mut() {
+                        let ring = ipc.ring();
+                        for event in buffer.release(Instant::now()) {
+                            Self::deliver_event(event, &enhanced_sender, &metrics, ring.as_ref());
+                        }
+                    }
+                }
                 
                 // Collect events into batch
                 event_result = system_events.recv() => {
@@ -248,6 +419,7 @@ impl Daemon {
                             if patterns.should_watch(&event.path) {
                                 info!("🎯 Event processing loop: Event APPROVED by patterns, adding to batch");
                                 batch.push(event);
+                                last_activity = tokio::time::Instant::now();
 
                                 // Process batch if full
                                 if batch.len() >= batch_size {
@@ -256,14 +428,30 @@ impl Daemon {
                                         &event_processor,
                                         &enhanced_sender,
                                         &metrics,
-                                        &ipc_ring,
+                                        &ipc,
+                                        &mut startup_ramp,
+                                        &mut reorder,
                                     ).await;
                                     batch.clear();
                                 }
+                            } else {
+                                metrics.record_dropped(DropReason::Excluded, 1);
                             }
                         }
-                        Err(e) => {
-                            debug!("Event receiver error: {}", e);
+                        // A burst outran the loop; the missed events are gone,
+                        // but everything after them can still be processed
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!("Event processing fell behind, skipped {} events", skipped);
+                            metrics.record_lagged_events(skipped);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {
+                            debug!("System event channel closed");
+                            if let Some(buffer) = reorder.as_mut() {
+                                let ring = ipc.ring();
+                                for event in buffer.drain() {
+                                    Self::deliver_event(event, &enhanced_sender, &metrics, ring.as_ref());
+                                }
+                            }
                             break;
                         }
                     }
@@ -278,7 +466,9 @@ impl Daemon {
                             &event_processor,
                             &enhanced_sender,
                             &metrics,
-                            &ipc_ring,
+                            &ipc,
+                            &mut startup_ramp,
+                            &mut reorder,
                         ).await;
                         info!("🎯 Event processing loop: BATCH PROCESSED - {} events sent to IPC", batch.len());
                         batch.clear();
@@ -288,36 +478,92 @@ impl Daemon {
         }
     }
 
+    /// Deliver a `Heartbeat` event over IPC and the enhanced event channel
+    fn send_heartbeat(
+        sender: &broadcast::Sender<EnhancedFileEvent>,
+        metrics: &MetricsCollector,
+        ipc: &IpcProducer,
+    ) {
+        let heartbeat = EnhancedFileEvent {
+            system_event: SystemEvent {
+                path: PathBuf::new(),
+                event_type: SystemEventType::Heartbeat,
+                timestamp: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_nanos() as u64,
+                size: 0,
+                is_directory: false,
+                metadata: None,
+            },
+            hash: None,
+            processing_time_ns: 0,
+            metadata: None,
+            events_processed: Some(metrics.events_processed()),
+        };
+
+        if let Some(ring) = ipc.ring() {
+            if !ring.push(&heartbeat) {
+                warn!("IPC ring buffer full, heartbeat dropped");
+            }
+        }
+        metrics.record_heartbeat();
+
+        if let Err(e) = sender.send(heartbeat) {
+            debug!("No enhanced event subscribers: {}", e);
+        }
+    }
+
     /// Process a batch of events with zero-copy IPC
     async fn process_event_batch(
         events: &[retrigger_system::SystemEvent],
         processor: &FileEventProcessor,
         sender: &broadcast::Sender<EnhancedFileEvent>,
         metrics: &MetricsCollector,
-        ipc_ring: &Option<Arc<ZeroCopyRing>>,
+        ipc: &IpcProducer,
+        startup_ramp: &mut Option<StartupRamp>,
+        reorder: &mut Option<ReorderBuffer>,
     ) {
         let start_time = std::time::Instant::now();
+        let ipc_ring = ipc.ring();
 
         for event in events {
-            match processor.process_event(event.clone()).await {
-                Ok(enhanced_event) => {
-                    // Send via zero-copy IPC if available
-                    if let Some(ring) = ipc_ring.as_ref() {
-                        if ring.push(&enhanced_event) {
-                            info!("🚀 Event processing: PUSHED to IPC ring buffer: {:?}", enhanced_event.system_event.path);
-                        } else {
-                            warn!("IPC ring buffer full, event dropped");
-                        }
-                    } else {
-                        warn!("No IPC ring buffer available - events not delivered to external clients");
-                    }
-
-                    metrics.record_event(&enhanced_event);
-
-                    if let Err(e) = sender.send(enhanced_event) {
-                        debug!("No enhanced event subscribers: {}", e);
-                    }
+            let hashes = !event.is_directory
+                && matches!(
+                    event.event_type,
+                    SystemEventType::Created
+                        | SystemEventType::Modified
+                        | SystemEventType::StabilizedModified
+                );
+            let permit = match startup_ramp.as_mut() {
+                Some(ramp) if hashes => ramp.try_acquire(),
+                _ => RampPermit::Granted,
+            };
+            let processed = match permit {
+                // Waiting for a permit would stall `recv` until the channel lags
+                RampPermit::Throttled => {
+                    debug!("Startup ramp: delivering {} unhashed", event.path.display());
+                    Ok(EnhancedFileEvent {
+                        system_event: event.clone(),
+                        hash: None,
+                        processing_time_ns: 0,
+                        metadata: None,
+                        events_processed: None,
+                    })
                 }
+                RampPermit::Finished => {
+                    info!("Startup hashing ramp finished, hashing at full speed");
+                    *startup_ramp = None;
+                    processor.process_event(event.clone()).await
+                }
+                RampPermit::Granted => processor.process_event(event.clone()).await,
+            };
+
+            match processed {
+                Ok(enhanced_event) => match reorder.as_mut() {
+                    Some(buffer) => buffer.push(enhanced_event, Instant::now()),
+                    None => Self::deliver_event(enhanced_event, sender, metrics, ipc_ring.as_ref()),
+                },
                 Err(e) => {
                     warn!(
                         "Failed to process event for {}: {}",
@@ -333,17 +579,62 @@ impl Daemon {
         metrics.record_batch_processing(events.len(), processing_time);
     }
 
+    /// Send a processed event over IPC and the enhanced event channel
+    fn deliver_event(
+        enhanced_event: EnhancedFileEvent,
+        sender: &broadcast::Sender<EnhancedFileEvent>,
+        metrics: &MetricsCollector,
+        ipc_ring: Option<&Arc<ZeroCopyRing>>,
+    ) {
+        // Send via zero-copy IPC if available
+        if let Some(ring) = ipc_ring {
+            if ring.push(&enhanced_event) {
+                info!(
+                    "🚀 Event processing: PUSHED to IPC ring buffer: {:?}",
+                    enhanced_event.system_event.path
+                );
+            } else {
+                warn!("IPC ring buffer full, event dropped");
+                metrics.record_dropped(DropReason::RingFull, 1);
+            }
+        } else {
+            warn!("No IPC ring buffer available - events not delivered to external clients");
+            metrics.record_dropped(DropReason::RingUnavailable, 1);
+        }
+
+        metrics.record_event(&enhanced_event);
+
+        if let Err(e) = sender.send(enhanced_event) {
+            debug!("No enhanced event subscribers: {}", e);
+        }
+    }
+
     /// Start metrics collection
     async fn start_metrics_collector(&self) -> Result<()> {
         let metrics = Arc::clone(&self.metrics_collector);
         let system_watcher = Arc::clone(&self.system_watcher);
         let event_processor = Arc::clone(&self.event_processor);
+        let ipc = Arc::clone(&self.ipc);
+        let enhanced_sender = self.enhanced_event_sender.clone();
 
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_secs(10));
+            let mut near_limit: Vec<&'static str> = Vec::new();
 
             loop {
                 interval.tick().await;
+                metrics.update_ipc_status(ipc.is_available());
+
+                // Warn once per resource as it approaches its OS limit
+                let resources = ResourceStats::collect();
+                metrics.update_resource_stats(&resources);
+                let pressure = resources.near_limits(NEAR_LIMIT_FRACTION);
+                for p in &pressure {
+                    if !near_limit.contains(&p.resource) {
+                        warn!("Approaching OS limit for {}", p);
+                    }
+                }
+                near_limit = pressure.iter().map(|p| p.resource).collect();
 
                 // Collect system metrics
                 let watcher_stats = system_watcher.get_stats().await;
@@ -352,6 +643,12 @@ impl Daemon {
                 // Collect cache metrics
                 let (cache_entries, cache_capacity) = event_processor.cache_stats();
                 metrics.update_cache_stats(cache_entries, cache_capacity);
+                metrics.update_memory_estimate(&Self::estimate_memory(
+                    &system_watcher,
+                    &event_processor,
+                    &enhanced_sender,
+                    &ipc,
+                ));
 
                 // Cleanup old cache entries
                 event_processor
@@ -370,7 +667,13 @@ impl Daemon {
         let system_watcher = Arc::clone(&self.system_watcher);
 
         tokio::spawn(async move {
-            while let Ok(new_config) = config_changes.recv().await {
+            loop {
+                let new_config = match config_changes.recv().await {
+                    Ok(new_config) => new_config,
+                    // Only the latest config matters; the next one is current
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
                 info!("Configuration changed, applying updates");
 
                 // Apply configuration changes
@@ -405,6 +708,74 @@ impl Daemon {
         Ok(())
     }
 
+    /// Shut the daemon down once no consumer has been connected for `timeout`
+    ///
+    /// The countdown starts at launch and restarts whenever the last gRPC
+    /// stream or IPC consumer disconnects; it never fires while one is attached.
+    fn start_idle_watchdog(&self, timeout: Duration) {
+        Self::spawn_idle_watchdog(
+            timeout,
+            self.active_streams.clone(),
+            Arc::clone(&self.ipc),
+            self.shutdown_sender.clone(),
+        );
+        info!("Started idle watchdog (timeout: {}s)", timeout.as_secs());
+    }
+
+    fn spawn_idle_watchdog(
+        timeout: Duration,
+        active_streams: ActiveStreams,
+        ipc: Arc<IpcProducer>,
+        shutdown_sender: broadcast::Sender<()>,
+    ) {
+        tokio::spawn(async move {
+            let mut tracker = IdleTracker::new(timeout, Instant::now());
+            let mut interval = tokio::time::interval(timeout.min(Duration::from_secs(1)));
+
+            loop {
+                interval.tick().await;
+
+                let streams = active_streams.count();
+                let ipc_consumer = ipc.ring().is_some_and(|ring| ring.has_consumer());
+
+                if tracker.observe(streams > 0 || ipc_consumer, Instant::now()) {
+                    info!(
+                        "⏱️ No gRPC stream or IPC consumer connected for {}s, shutting down idle daemon",
+                        timeout.as_secs()
+                    );
+                    let _ = shutdown_sender.send(());
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Keep retrying IPC ring creation until it succeeds
+    ///
+    /// Lets a transient problem with the shared file's directory (full or
+    /// read-only `/tmp`) heal without restarting the daemon.
+    fn start_ipc_recovery(&self, retry_interval: Duration) {
+        let ipc = Arc::clone(&self.ipc);
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(retry_interval);
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+                if ipc.retry() {
+                    info!("IPC ring buffer recovered, consumers can connect");
+                    break;
+                }
+            }
+        });
+
+        info!(
+            "IPC unavailable, retrying every {}s",
+            retry_interval.as_secs()
+        );
+    }
+
     /// Apply configuration changes
     async fn apply_config_changes(
         config: &DaemonConfig,
@@ -421,7 +792,7 @@ impl Daemon {
             if watch_path.enabled {
                 // This is simplified - real implementation would check if already watching
                 system_watcher
-                    .watch_directory(&watch_path.path, watch_path.recursive)
+                    .watch_directory_with(&watch_path.path, watch_path.watch_settings())
                     .await?;
             }
         }
@@ -447,42 +818,807 @@ impl Daemon {
             grpc_server.shutdown().await?;
         }
 
+        // Let consumers read in-flight events before the ring's Drop removes
+        // the shared file
+        let ipc = Arc::clone(&self.ipc);
+        if tokio::task::spawn_blocking(move || ipc.quiesce(IPC_DRAIN_TIMEOUT)).await? {
+            info!("IPC ring drained");
+        }
+
         // Cleanup would happen in Drop implementations
 
         info!("Graceful shutdown completed");
         Ok(())
     }
 
-    /// Get daemon statistics
-    pub async fn get_stats(&self) -> DaemonStats {
-        let watcher_stats = self.system_watcher.get_stats().await;
-        let (cache_entries, cache_capacity) = self.event_processor.cache_stats();
-        let detailed_cache_stats = self.event_processor.detailed_cache_stats();
-        let metrics_stats = self.metrics_collector.get_stats();
-        let ipc_stats = self.ipc_ring.as_ref().map(|ring| ring.stats());
+    /// Estimate the memory currently held by the caches and event buffers
+    pub fn memory_estimate(&self) -> MemoryEstimate {
+        Self::estimate_memory(
+            &self.system_watcher,
+            &self.event_processor,
+            &self.enhanced_event_sender,
+            &self.ipc,
+        )
+    }
 
-        DaemonStats {
-            watcher_stats,
-            cache_entries,
-            cache_capacity,
-            detailed_cache_stats,
-            ipc_stats,
-            uptime_seconds: metrics_stats.uptime_seconds,
-            events_processed: metrics_stats.events_processed,
-            errors_count: metrics_stats.errors_count,
+    fn estimate_memory(
+        system_watcher: &SystemWatcher,
+        event_processor: &FileEventProcessor,
+        enhanced_sender: &broadcast::Sender<EnhancedFileEvent>,
+        ipc: &IpcProducer,
+    ) -> MemoryEstimate {
+        MemoryEstimate {
+            hash_cache_bytes: event_processor.hash_cache_memory_bytes(),
+            directory_cache_bytes: event_processor.directory_cache_memory_bytes(),
+            event_channel_bytes: system_watcher.buffered_events()
+                * BufferBudget::system_slot_bytes()
+                + enhanced_sender.len() * BufferBudget::enhanced_slot_bytes(),
+            ipc_ring_bytes: ipc.memory_size(),
         }
     }
+
+    /// Get a consistent snapshot of daemon statistics
+    ///
+    /// The event counters are read in one tight, non-yielding sequence while
+    /// the watcher's stats lock is held, so the watcher totals cannot move
+    /// during the read. The remaining counters are lock-free atomics; the skew
+    /// between them is bounded by the duration of that sequence (a copy of the
+    /// watcher stats and a few atomic loads), during which at most the events
+    /// completing concurrently in the processing task can be counted on one
+    /// side and not the other. `snapshot_time` is taken inside the sequence.
+    ///
+    /// Everything that walks a map or the filesystem is read just before it
+    /// and may be slightly older: `memory` and the cache statistics lock
+    /// every cache shard, `watch_registration` and the IPC status take their
+    /// own locks, and `resources` reads `/proc`.
+    pub async fn get_stats(&self) -> DaemonStats {
+        let memory = self.memory_estimate();
+        let resources = ResourceStats::collect();
+        let detailed_cache_stats = self.event_processor.detailed_cache_stats();
+        let watch_registration = self.system_watcher.registration_progress();
+        let ipc_available = self.ipc.is_available();
+        let ipc_error = self.ipc.last_error();
+        let ipc_ring = self.ipc.ring();
+        self.system_watcher
+            .with_stats(|watcher_stats| {
+                let snapshot_time = SystemTime::now();
+                let metrics_stats = self.metrics_collector.get_stats();
+                let ipc_stats = ipc_ring.as_ref().map(|ring| ring.stats());
+
+                DaemonStats {
+                    snapshot_time,
+                    watcher_stats: watcher_stats.clone(),
+                    cache_entries: detailed_cache_stats.entry_count,
+                    cache_capacity: detailed_cache_stats.capacity,
+                    detailed_cache_stats,
+                    ipc_stats,
+                    ipc_available,
+                    ipc_error,
+                    watch_registration,
+                    uptime_seconds: metrics_stats.uptime_seconds,
+                    events_processed: metrics_stats.events_processed,
+                    errors_count: metrics_stats.errors_count,
+                    memory,
+                    resources,
+                }
+            })
+            .await
+    }
 }
 
 /// Daemon statistics
 #[derive(Debug, Clone)]
 pub struct DaemonStats {
+    /// Wall-clock instant at which the counters were read
+    pub snapshot_time: SystemTime,
     pub watcher_stats: retrigger_system::WatcherStats,
     pub cache_entries: usize,
     pub cache_capacity: usize,
     pub detailed_cache_stats: retrigger_system::DetailedCacheStats,
     pub ipc_stats: Option<crate::ipc::RingStats>,
+    /// False if the IPC ring could not be created; consumers get no events
+    pub ipc_available: bool,
+    /// Why the IPC ring is unavailable
+    pub ipc_error: Option<String>,
+    /// Startup watch registration, for showing progress on large trees
+    pub watch_registration: retrigger_system::RegistrationProgress,
     pub uptime_seconds: u64,
     pub events_processed: u64,
     pub errors_count: u64,
+    pub memory: MemoryEstimate,
+    /// Descriptor, inotify and memory usage against their OS limits
+    pub resources: ResourceStats,
+}
+
+/// Approximate memory held by the daemon's caches and event buffers
+///
+/// Cache entries are costed at their in-memory size plus their path's
+/// length, buffered events at the per-slot sizes used for
+/// `memory_budget_bytes`, and the IPC ring at its mapping size. Allocator
+/// overhead and hash map spare capacity are not counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    pub hash_cache_bytes: usize,
+    pub directory_cache_bytes: usize,
+    /// Events buffered in the raw and enhanced broadcast channels
+    pub event_channel_bytes: usize,
+    pub ipc_ring_bytes: usize,
+}
+
+impl MemoryEstimate {
+    pub fn total_bytes(&self) -> usize {
+        self.hash_cache_bytes
+            + self.directory_cache_bytes
+            + self.event_channel_bytes
+            + self.ipc_ring_bytes
+    }
+}
+
+/// Paces hashing during the startup ramp; see [`StartupRampConfig`]
+///
+/// Each hash takes a permit from a semaphore that a background task refills
+/// at the ramp's current rate. Taking a permit never waits: an event that
+/// finds none is delivered unhashed, so the processing loop keeps draining
+/// the event channel. Once the ramp ends the semaphore is closed and
+/// `try_acquire` reports it.
+pub(crate) struct StartupRamp {
+    permits: Arc<Semaphore>,
+    hashed: Arc<AtomicU64>,
+    events: u64,
+}
+
+impl StartupRamp {
+    fn start(config: &StartupRampConfig) -> Self {
+        let permits = Arc::new(Semaphore::new(1));
+        let hashed = Arc::new(AtomicU64::new(0));
+        let schedule = RampSchedule::new(config, Instant::now());
+        tokio::spawn(Self::refill(
+            Arc::clone(&permits),
+            Arc::clone(&hashed),
+            schedule,
+        ));
+
+        Self {
+            permits,
+            hashed,
+            events: config.events,
+        }
+    }
+
+    /// Grant permits at the scheduled rate until the ramp is over
+    async fn refill(permits: Arc<Semaphore>, hashed: Arc<AtomicU64>, schedule: RampSchedule) {
+        while !permits.is_closed() {
+            let interval = schedule.interval(Instant::now(), hashed.load(Ordering::Relaxed));
+            let Some(interval) = interval else { break };
+            tokio::time::sleep(interval).await;
+            // Only bank one permit, so an idle spell does not turn into a burst
+            if permits.available_permits() == 0 {
+                permits.add_permits(1);
+            }
+        }
+        permits.close();
+    }
+
+    /// Whether a hash may run now
+    fn try_acquire(&mut self) -> RampPermit {
+        if self.hashed.load(Ordering::Relaxed) >= self.events {
+            self.permits.close();
+            return RampPermit::Finished;
+        }
+
+        match self.permits.try_acquire() {
+            Ok(permit) => {
+                permit.forget();
+                self.hashed.fetch_add(1, Ordering::Relaxed);
+                RampPermit::Granted
+            }
+            Err(TryAcquireError::NoPermits) => RampPermit::Throttled,
+            Err(TryAcquireError::Closed) => RampPermit::Finished,
+        }
+    }
+}
+
+/// Outcome of asking the startup ramp for a hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RampPermit {
+    /// Hash the event
+    Granted,
+    /// Over the current rate; deliver the event without a hash
+    Throttled,
+    /// The ramp is over; hash at full speed from now on
+    Finished,
+}
+
+impl Drop for StartupRamp {
+    fn drop(&mut self) {
+        // Stops the refill task
+        self.permits.close();
+    }
+}
+
+/// How fast the startup ramp grants hashes at a given point
+struct RampSchedule {
+    started: Instant,
+    duration: Duration,
+    events: u64,
+    initial_interval: Duration,
+}
+
+impl RampSchedule {
+    fn new(config: &StartupRampConfig, now: Instant) -> Self {
+        Self {
+            started: now,
+            duration: Duration::from_secs(config.duration_secs),
+            events: config.events,
+            initial_interval: Duration::from_secs(1) / config.initial_hashes_per_sec,
+        }
+    }
+
+    /// Time between permits at `now` after `hashed` hashes, or `None` once
+    /// the ramp is over
+    fn interval(&self, now: Instant, hashed: u64) -> Option<Duration> {
+        let by_time = now.duration_since(self.started).as_secs_f64() / self.duration.as_secs_f64();
+        let by_events = hashed as f64 / self.events as f64;
+        let progress = by_time.max(by_events);
+        if progress >= 1.0 {
+            return None;
+        }
+
+        Some(self.initial_interval.mul_f64(1.0 - progress))
+    }
+}
+
+/// Holds processed events for a window and releases them in timestamp order
+///
+/// The earliest buffered event is released once it has been held for the
+/// full window, so delivery only goes back in time for an event that took
+/// longer than the window to be processed.
+pub(crate) struct ReorderBuffer {
+    window: Duration,
+    /// Keyed by timestamp, then by arrival so equal timestamps keep the
+    /// order they were processed in; values carry when they arrived
+    pending: BTreeMap<(u64, u64), (Instant, EnhancedFileEvent)>,
+    arrivals: u64,
+}
+
+impl ReorderBuffer {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: BTreeMap::new(),
+            arrivals: 0,
+        }
+    }
+
+    fn push(&mut self, event: EnhancedFileEvent, now: Instant) {
+        let key = (event.system_event.timestamp, self.arrivals);
+        self.arrivals += 1;
+        self.pending.insert(key, (now, event));
+    }
+
+    /// When the earliest buffered event is due, if any is buffered
+    fn next_release(&self) -> Option<Instant> {
+        let (_, (arrived, _)) = self.pending.first_key_value()?;
+        Some(*arrived + self.window)
+    }
+
+    /// Remove the events due at `now`, in timestamp order
+    fn release(&mut self, now: Instant) -> Vec<EnhancedFileEvent> {
+        let mut released = Vec::new();
+        while let Some(entry) = self.pending.first_entry() {
+            if now < entry.get().0 + self.window {
+                break;
+            }
+            released.push(entry.remove().1);
+        }
+        released
+    }
+
+    /// Remove every buffered event, in timestamp order
+    fn drain(&mut self) -> Vec<EnhancedFileEvent> {
+        std::mem::take(&mut self.pending)
+            .into_values()
+            .map(|(_, event)| event)
+            .collect()
+    }
+}
+
+/// Tracks how long the daemon has gone without a connected consumer
+struct IdleTracker {
+    timeout: Duration,
+    idle_since: Instant,
+}
+
+impl IdleTracker {
+    fn new(timeout: Duration, now: Instant) -> Self {
+        Self {
+            timeout,
+            idle_since: now,
+        }
+    }
+
+    /// Record whether a consumer is connected at `now`; returns true once the
+    /// daemon has been idle for the full timeout
+    fn observe(&mut self, consumer_connected: bool, now: Instant) -> bool {
+        if consumer_connected {
+            self.idle_since = now;
+            return false;
+        }
+        now.duration_since(self.idle_since) >= self.timeout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::config::PatternConfig;
+    use crate::ipc::ZeroCopyRing;
+    use retrigger_system::{EventFilter, EventNormalization};
+    use tempfile::NamedTempFile;
+
+    #[tokio::test]
+    async fn test_processing_pipeline_with_injected_events() {
+        let mut watcher = SystemWatcher::stub();
+        watcher.set_options(WatcherOptions {
+            normalization: EventNormalization::Raw,
+            ..Default::default()
+        });
+        watcher.set_event_filter(EventFilter {
+            exclude_patterns: vec![],
+            ..Default::default()
+        })
+        .unwrap();
+        watcher.watch_directory("/project", true).await.unwrap();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let ipc_config = ZeroCopyConfig {
+            memory_size: 1024 * 1024,
+            ring_capacity: 100,
+            shared_path: temp_file.path().to_path_buf(),
+            enable_notifications: false,
+            consumer_timeout_ms: 100,
+        };
+        let producer = Arc::new(IpcProducer::create(ipc_config.clone()));
+        let consumer = ZeroCopyRing::create_consumer(ipc_config).unwrap();
+
+        let (enhanced_sender, mut enhanced_events) = broadcast::channel(16);
+        let metrics = Arc::new(MetricsCollector::new());
+        let patterns = CompiledPatterns::new(&PatternConfig::default()).unwrap();
+
+        let processing = tokio::spawn(Daemon::event_processing_loop(
+            watcher.subscribe(),
+            Arc::new(FileEventProcessor::new()),
+            enhanced_sender,
+            Arc::clone(&metrics),
+            patterns,
+            producer,
+            None,
+            None,
+            None,
+        ));
+
+        // A delete needs no file on disk to hash
+        let path = PathBuf::from("/project/src/removed.rs");
+        assert!(
+            watcher
+                .inject_event(SystemEvent {
+                    path: path.clone(),
+                    event_type: SystemEventType::Deleted,
+                    timestamp: 1,
+                    size: 0,
+                    is_directory: false,
+                    metadata: None,
+                })
+                .await
+        );
+
+        let enhanced = tokio::time::timeout(Duration::from_secs(1), enhanced_events.recv())
+            .await
+            .expect("event was not processed")
+            .unwrap();
+        assert_eq!(enhanced.system_event.path, path);
+        assert!(enhanced.hash.is_none());
+        assert_eq!(metrics.get_stats().events_processed, 1);
+        assert_eq!(consumer.pop().unwrap().system_event.path, path);
+
+        processing.abort();
+    }
+
+    #[tokio::test]
+    async fn test_processing_survives_lag() {
+        let (system_sender, system_events) = broadcast::channel(2);
+        let temp_file = NamedTempFile::new().unwrap();
+        let producer = Arc::new(IpcProducer::create(ZeroCopyConfig {
+            memory_size: 1024 * 1024,
+            ring_capacity: 100,
+            shared_path: temp_file.path().to_path_buf(),
+            enable_notifications: false,
+            consumer_timeout_ms: 100,
+        }));
+        let (enhanced_sender, mut enhanced_events) = broadcast::channel(16);
+        let metrics = Arc::new(MetricsCollector::new());
+
+        // Overflow the channel before the loop reads anything
+        for i in 0..4 {
+            system_sender
+                .send(SystemEvent {
+                    path: PathBuf::from(format!("/project/src/{i}.rs")),
+                    event_type: SystemEventType::Deleted,
+                    timestamp: 1,
+                    size: 0,
+                    is_directory: false,
+                    metadata: None,
+                })
+                .unwrap();
+        }
+
+        let processing = tokio::spawn(Daemon::event_processing_loop(
+            system_events,
+            Arc::new(FileEventProcessor::new()),
+            enhanced_sender,
+            Arc::clone(&metrics),
+            CompiledPatterns::new(&PatternConfig::default()).unwrap(),
+            producer,
+            None,
+            None,
+            None,
+        ));
+
+        for expected in ["/project/src/2.rs", "/project/src/3.rs"] {
+            let enhanced = tokio::time::timeout(Duration::from_secs(1), enhanced_events.recv())
+                .await
+                .expect("loop stopped after lagging")
+                .unwrap();
+            assert_eq!(enhanced.system_event.path, PathBuf::from(expected));
+        }
+        let stats = metrics.get_stats();
+        assert_eq!(stats.lagged_events, 2);
+        assert_eq!(stats.dropped_by_reason[&DropReason::Lagged], 2);
+
+        // Only a closed channel ends the loop
+        drop(system_sender);
+        tokio::time::timeout(Duration::from_secs(1), processing)
+            .await
+            .expect("loop did not stop on close")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_when_idle() {
+        let (system_sender, system_events) = broadcast::channel(16);
+        let temp_file = NamedTempFile::new().unwrap();
+        let ipc_config = ZeroCopyConfig {
+            memory_size: 1024 * 1024,
+            ring_capacity: 100,
+            shared_path: temp_file.path().to_path_buf(),
+            enable_notifications: false,
+            consumer_timeout_ms: 100,
+        };
+        let producer = Arc::new(IpcProducer::create(ipc_config.clone()));
+        let consumer = ZeroCopyRing::create_consumer(ipc_config).unwrap();
+        let (enhanced_sender, mut enhanced_events) = broadcast::channel(16);
+
+        let processing = tokio::spawn(Daemon::event_processing_loop(
+            system_events,
+            Arc::new(FileEventProcessor::new()),
+            enhanced_sender,
+            Arc::new(MetricsCollector::new()),
+            CompiledPatterns::new(&PatternConfig::default()).unwrap(),
+            producer,
+            Some(Duration::from_millis(50)),
+            None,
+            None,
+        ));
+
+        let heartbeat = tokio::time::timeout(Duration::from_secs(1), enhanced_events.recv())
+            .await
+            .expect("no heartbeat while idle")
+            .unwrap();
+        assert_eq!(
+            heartbeat.system_event.event_type,
+            SystemEventType::Heartbeat
+        );
+        assert!(heartbeat.system_event.timestamp > 0);
+        assert_eq!(heartbeat.system_event.size, 0);
+        assert_eq!(heartbeat.events_processed, Some(0));
+        let delivered = consumer.pop().unwrap();
+        assert_eq!(
+            delivered.system_event.event_type,
+            SystemEventType::Heartbeat
+        );
+        assert_eq!(delivered.events_processed, Some(0));
+
+        // A real event is delivered as itself and pushes the next heartbeat back
+        system_sender
+            .send(SystemEvent {
+                path: PathBuf::from("/project/src/removed.rs"),
+                event_type: SystemEventType::Deleted,
+                timestamp: 1,
+                size: 0,
+                is_directory: false,
+                metadata: None,
+            })
+            .unwrap();
+        let mut next = enhanced_events.recv().await.unwrap();
+        while next.system_event.event_type == SystemEventType::Heartbeat {
+            next = enhanced_events.recv().await.unwrap();
+        }
+        assert_eq!(next.system_event.event_type, SystemEventType::Deleted);
+
+        let heartbeat = tokio::time::timeout(Duration::from_secs(1), enhanced_events.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            heartbeat.system_event.event_type,
+            SystemEventType::Heartbeat
+        );
+        // Carries the processed-event count
+        assert_eq!(heartbeat.events_processed, Some(1));
+
+        processing.abort();
+    }
+
+    #[tokio::test]
+    async fn test_memory_estimate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cached.rs");
+        std::fs::write(&path, b"fn main() {}").unwrap();
+
+        let processor = FileEventProcessor::new();
+        processor
+            .process_event(SystemEvent {
+                path: path.clone(),
+                event_type: SystemEventType::Created,
+                timestamp: 1,
+                size: 12,
+                is_directory: false,
+                metadata: None,
+            })
+            .await
+            .unwrap();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let ipc = IpcProducer::create(ZeroCopyConfig {
+            memory_size: 1024 * 1024,
+            ring_capacity: 100,
+            shared_path: temp_file.path().to_path_buf(),
+            enable_notifications: false,
+            consumer_timeout_ms: 100,
+        });
+        let (enhanced_sender, _receiver) = broadcast::channel(16);
+        for _ in 0..3 {
+            let removed = SystemEvent {
+                path: PathBuf::from("/project/removed.rs"),
+                event_type: SystemEventType::Deleted,
+                timestamp: 2,
+                size: 0,
+                is_directory: false,
+                metadata: None,
+            };
+            let enhanced = processor.process_event(removed).await.unwrap();
+            enhanced_sender.send(enhanced).unwrap();
+        }
+
+        let watcher = SystemWatcher::stub();
+        let estimate = Daemon::estimate_memory(&watcher, &processor, &enhanced_sender, &ipc);
+        let slot_bytes = BufferBudget::enhanced_slot_bytes();
+        assert!(estimate.hash_cache_bytes >= path.as_os_str().len());
+        assert_eq!(estimate.event_channel_bytes, 3 * slot_bytes);
+        assert_eq!(estimate.ipc_ring_bytes, 1024 * 1024);
+        assert_eq!(
+            estimate.total_bytes(),
+            estimate.hash_cache_bytes
+                + estimate.directory_cache_bytes
+                + estimate.event_channel_bytes
+                + estimate.ipc_ring_bytes
+        );
+    }
+
+    #[test]
+    fn test_reorder_buffer() {
+        let event = |timestamp: u64, name: &str| EnhancedFileEvent {
+            system_event: SystemEvent {
+                path: PathBuf::from(name),
+                event_type: SystemEventType::Modified,
+                timestamp,
+                size: 0,
+                is_directory: false,
+                metadata: None,
+            },
+            hash: None,
+            processing_time_ns: 0,
+            metadata: None,
+            events_processed: None,
+        };
+        let names = |events: Vec<EnhancedFileEvent>| -> Vec<String> {
+            events
+                .iter()
+                .map(|e| e.system_event.path.display().to_string())
+                .collect()
+        };
+
+        let window = Duration::from_millis(20);
+        let start = Instant::now();
+        let mut buffer = ReorderBuffer::new(window);
+        assert_eq!(buffer.next_release(), None);
+
+        // Hashing finished out of order; equal timestamps keep arrival order
+        buffer.push(event(30, "c"), start);
+        buffer.push(event(10, "a"), start);
+        buffer.push(event(20, "b1"), start + Duration::from_millis(5));
+        buffer.push(event(20, "b2"), start + Duration::from_millis(5));
+
+        assert_eq!(buffer.next_release(), Some(start + window));
+        assert!(buffer.release(start + Duration::from_millis(19)).is_empty());
+        assert_eq!(names(buffer.release(start + window)), ["a"]);
+        assert_eq!(
+            names(buffer.release(start + Duration::from_millis(25))),
+            ["b1", "b2", "c"]
+        );
+
+        // A late, earlier event holds back later ones until it is due too
+        buffer.push(event(50, "e"), start);
+        buffer.push(event(40, "d"), start + Duration::from_millis(30));
+        assert!(buffer.release(start + Duration::from_millis(40)).is_empty());
+        assert_eq!(names(buffer.drain()), ["d", "e"]);
+        assert_eq!(buffer.next_release(), None);
+    }
+
+    #[test]
+    fn test_startup_ramp_schedule() {
+        let start = Instant::now();
+        let config = StartupRampConfig {
+            duration_secs: 10,
+            events: 100,
+            initial_hashes_per_sec: 10,
+        };
+
+        // Starts at the initial rate and speeds up as time passes
+        let schedule = RampSchedule::new(&config, start);
+        let initial = schedule.interval(start, 0);
+        assert_eq!(initial, Some(Duration::from_millis(100)));
+        let halfway = schedule.interval(start + Duration::from_secs(5), 0);
+        assert_eq!(halfway, Some(Duration::from_millis(50)));
+        assert_eq!(schedule.interval(start + Duration::from_secs(10), 0), None);
+
+        // Or as events are hashed, ending once enough have been
+        let half_hashed = schedule.interval(start, 50);
+        assert_eq!(half_hashed, Some(Duration::from_millis(50)));
+        assert_eq!(schedule.interval(start, 100), None);
+    }
+
+    #[tokio::test]
+    async fn test_startup_ramp_gates_hashing() {
+        let config = StartupRampConfig {
+            duration_secs: 60,
+            events: 3,
+            initial_hashes_per_sec: 1000,
+        };
+        let mut ramp = StartupRamp::start(&config);
+
+        // The first hash uses the banked permit, the next finds none left
+        assert_eq!(ramp.try_acquire(), RampPermit::Granted);
+        assert_eq!(ramp.try_acquire(), RampPermit::Throttled);
+
+        // The ramp ends once the event budget runs out
+        ramp.hashed.store(3, Ordering::Relaxed);
+        assert_eq!(ramp.try_acquire(), RampPermit::Finished);
+        assert!(ramp.permits.is_closed());
+    }
+
+    #[tokio::test]
+    async fn test_startup_ramp_never_blocks_processing() {
+        let dir = tempfile::tempdir().unwrap();
+        let (system_sender, system_events) = broadcast::channel(16);
+        let temp_file = NamedTempFile::new().unwrap();
+        let producer = Arc::new(IpcProducer::create(ZeroCopyConfig {
+            memory_size: 1024 * 1024,
+            ring_capacity: 100,
+            shared_path: temp_file.path().to_path_buf(),
+            enable_notifications: false,
+            consumer_timeout_ms: 100,
+        }));
+        let (enhanced_sender, mut enhanced_events) = broadcast::channel(16);
+        let metrics = Arc::new(MetricsCollector::new());
+        let ramp = StartupRamp::start(&StartupRampConfig {
+            duration_secs: 60,
+            events: 100,
+            initial_hashes_per_sec: 1,
+        });
+
+        let processing = tokio::spawn(Daemon::event_processing_loop(
+            system_events,
+            Arc::new(FileEventProcessor::new()),
+            enhanced_sender,
+            Arc::clone(&metrics),
+            CompiledPatterns::new(&PatternConfig::default()).unwrap(),
+            producer,
+            None,
+            Some(ramp),
+            None,
+        ));
+
+        // A burst far over one hash per second
+        for i in 0..8 {
+            let path = dir.path().join(format!("{i}.rs"));
+            std::fs::write(&path, b"fn main() {}").unwrap();
+            system_sender
+                .send(SystemEvent {
+                    path,
+                    event_type: SystemEventType::Modified,
+                    timestamp: 1,
+                    size: 12,
+                    is_directory: false,
+                    metadata: None,
+                })
+                .unwrap();
+        }
+
+        // Every event arrives promptly; only the first one is hashed
+        let mut hashed = 0;
+        for _ in 0..8 {
+            let enhanced = tokio::time::timeout(Duration::from_secs(1), enhanced_events.recv())
+                .await
+                .expect("processing blocked on the startup ramp")
+                .unwrap();
+            hashed += usize::from(enhanced.hash.is_some());
+        }
+        assert_eq!(hashed, 1);
+        assert_eq!(metrics.get_stats().lagged_events, 0);
+
+        processing.abort();
+    }
+
+    #[test]
+    fn test_idle_tracker() {
+        let start = Instant::now();
+        let timeout = Duration::from_secs(30);
+        let mut tracker = IdleTracker::new(timeout, start);
+
+        assert!(!tracker.observe(false, start + Duration::from_secs(29)));
+
+        // A consumer connecting keeps the daemon alive and restarts the countdown
+        assert!(!tracker.observe(true, start + Duration::from_secs(29)));
+        assert!(!tracker.observe(true, start + Duration::from_secs(120)));
+        assert!(!tracker.observe(false, start + Duration::from_secs(140)));
+        assert!(tracker.observe(false, start + Duration::from_secs(150)));
+    }
+
+    #[tokio::test]
+    async fn test_open_stream_blocks_idle_shutdown() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let ipc = Arc::new(IpcProducer::create(ZeroCopyConfig {
+            memory_size: 1024 * 1024,
+            ring_capacity: 16,
+            shared_path: temp_file.path().to_path_buf(),
+            enable_notifications: false,
+            consumer_timeout_ms: 100,
+        }));
+        let (shutdown_sender, mut shutdown_receiver) = broadcast::channel(1);
+        let (enhanced_sender, _) = broadcast::channel::<EnhancedFileEvent>(16);
+        let active_streams = ActiveStreams::new();
+
+        let stream = EventStream::new(enhanced_sender.subscribe(), active_streams.open());
+        Daemon::spawn_idle_watchdog(
+            Duration::from_millis(20),
+            active_streams.clone(),
+            ipc,
+            shutdown_sender,
+        );
+
+        // Many timeouts pass without shutdown while the stream is open
+        let early =
+            tokio::time::timeout(Duration::from_millis(200), shutdown_receiver.recv()).await;
+        assert!(early.is_err(), "idle shutdown fired with a stream open");
+
+        drop(stream);
+        assert_eq!(active_streams.count(), 0);
+        let shutdown = tokio::time::timeout(Duration::from_secs(5), shutdown_receiver.recv()).await;
+        assert!(
+            shutdown.is_ok(),
+            "idle shutdown never fired after the stream closed"
+        );
+    }
 }
@@ -2,32 +2,323 @@
 //! Provides remote API access following Interface Segregation Principle
 
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
-use retrigger_system::{EnhancedFileEvent, SystemWatcher};
+use retrigger_core::HashStrategy;
+use retrigger_system::{EnhancedFileEvent, SystemWatcher, WatchSettings};
 use tokio::sync::broadcast;
 use tracing::info;
 
+use crate::ipc::IpcProducer;
+use crate::log_level::LogLevelControl;
+
 // Generated gRPC code would go here
 // For this example, we'll create simplified placeholders
 
+/// Request to add or update a watch
+#[derive(Debug, Clone, Default)]
+pub struct WatchRequest {
+    pub path: String,
+    pub recursive: bool,
+    pub include_patterns: Vec<String>,
+    pub exclude_patterns: Vec<String>,
+    /// Hash algorithm for this root; the daemon default when unset
+    pub hash_strategy: Option<HashStrategy>,
+    /// Descend into mounted filesystems below a recursive watch
+    pub cross_filesystem: bool,
+}
+
+/// Result of a watch request
+#[allow(dead_code)] // Read by the generated service
+#[derive(Debug, Clone, Default)]
+pub struct WatchResponse {
+    pub success: bool,
+    pub error: String,
+    /// True if the path was already watched and its settings were replaced
+    pub updated: bool,
+}
+
+/// Request to remove a watch
+#[derive(Debug, Clone, Default)]
+pub struct UnwatchRequest {
+    pub path: String,
+}
+
+/// Result of an unwatch request
+#[allow(dead_code)] // Read by the generated service
+#[derive(Debug, Clone, Default)]
+pub struct UnwatchResponse {
+    pub success: bool,
+    pub error: String,
+}
+
+/// A single active watch
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WatchInfo {
+    pub path: String,
+    pub recursive: bool,
+    pub include_patterns: Vec<String>,
+    pub exclude_patterns: Vec<String>,
+    pub hash_strategy: Option<HashStrategy>,
+    pub cross_filesystem: bool,
+}
+
+/// All active watches
+#[allow(dead_code)] // Read by the generated service
+#[derive(Debug, Clone, Default)]
+pub struct WatchList {
+    pub watches: Vec<WatchInfo>,
+}
+
+/// Empty request message
+#[derive(Debug, Clone, Default)]
+pub struct Empty {}
+
+/// Request for daemon statistics
+#[derive(Debug, Clone, Default)]
+pub struct StatsRequest {}
+
+/// Daemon health and statistics
+#[allow(dead_code)] // Read by the generated service
+#[derive(Debug, Clone, Default)]
+pub struct StatsResponse {
+    pub watched_directories: u64,
+    /// False if the IPC ring could not be created; consumers get no events
+    pub ipc_available: bool,
+    /// Why the IPC ring is unavailable, empty when it is available
+    pub ipc_error: String,
+    /// Directories not descended into because they exceed
+    /// `max_entries_per_dir`
+    pub skipped_large_dirs: Vec<String>,
+}
+
+/// Request to change the daemon's log filter
+#[derive(Debug, Clone, Default)]
+pub struct SetLogLevelRequest {
+    /// A level such as `debug`, or `RUST_LOG`-style directives
+    pub level: String,
+}
+
+/// Result of a log level change
+#[allow(dead_code)] // Read by the generated service
+#[derive(Debug, Clone, Default)]
+pub struct SetLogLevelResponse {
+    pub success: bool,
+    pub error: String,
+    /// The filter now in effect, unchanged if the request failed
+    pub level: String,
+}
+
+/// Request to stream file events
+#[derive(Debug, Clone, Default)]
+pub struct StreamRequest {
+    pub include_hash: bool,
+    pub buffer_size: u32,
+}
+
+/// Number of event streams open across the daemon
+///
+/// Every path that hands events to a consumer (StreamEvents, in-process
+/// subscriptions) holds a [`StreamGuard`] from here, so the idle watchdog
+/// never shuts down under a connected client.
+#[derive(Debug, Clone, Default)]
+pub struct ActiveStreams(Arc<AtomicUsize>);
+
+impl ActiveStreams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a stream as open until the returned guard drops
+    pub fn open(&self) -> StreamGuard {
+        self.0.fetch_add(1, Ordering::AcqRel);
+        StreamGuard {
+            active_streams: Arc::clone(&self.0),
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// Marks an open event stream for as long as it is alive
+#[derive(Debug)]
+pub struct StreamGuard {
+    active_streams: Arc<AtomicUsize>,
+}
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        self.active_streams.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Enhanced events for one consumer, counted as an open stream until dropped
+#[derive(Debug)]
+pub struct EventStream {
+    receiver: broadcast::Receiver<EnhancedFileEvent>,
+    _guard: StreamGuard,
+}
+
+impl EventStream {
+    pub fn new(receiver: broadcast::Receiver<EnhancedFileEvent>, guard: StreamGuard) -> Self {
+        Self {
+            receiver,
+            _guard: guard,
+        }
+    }
+
+    /// Next event; see [`broadcast::Receiver::recv`]
+    pub async fn recv(&mut self) -> Result<EnhancedFileEvent, broadcast::error::RecvError> {
+        self.receiver.recv().await
+    }
+}
+
 /// gRPC service implementation
 pub struct RetriggerService {
-    #[allow(dead_code)]
     system_watcher: Arc<SystemWatcher>,
-    #[allow(dead_code)]
     enhanced_events: broadcast::Receiver<EnhancedFileEvent>,
+    active_streams: ActiveStreams,
+    ipc: Arc<IpcProducer>,
+    log_level: Option<LogLevelControl>,
 }
 
 impl RetriggerService {
     pub fn new(
         system_watcher: Arc<SystemWatcher>,
         enhanced_events: broadcast::Receiver<EnhancedFileEvent>,
+        ipc: Arc<IpcProducer>,
+        active_streams: ActiveStreams,
     ) -> Self {
         Self {
             system_watcher,
             enhanced_events,
+            active_streams,
+            ipc,
+            log_level: None,
+        }
+    }
+
+    /// StreamEvents RPC: events from now on, held by the stream task for as
+    /// long as the client is connected
+    #[allow(dead_code)] // Wired up by the generated service
+    pub fn stream_events(&self, _request: StreamRequest) -> EventStream {
+        EventStream::new(
+            self.enhanced_events.resubscribe(),
+            self.active_streams.open(),
+        )
+    }
+
+    /// WatchDirectory RPC: add a watch, or update its settings if it exists
+    #[allow(dead_code)] // Wired up by the generated service
+    pub async fn watch_directory(&self, request: WatchRequest) -> WatchResponse {
+        let updated = self.system_watcher.is_watched(&request.path);
+        let settings = WatchSettings {
+            recursive: request.recursive,
+            include_patterns: request.include_patterns,
+            exclude_patterns: request.exclude_patterns,
+            hash_strategy: request.hash_strategy,
+            cross_filesystem: request.cross_filesystem,
+        };
+
+        match self
+            .system_watcher
+            .watch_directory_with(&request.path, settings)
+            .await
+        {
+            Ok(()) => WatchResponse {
+                success: true,
+                error: String::new(),
+                updated,
+            },
+            Err(e) => WatchResponse {
+                success: false,
+                error: e.to_string(),
+                updated: false,
+            },
+        }
+    }
+
+    /// Unwatch RPC: stop delivering events for a watched path
+    #[allow(dead_code)] // Wired up by the generated service
+    pub async fn unwatch(&self, request: UnwatchRequest) -> UnwatchResponse {
+        if self.system_watcher.unwatch_directory(&request.path).await {
+            UnwatchResponse {
+                success: true,
+                error: String::new(),
+            }
+        } else {
+            UnwatchResponse {
+                success: false,
+                error: format!("Path is not watched: {}", request.path),
+            }
+        }
+    }
+
+    /// ListWatches RPC: report all active watches and their settings
+    #[allow(dead_code)] // Wired up by the generated service
+    pub fn list_watches(&self, _request: Empty) -> WatchList {
+        let watches = self
+            .system_watcher
+            .watched_paths()
+            .into_iter()
+            .map(|(path, settings)| WatchInfo {
+                path: path.to_string_lossy().into_owned(),
+                recursive: settings.recursive,
+                include_patterns: settings.include_patterns,
+                exclude_patterns: settings.exclude_patterns,
+                hash_strategy: settings.hash_strategy,
+                cross_filesystem: settings.cross_filesystem,
+            })
+            .collect();
+
+        WatchList { watches }
+    }
+
+    /// GetStats RPC: report watch count and whether IPC is available
+    #[allow(dead_code)] // Wired up by the generated service
+    pub fn get_stats(&self, _request: StatsRequest) -> StatsResponse {
+        StatsResponse {
+            watched_directories: self.system_watcher.watched_paths().len() as u64,
+            ipc_available: self.ipc.is_available(),
+            ipc_error: self.ipc.last_error().unwrap_or_default(),
+            skipped_large_dirs: self
+                .system_watcher
+                .skipped_large_dirs()
+                .iter()
+                .map(|dir| dir.to_string_lossy().into_owned())
+                .collect(),
+        }
+    }
+
+    /// SetLogLevel RPC: replace the log filter for all subsequent records
+    #[allow(dead_code)] // Wired up by the generated service
+    pub fn set_log_level(&self, request: SetLogLevelRequest) -> SetLogLevelResponse {
+        let Some(log_level) = &self.log_level else {
+            return SetLogLevelResponse {
+                success: false,
+                error: "Runtime log level changes are not enabled".to_string(),
+                level: String::new(),
+            };
+        };
+
+        match log_level.set_level(&request.level) {
+            Ok(level) => {
+                info!("Log level changed to {}", level);
+                SetLogLevelResponse {
+                    success: true,
+                    error: String::new(),
+                    level,
+                }
+            }
+            Err(e) => SetLogLevelResponse {
+                success: false,
+                error: format!("{e:#}"),
+                level: log_level.current().unwrap_or_default(),
+            },
         }
     }
 }
@@ -36,7 +327,6 @@ impl RetriggerService {
 pub struct GrpcServer {
     bind_address: String,
     port: u16,
-    #[allow(dead_code)]
     service: RetriggerService,
     server_handle: Option<tokio::task::JoinHandle<Result<(), tonic::transport::Error>>>,
 }
@@ -48,9 +338,11 @@ impl GrpcServer {
         port: u16,
         system_watcher: Arc<SystemWatcher>,
         enhanced_event_sender: broadcast::Sender<EnhancedFileEvent>,
+        ipc: Arc<IpcProducer>,
+        active_streams: ActiveStreams,
     ) -> Result<Self> {
         let enhanced_events = enhanced_event_sender.subscribe();
-        let service = RetriggerService::new(system_watcher, enhanced_events);
+        let service = RetriggerService::new(system_watcher, enhanced_events, ipc, active_streams);
 
         Ok(Self {
             bind_address: bind_address.to_string(),
@@ -87,6 +379,11 @@ impl GrpcServer {
         Ok(())
     }
 
+    /// Let the SetLogLevel RPC change the installed log filter
+    pub fn set_log_level_control(&mut self, log_level: LogLevelControl) {
+        self.service.log_level = Some(log_level);
+    }
+
     /// Shutdown the gRPC server
     pub async fn shutdown(self) -> Result<()> {
         info!("Shutting down gRPC server");
@@ -110,8 +407,11 @@ package retrigger.v1;
 
 service Retrigger {
   rpc WatchDirectory(WatchRequest) returns (WatchResponse);
+  rpc Unwatch(UnwatchRequest) returns (UnwatchResponse);
+  rpc ListWatches(Empty) returns (WatchList);
   rpc StreamEvents(StreamRequest) returns (stream FileEvent);
   rpc GetStats(StatsRequest) returns (StatsResponse);
+  rpc SetLogLevel(SetLogLevelRequest) returns (SetLogLevelResponse);
 }
 
 message WatchRequest {
@@ -119,11 +419,64 @@ message WatchRequest {
   bool recursive = 2;
   repeated string include_patterns = 3;
   repeated string exclude_patterns = 4;
+  optional HashStrategy hash_strategy = 5;
+  bool cross_filesystem = 6;
 }
 
 message WatchResponse {
   bool success = 1;
   string error = 2;
+  bool updated = 3;
+}
+
+message UnwatchRequest {
+  string path = 1;
+}
+
+message UnwatchResponse {
+  bool success = 1;
+  string error = 2;
+}
+
+message Empty {}
+
+message WatchInfo {
+  string path = 1;
+  bool recursive = 2;
+  repeated string include_patterns = 3;
+  repeated string exclude_patterns = 4;
+  optional HashStrategy hash_strategy = 5;
+  bool cross_filesystem = 6;
+}
+
+enum HashStrategy {
+  BLAKE3_ONLY = 0;
+  XXH3_ONLY = 1;
+  HYBRID = 2;
+  AUTO = 3;
+}
+
+message WatchList {
+  repeated WatchInfo watches = 1;
+}
+
+message StatsRequest {}
+
+message StatsResponse {
+  uint64 watched_directories = 1;
+  bool ipc_available = 2;
+  string ipc_error = 3;
+  repeated string skipped_large_dirs = 4;
+}
+
+message SetLogLevelRequest {
+  string level = 1;
+}
+
+message SetLogLevelResponse {
+  bool success = 1;
+  string error = 2;
+  string level = 3;
 }
 
 message StreamRequest {
@@ -146,6 +499,8 @@ enum EventType {
   DELETED = 2;
   MOVED = 3;
   METADATA_CHANGED = 4;
+  STABILIZED_MODIFIED = 5;
+  HEARTBEAT = 6;
 }
 
 message FileHash {
@@ -154,3 +509,121 @@ message FileHash {
   bool is_incremental = 3;
 }
 */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::ZeroCopyConfig;
+
+    fn service_with_ipc(shared_path: std::path::PathBuf) -> RetriggerService {
+        let (sender, _) = broadcast::channel(16);
+        let ipc = IpcProducer::create(ZeroCopyConfig {
+            memory_size: 1024 * 1024,
+            ring_capacity: 100,
+            shared_path,
+            enable_notifications: false,
+            consumer_timeout_ms: 100,
+        });
+        RetriggerService::new(
+            Arc::new(SystemWatcher::stub()),
+            sender.subscribe(),
+            Arc::new(ipc),
+            ActiveStreams::new(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_watch_management_rpcs() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = service_with_ipc(dir.path().join("ring.mmap"));
+
+        let request = WatchRequest {
+            path: "/tmp/watched".to_string(),
+            recursive: false,
+            ..Default::default()
+        };
+        let response = service.watch_directory(request.clone()).await;
+        assert!(response.success && !response.updated);
+
+        let response = service
+            .watch_directory(WatchRequest {
+                recursive: true,
+                include_patterns: vec!["**/*.rs".to_string()],
+                ..request
+            })
+            .await;
+        assert!(response.success && response.updated);
+
+        let list = service.list_watches(Empty {});
+        assert_eq!(
+            list.watches,
+            vec![WatchInfo {
+                path: "/tmp/watched".to_string(),
+                recursive: true,
+                include_patterns: vec!["**/*.rs".to_string()],
+                exclude_patterns: vec![],
+                hash_strategy: None,
+                cross_filesystem: false,
+            }]
+        );
+
+        let unwatch = UnwatchRequest {
+            path: "/tmp/watched".to_string(),
+        };
+        assert!(service.unwatch(unwatch.clone()).await.success);
+        assert!(!service.unwatch(unwatch).await.success);
+        assert!(service.list_watches(Empty {}).watches.is_empty());
+    }
+
+    #[test]
+    fn test_stats_report_unavailable_ipc() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let stats = service_with_ipc(dir.path().join("ring.mmap")).get_stats(StatsRequest {});
+        assert!(stats.ipc_available);
+        assert!(stats.ipc_error.is_empty());
+
+        let missing = dir.path().join("missing/ring.mmap");
+        let stats = service_with_ipc(missing.clone()).get_stats(StatsRequest {});
+        assert!(!stats.ipc_available);
+        assert!(stats.ipc_error.contains(&missing.display().to_string()));
+    }
+
+    #[test]
+    fn test_event_stream_counts_while_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = service_with_ipc(dir.path().join("ring.mmap"));
+        let active_streams = service.active_streams.clone();
+
+        let stream = service.stream_events(StreamRequest::default());
+        let second = service.stream_events(StreamRequest::default());
+        assert_eq!(active_streams.count(), 2);
+
+        drop(stream);
+        assert_eq!(active_streams.count(), 1);
+        drop(second);
+        assert_eq!(active_streams.count(), 0);
+    }
+
+    #[test]
+    fn test_set_log_level() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut service = service_with_ipc(dir.path().join("ring.mmap"));
+        let request = |level: &str| SetLogLevelRequest {
+            level: level.to_string(),
+        };
+        assert!(!service.set_log_level(request("debug")).success);
+
+        let (control, layer) = LogLevelControl::new(tracing_subscriber::EnvFilter::new("info"));
+        service.log_level = Some(control);
+
+        let response = service.set_log_level(request("retrigger=debug,warn"));
+        assert!(response.success, "{}", response.error);
+        assert_eq!(response.level, "retrigger=debug,warn");
+
+        let response = service.set_log_level(request("retrigger=loud"));
+        assert!(!response.success && !response.error.is_empty());
+        assert_eq!(response.level, "retrigger=debug,warn");
+        drop(layer);
+    }
+}
@@ -9,11 +9,11 @@ use memmap2::{MmapMut, MmapOptions};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::{info, warn};
 
-use retrigger_system::EnhancedFileEvent;
+use retrigger_system::{EnhancedFileEvent, SerializedFileEvent, WIRE_FORMAT_VERSION};
 
 /// Zero-copy IPC configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,7 +39,6 @@ impl Default for ZeroCopyConfig {
 
 /// Magic number for validation (RTRG in ASCII)
 const MAGIC_NUMBER: u32 = 0x52545247;
-const VERSION: u32 = 1;
 
 /// Lock-free ring buffer header in shared memory
 #[repr(C)]
@@ -74,7 +73,7 @@ impl RingHeader {
     pub fn new(capacity: u32, event_size: u32) -> Self {
         Self {
             magic: MAGIC_NUMBER,
-            version: VERSION,
+            version: WIRE_FORMAT_VERSION,
             write_pos: AtomicU32::new(0),
             read_pos: AtomicU32::new(0),
             capacity,
@@ -92,98 +91,30 @@ impl RingHeader {
     }
 
     pub fn is_valid(&self) -> bool {
-        self.magic == MAGIC_NUMBER && self.version == VERSION
+        self.magic == MAGIC_NUMBER && self.version == WIRE_FORMAT_VERSION
     }
 }
 
-/// Serialized file event for cross-process communication
-#[repr(C)]
-#[derive(Debug, Clone)]
-pub struct SerializedFileEvent {
-    timestamp: u64,
-    event_type: u32, // 0=created, 1=modified, 2=deleted, 3=moved, 4=metadata_changed
-    path_len: u32,
-    size: u64,
-    is_directory: u32,
-    hash_present: u32,
-    hash_value: u64,
-    path_data: [u8; 512], // Fixed-size path buffer
+/// Identity of the mapped file, used to detect it being replaced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileIdentity {
+    dev: u64,
+    ino: u64,
 }
 
-impl From<&EnhancedFileEvent> for SerializedFileEvent {
-    fn from(event: &EnhancedFileEvent) -> Self {
-        let path_string = event.system_event.path.to_string_lossy();
-        let path_bytes = path_string.as_bytes();
-        let path_len = std::cmp::min(path_bytes.len(), 511); // Leave room for null terminator
-
-        let mut path_data = [0u8; 512];
-        path_data[..path_len].copy_from_slice(&path_bytes[..path_len]);
-
-        let event_type = match event.system_event.event_type {
-            retrigger_system::SystemEventType::Created => 0,
-            retrigger_system::SystemEventType::Modified => 1,
-            retrigger_system::SystemEventType::Deleted => 2,
-            retrigger_system::SystemEventType::Moved => 3,
-            retrigger_system::SystemEventType::MetadataChanged => 4,
-        };
-
-        Self {
-            timestamp: event.system_event.timestamp,
-            event_type,
-            path_len: path_len as u32,
-            size: event.system_event.size,
-            is_directory: if event.system_event.is_directory {
-                1
-            } else {
-                0
-            },
-            hash_present: if event.hash.is_some() { 1 } else { 0 },
-            hash_value: event.hash.as_ref().map(|h| h.hash).unwrap_or(0),
-            path_data,
-        }
+impl FileIdentity {
+    #[cfg(unix)]
+    fn of(metadata: &std::fs::Metadata) -> Option<Self> {
+        use std::os::unix::fs::MetadataExt;
+        Some(Self {
+            dev: metadata.dev(),
+            ino: metadata.ino(),
+        })
     }
-}
-
-impl From<&SerializedFileEvent> for EnhancedFileEvent {
-    fn from(ser: &SerializedFileEvent) -> Self {
-        let path_str =
-            std::str::from_utf8(&ser.path_data[..ser.path_len as usize]).unwrap_or("invalid_path");
-
-        let event_type = match ser.event_type {
-            0 => retrigger_system::SystemEventType::Created,
-            1 => retrigger_system::SystemEventType::Modified,
-            2 => retrigger_system::SystemEventType::Deleted,
-            3 => retrigger_system::SystemEventType::Moved,
-            4 => retrigger_system::SystemEventType::MetadataChanged,
-            _ => retrigger_system::SystemEventType::Modified,
-        };
-
-        use retrigger_core::HashResult;
-        use retrigger_system::{EnhancedFileEvent, SystemEvent};
 
-        let system_event = SystemEvent {
-            path: PathBuf::from(path_str),
-            event_type,
-            timestamp: ser.timestamp,
-            size: ser.size,
-            is_directory: ser.is_directory == 1,
-        };
-
-        let hash = if ser.hash_present == 1 {
-            Some(HashResult {
-                hash: ser.hash_value,
-                size: ser.size as u32,
-                is_incremental: false,
-            })
-        } else {
-            None
-        };
-
-        EnhancedFileEvent {
-            system_event,
-            hash,
-            processing_time_ns: 0, // Will be set by consumer if needed
-        }
+    #[cfg(not(unix))]
+    fn of(_metadata: &std::fs::Metadata) -> Option<Self> {
+        None
     }
 }
 
@@ -196,6 +127,9 @@ pub struct ZeroCopyRing {
     config: ZeroCopyConfig,
     is_producer: bool,
     notifications_fd: Option<i32>,
+    // Producer and file seen when the ring was mapped
+    connected_producer_pid: u32,
+    file_identity: Option<FileIdentity>,
 }
 
 unsafe impl Send for ZeroCopyRing {}
@@ -223,6 +157,7 @@ impl ZeroCopyRing {
                 .context("Failed to map memory")?
         };
 
+        let file_identity = file.metadata().ok().and_then(|m| FileIdentity::of(&m));
         let header_ptr = mmap.as_ptr() as *mut RingHeader;
 
         // Initialize header (only producer does this)
@@ -258,13 +193,13 @@ impl ZeroCopyRing {
             config,
             is_producer: true,
             notifications_fd,
+            connected_producer_pid: std::process::id(),
+            file_identity,
         })
     }
 
     /// Create consumer (reader) instance  
     pub fn create_consumer(config: ZeroCopyConfig) -> Result<Self> {
-        info!("Creating IPC consumer: {}", config.shared_path.display());
-
         // Wait for producer to create the file
         let mut attempts = 0;
         let file = loop {
@@ -283,6 +218,24 @@ impl ZeroCopyRing {
             }
         };
 
+        Self::map_consumer(file, config)
+    }
+
+    /// Create consumer instance without waiting for the producer's file
+    pub fn try_create_consumer(config: ZeroCopyConfig) -> Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&config.shared_path)
+            .context("Failed to open IPC file")?;
+
+        Self::map_consumer(file, config)
+    }
+
+    fn map_consumer(file: std::fs::File, config: ZeroCopyConfig) -> Result<Self> {
+        info!("Creating IPC consumer: {}", config.shared_path.display());
+
+        let file_identity = file.metadata().ok().and_then(|m| FileIdentity::of(&m));
         let mmap = unsafe {
             MmapOptions::new()
                 .map_mut(&file)
@@ -297,30 +250,62 @@ impl ZeroCopyRing {
             return Err(anyhow::anyhow!("Invalid shared memory header"));
         }
 
-        // Register as consumer
-        header
-            .consumer_pid
-            .store(std::process::id(), Ordering::Release);
+        let connected_producer_pid = header.producer_pid.load(Ordering::Acquire);
 
         let data_start = unsafe { mmap.as_ptr().add(std::mem::size_of::<RingHeader>()) as *mut u8 };
 
-        // Setup eventfd for notifications
-        let notifications_fd = if config.enable_notifications {
-            Self::create_eventfd().ok()
-        } else {
-            None
-        };
+        // The producer signals the eventfd it created, which a consumer in
+        // another process cannot reach; an eventfd of our own would never
+        // fire, so consumers wait by polling the ring positions instead
+        let notifications_fd = None;
 
         info!("Connected to zero-copy ring buffer");
 
-        Ok(Self {
+        let ring = Self {
             mmap,
             header: header_ptr,
             data_start,
             config,
             is_producer: false,
             notifications_fd,
-        })
+            connected_producer_pid,
+            file_identity,
+        };
+        ring.register_consumer()?;
+        Ok(ring)
+    }
+
+    /// Record this process as the ring's consumer
+    ///
+    /// The ring has a single read position and a single consumer PID slot,
+    /// so it takes one consumer process at a time: this fails while another
+    /// live process is registered. A PID left behind by a process that no
+    /// longer exists is taken over.
+    pub fn register_consumer(&self) -> Result<()> {
+        let header = unsafe { &*self.header };
+        let pid = std::process::id();
+        let mut current = header.consumer_pid.load(Ordering::Acquire);
+        loop {
+            if current == pid {
+                return Ok(());
+            }
+            if current != 0 && Self::process_alive(current) {
+                anyhow::bail!(
+                    "IPC ring {} already has a consumer (pid {})",
+                    self.config.shared_path.display(),
+                    current
+                );
+            }
+            match header.consumer_pid.compare_exchange(
+                current,
+                pid,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return Ok(()),
+                Err(actual) => current = actual,
+            }
+        }
     }
 
     /// Create eventfd for notifications (Linux only)
@@ -377,8 +362,10 @@ impl ZeroCopyRing {
         header.last_write_timestamp.store(now, Ordering::Relaxed);
         header.total_events.fetch_add(1, Ordering::Relaxed);
 
-        // Update utilization tracking
-        let utilization = ((next_write.wrapping_sub(read_pos)) * 100) / header.capacity;
+        // Update utilization tracking; positions wrap, so measure the
+        // distance modulo capacity
+        let used = (next_write + header.capacity - read_pos) % header.capacity;
+        let utilization = (used * 100) / header.capacity;
         let current_max = header.max_utilization.load(Ordering::Relaxed);
         if utilization > current_max {
             header.max_utilization.store(utilization, Ordering::Relaxed);
@@ -417,6 +404,10 @@ impl ZeroCopyRing {
         } as *const SerializedFileEvent;
 
         let serialized = unsafe { std::ptr::read(event_ptr) };
+        // Another process wrote this slot; deliver what can be salvaged
+        if let Err(e) = serialized.validate() {
+            warn!("Malformed event in IPC ring: {}", e);
+        }
         let event = EnhancedFileEvent::from(&serialized);
 
         // Update statistics
@@ -554,6 +545,56 @@ impl ZeroCopyRing {
         }
     }
 
+    /// Whether a consumer process is currently attached to the ring
+    ///
+    /// Consumers clear their PID on drop; a PID left behind by a crashed
+    /// consumer is ignored once that process no longer exists.
+    pub fn has_consumer(&self) -> bool {
+        let header = unsafe { &*self.header };
+        let pid = header.consumer_pid.load(Ordering::Acquire);
+        pid != 0 && Self::process_alive(pid)
+    }
+
+    /// Whether the producer this consumer mapped has gone away
+    ///
+    /// True once the producer process has exited, another producer has taken
+    /// over the ring, or the shared file was removed or replaced. A consumer in
+    /// this state keeps reading its old mapping and will see no new events.
+    pub fn producer_gone(&self) -> bool {
+        if self.is_producer {
+            return false;
+        }
+
+        let header = unsafe { &*self.header };
+        let pid = header.producer_pid.load(Ordering::Acquire);
+        if pid == 0 || pid != self.connected_producer_pid || !Self::process_alive(pid) {
+            return true;
+        }
+
+        match self.file_identity {
+            Some(identity) => {
+                std::fs::metadata(&self.config.shared_path)
+                    .ok()
+                    .and_then(|m| FileIdentity::of(&m))
+                    != Some(identity)
+            }
+            None => false,
+        }
+    }
+
+    #[cfg(unix)]
+    fn process_alive(pid: u32) -> bool {
+        // Signal 0 only checks that the process exists; EPERM means it does
+        // but belongs to another user
+        let result = unsafe { libc::kill(pid as libc::pid_t, 0) };
+        result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+    }
+
+    #[cfg(not(unix))]
+    fn process_alive(_pid: u32) -> bool {
+        true
+    }
+
     /// Signal shutdown to all consumers
     pub fn shutdown(&self) {
         let header = unsafe { &*self.header };
@@ -565,6 +606,40 @@ impl ZeroCopyRing {
         }
     }
 
+    /// Wait up to `timeout` for consumers to read every event still in the
+    /// ring, then signal shutdown (producer only)
+    ///
+    /// The flag is only set once the wait is over, so a consumer that stops
+    /// reading when it sees it has already had every event it could get.
+    /// Returns whether the ring drained. Stops waiting early if no consumer
+    /// is attached, since nothing will read the rest; either way the number
+    /// of abandoned events is logged.
+    pub fn quiesce(&self, timeout: Duration) -> bool {
+        if !self.is_producer {
+            warn!("Attempted to quiesce from consumer");
+            return false;
+        }
+
+        let deadline = std::time::Instant::now() + timeout;
+        let drained = loop {
+            let pending = self.stats().used;
+            if pending == 0 {
+                break true;
+            }
+            if !self.has_consumer() || std::time::Instant::now() >= deadline {
+                warn!(
+                    "IPC ring shut down with {} unread events abandoned",
+                    pending
+                );
+                break false;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        };
+
+        self.shutdown();
+        drained
+    }
+
     /// Check if shutdown has been signaled
     pub fn is_shutdown(&self) -> bool {
         let header = unsafe { &*self.header };
@@ -572,6 +647,9 @@ impl ZeroCopyRing {
     }
 
     /// Get the file descriptor for external polling (Linux only)
+    ///
+    /// Only a producer has one; it is signaled on every push, so it is
+    /// useful to a consumer that shares the producer's process.
     pub fn get_event_fd(&self) -> Option<i32> {
         self.notifications_fd
     }
@@ -584,8 +662,11 @@ impl ZeroCopyRing {
 
 impl Drop for ZeroCopyRing {
     fn drop(&mut self) {
-        // Signal shutdown
-        self.shutdown();
+        // Only the producer signals shutdown; a consumer detaching or
+        // reconnecting must not mark a shared ring as shut down
+        if self.is_producer {
+            self.shutdown();
+        }
 
         // Close eventfd if open
         if let Some(fd) = self.notifications_fd {
@@ -604,6 +685,15 @@ impl Drop for ZeroCopyRing {
         // If we're the producer, cleanup the shared file
         if self.is_producer {
             let _ = std::fs::remove_file(&self.config.shared_path);
+        } else {
+            // Deregister, unless another consumer has already taken over
+            let header = unsafe { &*self.header };
+            let _ = header.consumer_pid.compare_exchange(
+                std::process::id(),
+                0,
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            );
         }
     }
 }
@@ -622,6 +712,87 @@ pub struct RingStats {
     pub consumer_pid: u32,
 }
 
+/// The daemon's producer ring, which may be unavailable
+///
+/// Creating the shared file can fail (read-only or full `/tmp`, bad
+/// permissions). The daemon then keeps running without IPC, reports it as
+/// disabled along with the reason, and can retry creation later.
+pub struct IpcProducer {
+    config: ZeroCopyConfig,
+    ring: RwLock<Option<Arc<ZeroCopyRing>>>,
+    last_error: Mutex<Option<String>>,
+}
+
+impl IpcProducer {
+    /// Try to create the producer ring, recording the error on failure
+    pub fn create(config: ZeroCopyConfig) -> Self {
+        let producer = Self {
+            config,
+            ring: RwLock::new(None),
+            last_error: Mutex::new(None),
+        };
+        producer.retry();
+        producer
+    }
+
+    /// Create the ring if it is not available yet; returns availability
+    pub fn retry(&self) -> bool {
+        if self.is_available() {
+            return true;
+        }
+
+        match ZeroCopyRing::create_producer(self.config.clone()) {
+            Ok(ring) => {
+                *self.ring.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(ring));
+                *self.last_error.lock().unwrap_or_else(|e| e.into_inner()) = None;
+                true
+            }
+            Err(e) => {
+                let error = format!(
+                    "failed to create IPC ring at {}: {:#}",
+                    self.config.shared_path.display(),
+                    e
+                );
+                warn!("IPC disabled, consumers will receive no events: {}", error);
+                *self.last_error.lock().unwrap_or_else(|e| e.into_inner()) = Some(error);
+                false
+            }
+        }
+    }
+
+    /// The ring, if it was created
+    pub fn ring(&self) -> Option<Arc<ZeroCopyRing>> {
+        self.ring.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.ring.read().unwrap_or_else(|e| e.into_inner()).is_some()
+    }
+
+    /// Size of the shared memory mapping, or 0 while the ring is unavailable
+    pub fn memory_size(&self) -> usize {
+        if self.is_available() {
+            self.config.memory_size
+        } else {
+            0
+        }
+    }
+
+    /// Drain the ring before shutdown; see [`ZeroCopyRing::quiesce`]. True
+    /// if there is no ring to drain.
+    pub fn quiesce(&self, timeout: Duration) -> bool {
+        self.ring().is_none_or(|ring| ring.quiesce(timeout))
+    }
+
+    /// Why the ring is unavailable
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
 /// IPC Manager for handling multiple consumers
 pub struct IPCManager {
     producer_ring: Option<Arc<ZeroCopyRing>>,
@@ -680,6 +851,111 @@ mod tests {
     use retrigger_system::{EnhancedFileEvent, SystemEvent, SystemEventType};
     use tempfile::NamedTempFile;
 
+    #[test]
+    fn test_consumer_registration() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let config = ZeroCopyConfig {
+            memory_size: 1024 * 1024,
+            ring_capacity: 100,
+            shared_path: temp_file.path().to_path_buf(),
+            enable_notifications: false,
+            consumer_timeout_ms: 100,
+        };
+
+        let producer = ZeroCopyRing::create_producer(config.clone()).unwrap();
+        assert!(!producer.has_consumer());
+
+        let consumer = ZeroCopyRing::create_consumer(config).unwrap();
+        assert!(producer.has_consumer());
+
+        drop(consumer);
+        assert!(!producer.has_consumer());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_second_consumer_is_rejected() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let config = ZeroCopyConfig {
+            memory_size: 1024 * 1024,
+            ring_capacity: 100,
+            shared_path: temp_file.path().to_path_buf(),
+            enable_notifications: false,
+            consumer_timeout_ms: 100,
+        };
+
+        let producer = ZeroCopyRing::create_producer(config.clone()).unwrap();
+        let header = unsafe { &*producer.header };
+
+        // Another live process holds the ring, and keeps it
+        let other = std::os::unix::process::parent_id();
+        header.consumer_pid.store(other, Ordering::Release);
+        assert!(ZeroCopyRing::create_consumer(config.clone()).is_err());
+        assert_eq!(producer.stats().consumer_pid, other);
+
+        // A consumer that exited without deregistering is taken over
+        let exited = i32::MAX as u32;
+        header.consumer_pid.store(exited, Ordering::Release);
+        let consumer = ZeroCopyRing::create_consumer(config).unwrap();
+        assert_eq!(producer.stats().consumer_pid, std::process::id());
+        drop(consumer);
+        assert!(!producer.has_consumer());
+    }
+
+    #[test]
+    fn test_consumer_rejects_other_wire_version() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let config = ZeroCopyConfig {
+            memory_size: 1024 * 1024,
+            ring_capacity: 100,
+            shared_path: temp_file.path().to_path_buf(),
+            enable_notifications: false,
+            consumer_timeout_ms: 100,
+        };
+
+        let producer = ZeroCopyRing::create_producer(config.clone()).unwrap();
+        assert_eq!(
+            unsafe { (*producer.header).version },
+            WIRE_FORMAT_VERSION
+        );
+
+        // A producer built against another wire format
+        unsafe { (*(producer.header as *mut RingHeader)).version = WIRE_FORMAT_VERSION + 1 };
+        assert!(ZeroCopyRing::try_create_consumer(config).is_err());
+    }
+
+    #[test]
+    fn test_header_layout_matches_node_bridge() {
+        // Mirrored by MEMORY_LAYOUT in bindings/nodejs/src-js/ipc-bridge.js
+        assert_eq!(std::mem::offset_of!(RingHeader, read_pos), 12);
+        assert_eq!(std::mem::offset_of!(RingHeader, shutdown_flag), 64);
+        assert_eq!(std::mem::size_of::<RingHeader>(), 80);
+    }
+
+    #[test]
+    fn test_ipc_producer_reports_and_recovers() {
+        let dir = tempfile::tempdir().unwrap();
+        let shared_path = dir.path().join("missing/ring.mmap");
+        let config = ZeroCopyConfig {
+            memory_size: 1024 * 1024,
+            ring_capacity: 100,
+            shared_path: shared_path.clone(),
+            enable_notifications: false,
+            consumer_timeout_ms: 100,
+        };
+
+        let producer = IpcProducer::create(config);
+        assert!(!producer.is_available());
+        assert!(producer.ring().is_none());
+        let error = producer.last_error().unwrap();
+        assert!(error.contains(&shared_path.display().to_string()));
+
+        std::fs::create_dir_all(shared_path.parent().unwrap()).unwrap();
+        assert!(producer.retry());
+        assert!(producer.ring().is_some());
+        assert!(producer.last_error().is_none());
+    }
+
     #[test]
     fn test_zero_copy_ring_basic() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -708,13 +984,17 @@ mod tests {
                 timestamp: 123456789,
                 size: 1024,
                 is_directory: false,
+                metadata: None,
             },
             hash: Some(retrigger_core::HashResult {
                 hash: 0xDEADBEEF,
                 size: 1024,
                 is_incremental: false,
+                digest: None,
             }),
             processing_time_ns: 1000000,
+            metadata: None,
+            events_processed: None,
         };
 
         // Push event
@@ -735,6 +1015,67 @@ mod tests {
         assert_eq!(stats.used, 0);
     }
 
+    #[test]
+    fn test_quiesce_waits_for_consumer() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let config = ZeroCopyConfig {
+            memory_size: 1024 * 1024,
+            ring_capacity: 100,
+            shared_path: temp_file.path().to_path_buf(),
+            enable_notifications: false,
+            consumer_timeout_ms: 100,
+        };
+        let event = EnhancedFileEvent {
+            system_event: SystemEvent {
+                path: PathBuf::from("/test/in-flight.txt"),
+                event_type: SystemEventType::Modified,
+                timestamp: 1,
+                size: 0,
+                is_directory: false,
+                metadata: None,
+            },
+            hash: None,
+            processing_time_ns: 0,
+            metadata: None,
+            events_processed: None,
+        };
+
+        let producer = ZeroCopyRing::create_producer(config.clone()).unwrap();
+        let consumer = ZeroCopyRing::create_consumer(config).unwrap();
+        for _ in 0..3 {
+            assert!(producer.push(&event));
+        }
+
+        // Shutdown is only signaled once the reader has had every event
+        let reader = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            loop {
+                let shut_down = consumer.is_shutdown();
+                if consumer.pop().is_none() {
+                    break;
+                }
+                assert!(!shut_down);
+            }
+            consumer
+        });
+        assert!(producer.quiesce(Duration::from_secs(5)));
+        assert_eq!(producer.stats().used, 0);
+        assert!(producer.is_shutdown());
+        let consumer = reader.join().unwrap();
+
+        // A consumer that is not reading leaves the events abandoned
+        for _ in 0..3 {
+            assert!(producer.push(&event));
+        }
+        assert!(!producer.quiesce(Duration::from_millis(20)));
+        assert_eq!(producer.stats().used, 3);
+
+        // Without a consumer there is nothing to wait for
+        drop(consumer);
+        assert!(producer.push(&event));
+        assert!(!producer.quiesce(Duration::from_secs(5)));
+    }
+
     #[tokio::test]
     async fn test_ipc_manager() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -762,9 +1103,12 @@ mod tests {
                 timestamp: 987654321,
                 size: 512,
                 is_directory: false,
+                metadata: None,
             },
             hash: None,
             processing_time_ns: 500000,
+            metadata: None,
+            events_processed: None,
         };
 
         assert!(producer.push(&test_event));
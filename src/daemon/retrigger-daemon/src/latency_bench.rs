@@ -0,0 +1,212 @@
+//! End-to-end event latency benchmark
+//!
+//! Writes files whose content is the wall-clock time of the write, runs the
+//! resulting events through the daemon's own watcher → processor → IPC
+//! pipeline and times how long each takes to come out of the shared-memory
+//! ring. Reading the timestamp back from the file, rather than keeping it on
+//! the writer's side, pairs every event with its write even if events arrive
+//! out of order or more than once.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use retrigger_system::{FileEventProcessor, SystemWatcher};
+use tokio::sync::broadcast;
+
+use crate::config::{CompiledPatterns, PatternConfig};
+use crate::daemon::Daemon;
+use crate::ipc::{IpcProducer, ZeroCopyConfig, ZeroCopyRing};
+use crate::metrics::MetricsCollector;
+
+/// Benchmark files are named `latency-<n>.txt`
+const FILE_PREFIX: &str = "latency-";
+
+/// How long to keep waiting for events after the last write
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Latency percentiles over the delivered samples
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencySummary {
+    pub samples: usize,
+    pub min: Duration,
+    pub p50: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+    /// Samples delivered in under a millisecond
+    pub sub_millisecond: usize,
+}
+
+impl LatencySummary {
+    /// Summarize `latencies`; `None` if there are none
+    pub fn from_latencies(mut latencies: Vec<Duration>) -> Option<Self> {
+        latencies.sort_unstable();
+        Some(Self {
+            samples: latencies.len(),
+            min: *latencies.first()?,
+            p50: percentile(&latencies, 50.0),
+            p99: percentile(&latencies, 99.0),
+            max: *latencies.last()?,
+            sub_millisecond: latencies.partition_point(|l| *l < Duration::from_millis(1)),
+        })
+    }
+}
+
+/// Nearest-rank percentile of a sorted, non-empty slice
+fn percentile(sorted: &[Duration], percent: f64) -> Duration {
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Nanoseconds since the Unix epoch
+fn now_ns() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64
+}
+
+/// The write timestamp embedded in a benchmark file
+fn parse_timestamp(content: &str) -> Option<u64> {
+    content.trim().parse().ok()
+}
+
+/// Write `samples` files under `directory`, `interval` apart, and print the
+/// write-to-IPC-delivery latency distribution
+pub async fn run(directory: &Path, samples: usize, interval: Duration) -> Result<()> {
+    // Not a hidden directory, which the default patterns would exclude
+    let watch_dir = tempfile::Builder::new()
+        .prefix("retrigger-latency-")
+        .tempdir_in(directory)
+        .with_context(|| format!("Failed to create a directory in {}", directory.display()))?;
+    // The ring lives outside the watched directory so it generates no events
+    let ring_file = tempfile::NamedTempFile::new()?;
+
+    let watcher = SystemWatcher::new()?;
+    watcher.watch_directory(watch_dir.path(), true).await?;
+    watcher.start().await?;
+
+    let ipc_config = ZeroCopyConfig {
+        memory_size: 64 * 1024 * 1024,
+        ring_capacity: (samples * 4).max(1024),
+        shared_path: ring_file.path().to_path_buf(),
+        enable_notifications: true,
+        consumer_timeout_ms: 100,
+    };
+    let producer = Arc::new(IpcProducer::create(ipc_config.clone()));
+    if let Some(error) = producer.last_error() {
+        anyhow::bail!("Failed to create IPC ring: {error}");
+    }
+    let consumer = ZeroCopyRing::create_consumer(ipc_config)?;
+
+    let (enhanced_sender, _) = broadcast::channel(1024);
+    let processing = tokio::spawn(Daemon::event_processing_loop(
+        watcher.subscribe(),
+        Arc::new(FileEventProcessor::new()),
+        enhanced_sender,
+        Arc::new(MetricsCollector::new()),
+        CompiledPatterns::new(&PatternConfig::default())?,
+        producer,
+        None,
+        None,
+        None,
+    ));
+
+    // First delivery time of each benchmark file, read on a dedicated thread
+    // so a busy runtime does not delay the reads
+    let deadline = Instant::now() + interval * samples as u32 + DRAIN_TIMEOUT;
+    let reader = tokio::task::spawn_blocking(move || {
+        let mut delivered: HashMap<String, u64> = HashMap::with_capacity(samples);
+        while delivered.len() < samples && Instant::now() < deadline {
+            if !consumer.wait_for_events(10) {
+                continue;
+            }
+            while let Some(event) = consumer.pop() {
+                let received = now_ns();
+                let name = event.system_event.path.file_name();
+                if let Some(name) = name.and_then(|n| n.to_str()) {
+                    if name.starts_with(FILE_PREFIX) {
+                        delivered.entry(name.to_string()).or_insert(received);
+                    }
+                }
+            }
+        }
+        delivered
+    });
+
+    println!("Writing {samples} files {interval:?} apart...");
+    for i in 0..samples {
+        let path = watch_dir.path().join(format!("{FILE_PREFIX}{i}.txt"));
+        std::fs::write(&path, format!("{}\n", now_ns()))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        tokio::time::sleep(interval).await;
+    }
+
+    let delivered = reader.await?;
+    processing.abort();
+    watcher.stop().await?;
+
+    let mut latencies = Vec::with_capacity(delivered.len());
+    for (name, received) in &delivered {
+        let content = std::fs::read_to_string(watch_dir.path().join(name))?;
+        let written = parse_timestamp(&content)
+            .with_context(|| format!("{name} does not hold a timestamp"))?;
+        latencies.push(Duration::from_nanos(received.saturating_sub(written)));
+    }
+
+    println!("\nEvent Latency (write → IPC delivery)");
+    println!("====================================");
+    println!("Files written: {samples}");
+    println!("Events delivered: {}", delivered.len());
+    let Some(summary) = LatencySummary::from_latencies(latencies) else {
+        println!("No events were delivered within {DRAIN_TIMEOUT:?} of the last write");
+        return Ok(());
+    };
+    println!("min: {:?}", summary.min);
+    println!("p50: {:?}", summary.p50);
+    println!("p99: {:?}", summary.p99);
+    println!("max: {:?}", summary.max);
+    println!(
+        "Sub-millisecond: {}/{} ({:.1}%)",
+        summary.sub_millisecond,
+        summary.samples,
+        summary.sub_millisecond as f64 / summary.samples as f64 * 100.0
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_summary() {
+        assert_eq!(LatencySummary::from_latencies(Vec::new()), None);
+
+        // 1µs..=100µs shuffled, plus two slow outliers
+        let mut latencies: Vec<Duration> = (1..=100)
+            .rev()
+            .map(Duration::from_micros)
+            .chain([Duration::from_millis(5), Duration::from_millis(2)])
+            .collect();
+        latencies.swap(3, 70);
+
+        let summary = LatencySummary::from_latencies(latencies).unwrap();
+        assert_eq!(summary.samples, 102);
+        assert_eq!(summary.min, Duration::from_micros(1));
+        assert_eq!(summary.p50, Duration::from_micros(51));
+        assert_eq!(summary.p99, Duration::from_millis(2));
+        assert_eq!(summary.max, Duration::from_millis(5));
+        assert_eq!(summary.sub_millisecond, 100);
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let written = now_ns();
+        assert_eq!(parse_timestamp(&format!("{written}\n")), Some(written));
+        assert_eq!(parse_timestamp(""), None);
+        assert_eq!(parse_timestamp("not a timestamp"), None);
+    }
+}
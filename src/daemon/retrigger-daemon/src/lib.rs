@@ -7,8 +7,11 @@ pub mod config;
 pub mod daemon;
 pub mod grpc;
 pub mod ipc; // Zero-copy IPC module
+pub mod log_level;
 pub mod metrics; // Zero-copy public APIs
+pub mod resources;
 
 pub use config::{ConfigManager, DaemonConfig};
-pub use daemon::{Daemon, DaemonStats};
+pub use daemon::{Daemon, DaemonStats, MemoryEstimate};
 pub use ipc::{RingStats, ZeroCopyConfig, ZeroCopyRing};
+pub use resources::ResourceStats;
@@ -0,0 +1,105 @@
+//! Runtime log level control
+//!
+//! The daemon's `EnvFilter` sits behind a `tracing_subscriber` reload layer,
+//! so its directives can be swapped while the daemon runs, from the
+//! SetLogLevel RPC or by sending the process SIGUSR1. The swap is atomic and
+//! applies to the next log record; the fmt layer and any other subscriber
+//! layers stay installed throughout.
+
+use anyhow::{Context, Result};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+/// Levels SIGUSR1 cycles through, least to most verbose
+const CYCLE: [LevelFilter; 5] = [
+    LevelFilter::ERROR,
+    LevelFilter::WARN,
+    LevelFilter::INFO,
+    LevelFilter::DEBUG,
+    LevelFilter::TRACE,
+];
+
+/// Handle for changing the installed `EnvFilter`
+#[derive(Clone)]
+pub struct LogLevelControl {
+    handle: reload::Handle<EnvFilter, Registry>,
+}
+
+impl LogLevelControl {
+    /// Wrap `filter` in a reload layer; install the layer directly on the
+    /// `Registry` and keep the control to change the filter later
+    pub fn new(filter: EnvFilter) -> (Self, reload::Layer<EnvFilter, Registry>) {
+        let (layer, handle) = reload::Layer::new(filter);
+        (Self { handle }, layer)
+    }
+
+    /// The active filter directives, e.g. `info` or `retrigger=debug,warn`
+    pub fn current(&self) -> Result<String> {
+        self.handle
+            .with_current(|filter| filter.to_string())
+            .with_context(|| "Log subscriber is no longer installed")
+    }
+
+    /// Replace the filter with `directives`, in `RUST_LOG` syntax
+    pub fn set_level(&self, directives: &str) -> Result<String> {
+        if directives.trim().is_empty() {
+            anyhow::bail!("Log level must not be empty");
+        }
+        let filter = EnvFilter::try_new(directives)
+            .with_context(|| format!("Invalid log level: {directives}"))?;
+        self.handle
+            .reload(filter)
+            .with_context(|| "Log subscriber is no longer installed")?;
+        self.current()
+    }
+
+    /// Switch to the next more verbose level, wrapping from trace to error
+    ///
+    /// Per-target directives are replaced by the single global level.
+    pub fn cycle(&self) -> Result<LevelFilter> {
+        let current = self
+            .handle
+            .with_current(|filter| filter.max_level_hint())
+            .with_context(|| "Log subscriber is no longer installed")?;
+        let next = CYCLE
+            .iter()
+            .position(|level| Some(*level) == current)
+            .map_or(CYCLE[0], |i| CYCLE[(i + 1) % CYCLE.len()]);
+        self.handle
+            .reload(EnvFilter::new(next.to_string()))
+            .with_context(|| "Log subscriber is no longer installed")?;
+        Ok(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::Level;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_level_changes_apply_immediately() {
+        let (control, layer) = LogLevelControl::new(EnvFilter::new("info"));
+        let dispatch = tracing::Dispatch::new(tracing_subscriber::registry().with(layer));
+        let debug_enabled =
+            || tracing::dispatcher::with_default(&dispatch, || tracing::enabled!(Level::DEBUG));
+
+        assert!(!debug_enabled());
+        assert_eq!(control.set_level("debug").unwrap(), "debug");
+        assert!(debug_enabled());
+
+        // A rejected level leaves the filter alone
+        assert!(control.set_level("").is_err());
+        assert!(control.set_level("retrigger=loud").is_err());
+        assert_eq!(control.current().unwrap(), "debug");
+
+        assert_eq!(control.cycle().unwrap(), LevelFilter::TRACE);
+        assert_eq!(control.cycle().unwrap(), LevelFilter::ERROR);
+        assert!(!debug_enabled());
+        assert_eq!(control.cycle().unwrap(), LevelFilter::WARN);
+
+        drop(dispatch);
+        assert!(control.set_level("info").is_err());
+    }
+}
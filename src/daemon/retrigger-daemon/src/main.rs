@@ -9,6 +9,7 @@ use std::time::Duration;
 use anyhow::{Context, Result};
 use clap::{Args, Parser, Subcommand};
 use metrics_exporter_prometheus::PrometheusBuilder;
+use retrigger_core::{FastHash, HashEngine, HashStrategy};
 use retrigger_system::{FileEventProcessor, SystemWatcher};
 use tokio::signal;
 use tracing::{info, warn};
@@ -19,10 +20,15 @@ mod config;
 mod daemon;
 mod grpc;
 mod ipc; // Zero-copy IPC module
+mod latency_bench;
+mod log_level;
 mod metrics; // Zero-copy public APIs
+mod resources;
+mod strategy_bench;
 
 use config::{ConfigManager, DaemonConfig};
 use daemon::Daemon;
+use log_level::LogLevelControl;
 
 /// Retrigger - High-performance file system watcher
 #[derive(Parser)]
@@ -48,6 +54,8 @@ enum Commands {
     Config(ConfigArgs),
     /// Run benchmarks
     Benchmark(BenchmarkArgs),
+    /// Hash files, directories or stdin
+    Hash(HashArgs),
 }
 
 #[derive(Args)]
@@ -111,6 +119,51 @@ struct BenchmarkArgs {
     /// File size in bytes
     #[arg(short, long, default_value = "1024")]
     size: usize,
+
+    /// Benchmark hash strategies on files already in the directory, grouped
+    /// by extension, and recommend one per extension
+    #[arg(long)]
+    by_extension: bool,
+
+    /// Files sampled per extension with --by-extension
+    #[arg(long, default_value = "20")]
+    samples: usize,
+
+    /// Hashing passes over each extension's samples with --by-extension
+    #[arg(long, default_value = "5")]
+    iterations: usize,
+
+    /// Measure write-to-delivery latency through the watcher, processor and
+    /// IPC ring, writing --files files one at a time
+    #[arg(long)]
+    latency: bool,
+
+    /// Milliseconds between writes with --latency
+    #[arg(long, default_value = "10")]
+    interval_ms: u64,
+}
+
+#[derive(Args)]
+struct HashArgs {
+    /// Files or directories to hash; `-` reads stdin
+    #[arg(required = true)]
+    paths: Vec<PathBuf>,
+
+    /// blake3_only (matches b3sum), xxh3_only, hybrid or auto
+    #[arg(long, default_value = "blake3_only", value_parser = parse_hash_strategy)]
+    strategy: HashStrategy,
+}
+
+fn parse_hash_strategy(name: &str) -> Result<HashStrategy, String> {
+    [
+        HashStrategy::Blake3Only,
+        HashStrategy::Xxh3Only,
+        HashStrategy::Hybrid,
+        HashStrategy::Auto,
+    ]
+    .into_iter()
+    .find(|strategy| strategy.as_str() == name)
+    .ok_or_else(|| format!("unknown hash strategy {name:?}"))
 }
 
 #[tokio::main]
@@ -124,13 +177,14 @@ async fn main() -> Result<()> {
         Commands::Validate(args) => validate_config(args).await,
         Commands::Config(args) => generate_config(args).await,
         Commands::Benchmark(args) => run_benchmark(args).await,
+        Commands::Hash(args) => hash_paths(args),
     }
 }
 
 /// Start the Retrigger daemon
 async fn start_daemon(args: StartArgs) -> Result<()> {
     // Initialize tracing
-    init_tracing(&args)?;
+    let log_level = init_tracing(&args)?;
 
     info!("Starting Retrigger daemon v{}", env!("CARGO_PKG_VERSION"));
 
@@ -159,6 +213,11 @@ async fn start_daemon(args: StartArgs) -> Result<()> {
     // Validate configuration
     ConfigManager::validate(&config)?;
 
+    // Fragments in the `include` directory reload through their own watcher
+    if args.config.exists() {
+        config_manager.watch_include_dir().await?;
+    }
+
     // Start hot-reload if config file exists
     // TEMPORARY: Disable hot-reload to debug startup hang
     // if args.config.exists() {
@@ -171,7 +230,7 @@ async fn start_daemon(args: StartArgs) -> Result<()> {
     }
 
     // Create and start daemon
-    let daemon = Daemon::new(config_manager).await?;
+    let daemon = Daemon::new(config_manager).await?.with_log_level(log_level);
 
     if args.foreground {
         // Run in foreground
@@ -251,6 +310,22 @@ async fn generate_config(args: ConfigArgs) -> Result<()> {
 
 /// Run performance benchmarks
 async fn run_benchmark(args: BenchmarkArgs) -> Result<()> {
+    if args.by_extension {
+        info!(
+            "Benchmarking hash strategies by extension in {}",
+            args.directory.display()
+        );
+        return strategy_bench::run(&args.directory, args.samples, args.iterations);
+    }
+    if args.latency {
+        info!(
+            "Benchmarking end-to-end event latency in {}",
+            args.directory.display()
+        );
+        let interval = Duration::from_millis(args.interval_ms);
+        return latency_bench::run(&args.directory, args.files, interval).await;
+    }
+
     info!("Running Retrigger benchmarks");
     info!("Directory: {}", args.directory.display());
     info!("Files: {}, Size: {} bytes", args.files, args.size);
@@ -349,19 +424,62 @@ async fn run_benchmark(args: BenchmarkArgs) -> Result<()> {
     Ok(())
 }
 
-/// Initialize tracing/logging
-fn init_tracing(args: &StartArgs) -> Result<()> {
+/// Print a `sha256sum`-style `<digest>  <path>` line per hashed file
+///
+/// `-` streams stdin through the engine without buffering it all, so the
+/// command can sit at the end of a shell pipeline.
+fn hash_paths(args: HashArgs) -> Result<()> {
+    let engine = HashEngine::with_strategy(args.strategy);
+    let mut failed = 0;
+
+    for path in &args.paths {
+        if path.as_os_str() == "-" {
+            let hash = engine
+                .hash_reader(std::io::stdin().lock())
+                .with_context(|| "Failed to hash stdin")?;
+            println!("{}  -", hash.to_hex());
+        } else if path.is_dir() {
+            let tree = engine
+                .hash_directory(path, None)
+                .with_context(|| format!("Failed to hash {}", path.display()))?;
+            for (file, hash) in &tree.files {
+                println!("{}  {}", hash.to_hex(), file.display());
+            }
+            for file in &tree.failed {
+                eprintln!("retrigger: {}: could not be read", file.display());
+            }
+            failed += tree.failed.len();
+        } else {
+            match engine.hash_file(path) {
+                Ok(hash) => println!("{}  {}", hash.to_hex(), path.display()),
+                Err(e) => {
+                    eprintln!("retrigger: {}: {e}", path.display());
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{failed} file(s) could not be hashed");
+    }
+    Ok(())
+}
+
+/// Initialize tracing/logging, returning the handle for changing the level
+fn init_tracing(args: &StartArgs) -> Result<LogLevelControl> {
     let level = if args.debug { "debug" } else { "info" };
 
     let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(level));
+    let (log_level, env_filter) = LogLevelControl::new(env_filter);
 
     tracing_subscriber::registry()
         .with(env_filter)
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    Ok(())
+    Ok(log_level)
 }
 
 /// Initialize Prometheus metrics
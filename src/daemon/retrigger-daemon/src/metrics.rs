@@ -1,18 +1,28 @@
 //! Metrics collection and reporting
 //! Follows SRP: Only responsible for metrics collection and export
 
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
 use metrics::{counter, gauge, histogram};
-use retrigger_system::{EnhancedFileEvent, WatcherStats};
+use retrigger_system::{DropCounters, DropReason, EnhancedFileEvent, WatcherStats};
+
+use crate::daemon::MemoryEstimate;
+use crate::resources::ResourceStats;
 
 /// Metrics collector for daemon statistics
 pub struct MetricsCollector {
     start_time: Instant,
     events_processed: AtomicU64,
     errors_count: AtomicU64,
+    lagged_events: AtomicU64,
     total_processing_time_ns: AtomicU64,
+    /// Events dropped inside the daemon, after the watcher delivered them
+    drops: DropCounters,
+    /// Watcher drop counts already added to `retrigger_events_dropped_total`
+    watcher_drops_seen: Mutex<BTreeMap<DropReason, u64>>,
 }
 
 impl MetricsCollector {
@@ -22,7 +32,10 @@ impl MetricsCollector {
             start_time: Instant::now(),
             events_processed: AtomicU64::new(0),
             errors_count: AtomicU64::new(0),
+            lagged_events: AtomicU64::new(0),
             total_processing_time_ns: AtomicU64::new(0),
+            drops: DropCounters::new(),
+            watcher_drops_seen: Mutex::new(BTreeMap::new()),
         }
     }
 
@@ -44,6 +57,8 @@ impl MetricsCollector {
             retrigger_system::SystemEventType::Deleted => "deleted",
             retrigger_system::SystemEventType::Moved => "moved",
             retrigger_system::SystemEventType::MetadataChanged => "metadata_changed",
+            retrigger_system::SystemEventType::StabilizedModified => "stabilized_modified",
+            retrigger_system::SystemEventType::Heartbeat => "heartbeat",
         };
         counter!("retrigger_events_by_type_total", "type" => event_type).increment(1);
 
@@ -69,6 +84,58 @@ impl MetricsCollector {
         self.errors_count.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Record events skipped because the event loop fell behind the channel
+    pub fn record_lagged_events(&self, count: u64) {
+        counter!("retrigger_lagged_events_total").increment(count);
+        self.lagged_events.fetch_add(count, Ordering::Relaxed);
+        self.record_dropped(DropReason::Lagged, count);
+    }
+
+    /// Record events the daemon discarded, labelled with why
+    pub fn record_dropped(&self, reason: DropReason, count: u64) {
+        counter!("retrigger_events_dropped_total", "reason" => reason.as_str()).increment(count);
+        self.drops.record_many(reason, count);
+    }
+
+    /// Update the estimated memory footprint gauges
+    pub fn update_memory_estimate(&self, estimate: &MemoryEstimate) {
+        let components = [
+            ("hash_cache", estimate.hash_cache_bytes),
+            ("directory_cache", estimate.directory_cache_bytes),
+            ("event_channels", estimate.event_channel_bytes),
+            ("ipc_ring", estimate.ipc_ring_bytes),
+        ];
+        for (component, bytes) in components {
+            gauge!("retrigger_memory_estimate_bytes", "component" => component).set(bytes as f64);
+        }
+        gauge!("retrigger_memory_estimate_total_bytes").set(estimate.total_bytes() as f64);
+    }
+
+    /// Update the process resource usage gauges; unknown values are skipped
+    pub fn update_resource_stats(&self, stats: &ResourceStats) {
+        let gauges = [
+            ("retrigger_open_fds", stats.open_fds),
+            ("retrigger_fd_limit", stats.fd_limit.and_then(|l| l.soft)),
+            ("retrigger_inotify_instances", stats.inotify_instances),
+            ("retrigger_inotify_watches", stats.inotify_watches),
+            (
+                "retrigger_inotify_max_user_watches",
+                stats.inotify_max_user_watches,
+            ),
+            ("retrigger_rss_bytes", stats.rss_bytes),
+        ];
+        for (name, value) in gauges {
+            if let Some(value) = value {
+                gauge!(name).set(value as f64);
+            }
+        }
+    }
+
+    /// Record a heartbeat sent while no events were arriving
+    pub fn record_heartbeat(&self) {
+        counter!("retrigger_heartbeats_total").increment(1);
+    }
+
     /// Record batch processing metrics
     pub fn record_batch_processing(&self, batch_size: usize, processing_time: Duration) {
         histogram!("retrigger_batch_processing_duration").record(processing_time.as_nanos() as f64);
@@ -85,6 +152,22 @@ impl MetricsCollector {
         gauge!("retrigger_buffer_capacity").set(stats.buffer_capacity as f64);
         gauge!("retrigger_dropped_events").set(stats.dropped_events as f64);
         gauge!("retrigger_watched_directories").set(stats.watched_directories as f64);
+        gauge!("retrigger_skipped_mount_points").set(stats.skipped_mount_points as f64);
+        gauge!("retrigger_skipped_large_dirs").set(stats.skipped_large_dirs.len() as f64);
+        gauge!("retrigger_debounce_entries").set(stats.debounce_entries as f64);
+
+        // The watcher keeps running totals; add what is new since last time
+        let mut seen = self
+            .watcher_drops_seen
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        for (&reason, &count) in &stats.dropped_by_reason {
+            let previous = seen.insert(reason, count).unwrap_or(0);
+            if count > previous {
+                counter!("retrigger_events_dropped_total", "reason" => reason.as_str())
+                    .increment(count - previous);
+            }
+        }
 
         // Calculate buffer utilization percentage
         let utilization = if stats.buffer_capacity > 0 {
@@ -109,13 +192,25 @@ impl MetricsCollector {
         gauge!("retrigger_hash_cache_utilization_percent").set(utilization);
     }
 
+    /// Report whether the IPC ring is available to consumers
+    pub fn update_ipc_status(&self, available: bool) {
+        gauge!("retrigger_ipc_available").set(if available { 1.0 } else { 0.0 });
+    }
+
+    /// Events processed since startup
+    pub fn events_processed(&self) -> u64 {
+        self.events_processed.load(Ordering::Relaxed)
+    }
+
     /// Get current statistics
     pub fn get_stats(&self) -> MetricsStats {
         MetricsStats {
             uptime_seconds: self.start_time.elapsed().as_secs(),
             events_processed: self.events_processed.load(Ordering::Relaxed),
             errors_count: self.errors_count.load(Ordering::Relaxed),
+            lagged_events: self.lagged_events.load(Ordering::Relaxed),
             total_processing_time_ns: self.total_processing_time_ns.load(Ordering::Relaxed),
+            dropped_by_reason: self.drops.snapshot(),
         }
     }
 
@@ -156,7 +251,12 @@ pub struct MetricsStats {
     pub uptime_seconds: u64,
     pub events_processed: u64,
     pub errors_count: u64,
+    /// Events the processing loop missed because it fell behind
+    pub lagged_events: u64,
     pub total_processing_time_ns: u64,
+    /// Events dropped inside the daemon by reason; the watcher's own drops
+    /// are in `WatcherStats::dropped_by_reason`
+    pub dropped_by_reason: BTreeMap<DropReason, u64>,
 }
 
 #[cfg(test)]
@@ -176,12 +276,15 @@ mod tests {
             timestamp: 1234567890,
             size: 1024,
             is_directory: false,
+            metadata: None,
         };
 
         let enhanced_event = EnhancedFileEvent {
             system_event,
             hash: None,
             processing_time_ns: 1_000_000, // 1ms
+            metadata: None,
+            events_processed: None,
         };
 
         // Record event
@@ -204,12 +307,15 @@ mod tests {
                 timestamp: 1234567890 + i,
                 size: 1024,
                 is_directory: false,
+                metadata: None,
             };
 
             let enhanced_event = EnhancedFileEvent {
                 system_event,
                 hash: None,
                 processing_time_ns: (i + 1) * 1_000_000, // Variable processing time
+                metadata: None,
+                events_processed: None,
             };
 
             collector.record_event(&enhanced_event);
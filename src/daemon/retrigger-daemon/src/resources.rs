@@ -0,0 +1,249 @@
+//! Process resource usage, for spotting OS limits before they are hit
+//!
+//! Watching a large tree exhausts file descriptors and inotify watches long
+//! before it runs short of CPU or memory, and the failure then surfaces as
+//! a watch that silently did not register. [`ResourceStats::collect`] reads
+//! current usage and the matching limits: from `/proc` and `getrlimit` on
+//! Linux, and best-effort elsewhere (rlimits on any Unix, the descriptor
+//! count where `/dev/fd` lists it). Anything the platform cannot report is
+//! `None`.
+
+use std::fmt;
+
+/// Usage at or above this fraction of its limit counts as near the limit
+pub const NEAR_LIMIT_FRACTION: f64 = 0.8;
+
+/// Soft and hard values of an rlimit; `None` means unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceLimit {
+    pub soft: Option<u64>,
+    pub hard: Option<u64>,
+}
+
+/// Descriptor, inotify and memory usage of the daemon process
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceStats {
+    pub open_fds: Option<u64>,
+    /// `RLIMIT_NOFILE`
+    pub fd_limit: Option<ResourceLimit>,
+    /// inotify instances held by this process
+    pub inotify_instances: Option<u64>,
+    /// Watches across this process's inotify instances
+    pub inotify_watches: Option<u64>,
+    /// `fs.inotify.max_user_instances`, shared by all of the user's processes
+    pub inotify_max_user_instances: Option<u64>,
+    /// `fs.inotify.max_user_watches`, shared by all of the user's processes
+    pub inotify_max_user_watches: Option<u64>,
+    /// Resident set size
+    pub rss_bytes: Option<u64>,
+    /// `RLIMIT_AS`, which bounds virtual rather than resident memory
+    pub address_space_limit: Option<ResourceLimit>,
+}
+
+/// A resource whose usage has reached a large fraction of its limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourcePressure {
+    pub resource: &'static str,
+    pub used: u64,
+    pub limit: u64,
+}
+
+impl fmt::Display for ResourcePressure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} of {} ({:.0}%)",
+            self.resource,
+            self.used,
+            self.limit,
+            self.used as f64 / self.limit as f64 * 100.0
+        )
+    }
+}
+
+impl ResourceStats {
+    /// Read current usage and limits; never fails, missing values are `None`
+    pub fn collect() -> Self {
+        let mut stats = Self {
+            fd_limit: rlimit(Rlimit::OpenFiles),
+            address_space_limit: rlimit(Rlimit::AddressSpace),
+            ..Self::default()
+        };
+        stats.collect_platform();
+        stats
+    }
+
+    #[cfg(target_os = "linux")]
+    fn collect_platform(&mut self) {
+        let read = |path: &str| std::fs::read_to_string(path).ok();
+
+        if let Ok(entries) = std::fs::read_dir("/proc/self/fd") {
+            let (mut fds, mut instances, mut watches) = (0u64, 0u64, 0u64);
+            for entry in entries.flatten() {
+                fds += 1;
+                let target = std::fs::read_link(entry.path()).unwrap_or_default();
+                if target.as_os_str() != "anon_inode:inotify" {
+                    continue;
+                }
+                instances += 1;
+                let fdinfo = format!("/proc/self/fdinfo/{}", entry.file_name().to_string_lossy());
+                watches += read(&fdinfo).map_or(0, |info| count_inotify_watches(&info));
+            }
+            // Listing the directory holds a descriptor of its own
+            self.open_fds = Some(fds.saturating_sub(1));
+            self.inotify_instances = Some(instances);
+            self.inotify_watches = Some(watches);
+        }
+
+        let parse = |path: &str| read(path).and_then(|value| value.trim().parse().ok());
+        self.inotify_max_user_instances = parse("/proc/sys/fs/inotify/max_user_instances");
+        self.inotify_max_user_watches = parse("/proc/sys/fs/inotify/max_user_watches");
+
+        // SAFETY: sysconf has no preconditions
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        self.rss_bytes = read("/proc/self/statm")
+            .and_then(|statm| parse_statm_rss_pages(&statm))
+            .zip(u64::try_from(page_size).ok())
+            .map(|(pages, page_size)| pages * page_size);
+    }
+
+    #[cfg(all(unix, not(target_os = "linux")))]
+    fn collect_platform(&mut self) {
+        if let Ok(entries) = std::fs::read_dir("/dev/fd") {
+            // Listing the directory holds a descriptor of its own
+            self.open_fds = Some((entries.count() as u64).saturating_sub(1));
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn collect_platform(&mut self) {}
+
+    /// Resources whose usage is at least `fraction` of their limit
+    pub fn near_limits(&self, fraction: f64) -> Vec<ResourcePressure> {
+        let candidates = [
+            (
+                "open file descriptors",
+                self.open_fds,
+                self.fd_limit.and_then(|limit| limit.soft),
+            ),
+            (
+                "inotify instances",
+                self.inotify_instances,
+                self.inotify_max_user_instances,
+            ),
+            (
+                "inotify watches",
+                self.inotify_watches,
+                self.inotify_max_user_watches,
+            ),
+        ];
+
+        candidates
+            .into_iter()
+            .filter_map(|(resource, used, limit)| {
+                let (used, limit) = (used?, limit?);
+                (limit > 0 && used as f64 >= limit as f64 * fraction).then_some(ResourcePressure {
+                    resource,
+                    used,
+                    limit,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Watches listed in an inotify descriptor's `/proc/self/fdinfo` entry
+fn count_inotify_watches(fdinfo: &str) -> u64 {
+    fdinfo
+        .lines()
+        .filter(|line| line.starts_with("inotify wd:"))
+        .count() as u64
+}
+
+/// Resident pages, the second field of `/proc/self/statm`
+fn parse_statm_rss_pages(statm: &str) -> Option<u64> {
+    statm.split_whitespace().nth(1)?.parse().ok()
+}
+
+enum Rlimit {
+    OpenFiles,
+    AddressSpace,
+}
+
+#[cfg(unix)]
+fn rlimit(resource: Rlimit) -> Option<ResourceLimit> {
+    let resource = match resource {
+        Rlimit::OpenFiles => libc::RLIMIT_NOFILE,
+        Rlimit::AddressSpace => libc::RLIMIT_AS,
+    };
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: `limit` is a valid, writable rlimit
+    if unsafe { libc::getrlimit(resource, &mut limit) } != 0 {
+        return None;
+    }
+    // `rlim_t` is `u64` on Linux but not on every Unix
+    #[allow(clippy::unnecessary_cast)]
+    let value = |v: libc::rlim_t| (v != libc::RLIM_INFINITY).then_some(v as u64);
+    Some(ResourceLimit {
+        soft: value(limit.rlim_cur),
+        hard: value(limit.rlim_max),
+    })
+}
+
+#[cfg(not(unix))]
+fn rlimit(_resource: Rlimit) -> Option<ResourceLimit> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_near_limits() {
+        let stats = ResourceStats {
+            open_fds: Some(900),
+            fd_limit: Some(ResourceLimit {
+                soft: Some(1024),
+                hard: Some(4096),
+            }),
+            inotify_instances: Some(1),
+            inotify_watches: Some(10_000),
+            inotify_max_user_instances: Some(128),
+            inotify_max_user_watches: Some(8192),
+            ..ResourceStats::default()
+        };
+
+        let near = stats.near_limits(NEAR_LIMIT_FRACTION);
+        let resources: Vec<&str> = near.iter().map(|p| p.resource).collect();
+        assert_eq!(resources, ["open file descriptors", "inotify watches"]);
+        assert_eq!(
+            near[0].to_string(),
+            "open file descriptors: 900 of 1024 (88%)"
+        );
+
+        // Unknown usage or an unlimited limit is never near
+        assert!(ResourceStats::default().near_limits(0.0).is_empty());
+
+        let info = "pos:\t0\nflags:\t00\ninotify wd:1 ino:2 sdev:3 mask:fce\ninotify wd:2 ino:5 sdev:3 mask:fce\n";
+        assert_eq!(count_inotify_watches(info), 2);
+        assert_eq!(
+            parse_statm_rss_pages("4210 1536 800 1 0 900 0\n"),
+            Some(1536)
+        );
+        assert_eq!(parse_statm_rss_pages(""), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_collect_reads_proc() {
+        let _file = tempfile::tempfile().unwrap();
+        let stats = ResourceStats::collect();
+        assert!(stats.open_fds.unwrap() > 0);
+        assert!(stats.rss_bytes.unwrap() > 0);
+        assert!(stats.fd_limit.is_some());
+    }
+}
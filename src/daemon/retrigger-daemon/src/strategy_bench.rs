@@ -0,0 +1,230 @@
+//! Per-extension hash strategy benchmark over the user's own files
+//!
+//! Samples files under a directory, groups them by extension and times
+//! BLAKE3 and XXH3 on each group's samples, so the strategy for a file type
+//! can be chosen from measurements on real content instead of synthetic data.
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use retrigger_core::{walk_directory, HashEngine, HashStrategy, WalkOptions};
+
+/// Only this much of each sampled file is read; throughput is per byte, so a
+/// prefix is representative and huge media files stay cheap to sample
+const MAX_SAMPLE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Above this XXH3 collision probability across a group, recommend BLAKE3
+const NEGLIGIBLE_COLLISION_PROBABILITY: f64 = 1e-9;
+
+/// Files sharing an extension
+#[derive(Debug)]
+pub struct ExtensionGroup {
+    /// Lowercased with a leading dot, or `(none)`
+    pub extension: String,
+    /// Files with this extension in the whole directory
+    pub total_files: usize,
+    /// Evenly spaced subset of the files that gets benchmarked
+    pub samples: Vec<PathBuf>,
+}
+
+/// Timings for one group
+#[derive(Debug)]
+pub struct GroupTiming {
+    pub bytes: u64,
+    pub blake3: Duration,
+    pub xxh3: Duration,
+}
+
+impl GroupTiming {
+    fn throughput_mb_s(&self, elapsed: Duration) -> f64 {
+        self.bytes as f64 / 1_000_000.0 / elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// Group every file under `root` by extension, keeping up to
+/// `samples_per_group` evenly spaced samples of each
+pub fn sample_by_extension(root: &Path, samples_per_group: usize) -> Result<Vec<ExtensionGroup>> {
+    let walk = walk_directory(root, &WalkOptions::default())
+        .with_context(|| format!("Failed to scan {}", root.display()))?;
+
+    let mut by_extension: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for path in walk.files {
+        let extension = path
+            .extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy().to_lowercase()))
+            .unwrap_or_else(|| "(none)".to_string());
+        by_extension.entry(extension).or_default().push(path);
+    }
+
+    let mut groups: Vec<ExtensionGroup> = by_extension
+        .into_iter()
+        .map(|(extension, files)| {
+            let step = files.len().div_ceil(samples_per_group.max(1));
+            ExtensionGroup {
+                extension,
+                total_files: files.len(),
+                samples: files.into_iter().step_by(step.max(1)).collect(),
+            }
+        })
+        .collect();
+    groups.sort_by_key(|group| std::cmp::Reverse(group.total_files));
+    Ok(groups)
+}
+
+/// Time both strategies over `iterations` passes of the group's samples
+pub fn time_group(
+    engine: &HashEngine,
+    group: &ExtensionGroup,
+    iterations: usize,
+) -> Result<GroupTiming> {
+    let mut contents = Vec::with_capacity(group.samples.len());
+    for path in &group.samples {
+        let mut data = Vec::new();
+        std::fs::File::open(path)
+            .and_then(|file| file.take(MAX_SAMPLE_BYTES).read_to_end(&mut data))
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        contents.push(data);
+    }
+
+    let time = |strategy| -> Result<Duration> {
+        let start = Instant::now();
+        for _ in 0..iterations {
+            for data in &contents {
+                std::hint::black_box(engine.hash_bytes_with_strategy(data, strategy)?);
+            }
+        }
+        Ok(start.elapsed())
+    };
+
+    Ok(GroupTiming {
+        bytes: contents.iter().map(|data| data.len() as u64).sum::<u64>() * iterations as u64,
+        blake3: time(HashStrategy::Blake3Only)?,
+        xxh3: time(HashStrategy::Xxh3Only)?,
+    })
+}
+
+/// Birthday-bound probability that any two of `files` distinct files share a
+/// 64-bit XXH3 hash
+fn xxh3_collision_probability(files: usize) -> f64 {
+    let n = files as f64;
+    n * (n - 1.0) / 2f64.powi(65)
+}
+
+/// Recommended strategy for a group and why
+pub fn recommend(timing: &GroupTiming, total_files: usize) -> (HashStrategy, String) {
+    let speedup = timing.blake3.as_secs_f64() / timing.xxh3.as_secs_f64().max(f64::EPSILON);
+    let collision = xxh3_collision_probability(total_files);
+
+    if speedup <= 1.0 {
+        let blake3_speedup = 1.0 / speedup.max(f64::EPSILON);
+        return (
+            HashStrategy::Blake3Only,
+            format!("{blake3_speedup:.1}x faster than XXH3"),
+        );
+    }
+    if collision > NEGLIGIBLE_COLLISION_PROBABILITY {
+        return (
+            HashStrategy::Blake3Only,
+            format!(
+                "XXH3 is {speedup:.1}x faster, but collision risk is {collision:.0e} across {total_files} files"
+            ),
+        );
+    }
+    (
+        HashStrategy::Xxh3Only,
+        format!("{speedup:.1}x faster, collision risk negligible at your scale"),
+    )
+}
+
+/// Sample `root`, benchmark each extension and print a recommendation table
+pub fn run(root: &Path, samples_per_group: usize, iterations: usize) -> Result<()> {
+    let groups = sample_by_extension(root, samples_per_group)?;
+    if groups.is_empty() {
+        println!("No files found under {}", root.display());
+        return Ok(());
+    }
+
+    let engine = HashEngine::new();
+    println!("\nHash Strategy by Extension");
+    println!("==========================");
+    println!(
+        "{:<12} {:>8} {:>8} {:>12} {:>12}  Recommendation",
+        "Extension", "Files", "Sampled", "BLAKE3 MB/s", "XXH3 MB/s"
+    );
+
+    for group in &groups {
+        let timing = time_group(&engine, group, iterations)?;
+        let (strategy, reason) = recommend(&timing, group.total_files);
+        let name = match strategy {
+            HashStrategy::Xxh3Only => "XXH3",
+            _ => "BLAKE3",
+        };
+        println!(
+            "{:<12} {:>8} {:>8} {:>12.1} {:>12.1}  {} → {} ({})",
+            group.extension,
+            group.total_files,
+            group.samples.len(),
+            timing.throughput_mb_s(timing.blake3),
+            timing.throughput_mb_s(timing.xxh3),
+            group.extension,
+            name,
+            reason
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_by_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..10 {
+            std::fs::write(dir.path().join(format!("clip{i}.MP4")), b"video").unwrap();
+        }
+        std::fs::write(dir.path().join("main.rs"), b"fn main() {}").unwrap();
+        std::fs::write(dir.path().join("Makefile"), b"all:").unwrap();
+
+        let groups = sample_by_extension(dir.path(), 4).unwrap();
+        assert_eq!(groups[0].extension, ".mp4");
+        assert_eq!(groups[0].total_files, 10);
+        assert!(groups[0].samples.len() <= 4);
+
+        let extensions: Vec<&str> = groups.iter().map(|g| g.extension.as_str()).collect();
+        assert!(extensions.contains(&".rs") && extensions.contains(&"(none)"));
+
+        let timing = time_group(&HashEngine::new(), &groups[0], 2).unwrap();
+        assert_eq!(timing.bytes, 5 * groups[0].samples.len() as u64 * 2);
+    }
+
+    #[test]
+    fn test_recommendation() {
+        let timing = |blake3_ms, xxh3_ms| GroupTiming {
+            bytes: 1_000_000,
+            blake3: Duration::from_millis(blake3_ms),
+            xxh3: Duration::from_millis(xxh3_ms),
+        };
+
+        let (strategy, reason) = recommend(&timing(32, 10), 1_000);
+        assert_eq!(strategy, HashStrategy::Xxh3Only);
+        assert_eq!(
+            reason,
+            "3.2x faster, collision risk negligible at your scale"
+        );
+
+        assert_eq!(
+            recommend(&timing(10, 20), 1_000).0,
+            HashStrategy::Blake3Only
+        );
+        // Billions of files make 64-bit collisions plausible
+        assert_eq!(
+            recommend(&timing(32, 10), 1_000_000_000).0,
+            HashStrategy::Blake3Only
+        );
+    }
+}
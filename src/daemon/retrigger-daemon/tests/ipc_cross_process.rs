@@ -0,0 +1,144 @@
+//! The IPC ring with producer and consumer in separate processes
+//!
+//! The unit tests in `ipc` map both ends in one process, where they share a
+//! PID and the eventfds. Here the test binary re-executes itself as the
+//! consumer, so attachment, ordering across ring wraparound, shutdown and
+//! detachment are checked the way an out-of-process client sees them.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use retrigger_daemon::{ZeroCopyConfig, ZeroCopyRing};
+use retrigger_system::{EnhancedFileEvent, SystemEvent, SystemEventType};
+
+/// Set in the child to the shared file it should consume from
+const CONSUMER_ENV: &str = "RETRIGGER_IPC_TEST_CONSUMER";
+
+/// Events sent; several times the ring capacity, so it wraps and fills
+const EVENT_COUNT: usize = 200;
+
+fn config(shared_path: &Path) -> ZeroCopyConfig {
+    ZeroCopyConfig {
+        memory_size: 1024 * 1024,
+        ring_capacity: 16,
+        shared_path: shared_path.to_path_buf(),
+        enable_notifications: true,
+        consumer_timeout_ms: 100,
+    }
+}
+
+fn event_path(index: usize) -> PathBuf {
+    PathBuf::from(format!("/project/src/file-{index:04}.rs"))
+}
+
+fn event(index: usize) -> EnhancedFileEvent {
+    EnhancedFileEvent {
+        system_event: SystemEvent {
+            path: event_path(index),
+            event_type: SystemEventType::Modified,
+            timestamp: index as u64,
+            size: index as u64,
+            is_directory: false,
+            metadata: None,
+        },
+        hash: None,
+        processing_time_ns: 0,
+        metadata: None,
+        events_processed: None,
+    }
+}
+
+fn wait_until(timeout: Duration, mut condition: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + timeout;
+    while !condition() {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    true
+}
+
+/// Child side: print each event as it arrives, then leave once the producer
+/// has shut down and the ring is empty
+fn run_consumer(shared_path: &Path) {
+    let consumer = ZeroCopyRing::create_consumer(config(shared_path)).unwrap();
+    let deadline = Instant::now() + Duration::from_secs(30);
+
+    loop {
+        while let Some(event) = consumer.pop() {
+            println!("EVENT {}", event.system_event.path.display());
+        }
+        if consumer.is_shutdown() && consumer.stats().used == 0 {
+            break;
+        }
+        assert!(Instant::now() < deadline, "producer never shut down");
+        consumer.wait_for_events(10);
+    }
+}
+
+#[test]
+fn test_events_cross_process_boundary() {
+    if let Some(shared_path) = std::env::var_os(CONSUMER_ENV) {
+        run_consumer(Path::new(&shared_path));
+        return;
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let shared_path = dir.path().join("ring.mmap");
+    let producer = ZeroCopyRing::create_producer(config(&shared_path)).unwrap();
+    assert!(!producer.has_consumer());
+
+    let child = Command::new(std::env::current_exe().unwrap())
+        .args([
+            "test_events_cross_process_boundary",
+            "--exact",
+            "--nocapture",
+            "--test-threads=1",
+        ])
+        .env(CONSUMER_ENV, &shared_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .unwrap();
+
+    assert!(
+        wait_until(Duration::from_secs(10), || producer.has_consumer()),
+        "consumer process never attached"
+    );
+    assert_ne!(producer.stats().consumer_pid, std::process::id());
+
+    // A full ring rejects the push; retry until the consumer frees a slot
+    for index in 0..EVENT_COUNT {
+        let event = event(index);
+        assert!(
+            wait_until(Duration::from_secs(10), || producer.push(&event)),
+            "ring stayed full at event {index}"
+        );
+    }
+
+    assert!(producer.quiesce(Duration::from_secs(10)));
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "consumer process failed");
+
+    let received: Vec<PathBuf> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .filter_map(|line| line.strip_prefix("EVENT "))
+        .map(PathBuf::from)
+        .collect();
+    let expected: Vec<PathBuf> = (0..EVENT_COUNT).map(event_path).collect();
+    assert_eq!(received, expected);
+
+    // The consumer exited, so it no longer counts as attached
+    assert!(!producer.has_consumer());
+
+    let stats = producer.stats();
+    assert_eq!(stats.used, 0);
+    assert_eq!(stats.total_events, EVENT_COUNT as u64);
+
+    drop(producer);
+    assert!(!shared_path.exists());
+}
@@ -0,0 +1,107 @@
+//! Per-event cost of event filter pattern matching
+//!
+//! Compares compiling each glob to a regex on every event (the previous
+//! `glob_match`) with the precompiled `PathPatterns` used by `SystemWatcher`,
+//! over a realistic set of project include/exclude globs.
+
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use retrigger_system::PathPatterns;
+
+const INCLUDE: &[&str] = &[
+    "**/*.rs",
+    "**/*.toml",
+    "**/*.js",
+    "**/*.jsx",
+    "**/*.ts",
+    "**/*.tsx",
+    "**/*.json",
+    "**/*.css",
+    "**/*.scss",
+    "**/*.html",
+    "**/*.md",
+    "**/*.py",
+    "**/*.go",
+    "**/*.zig",
+];
+
+const EXCLUDE: &[&str] = &[
+    "**/node_modules/**",
+    "**/.git/**",
+    "**/target/**",
+    "**/dist/**",
+    "**/build/**",
+    "**/.cache/**",
+    "**/coverage/**",
+    "**/__pycache__/**",
+    "**/*.tmp",
+    "**/*.swp",
+    "**/*.log",
+    "**/*.lock",
+];
+
+const PATHS: &[&str] = &[
+    "/home/dev/project/src/daemon/retrigger-system/src/lib.rs",
+    "/home/dev/project/src/bindings/nodejs/index.ts",
+    "/home/dev/project/node_modules/react/cjs/react.development.js",
+    "/home/dev/project/target/debug/build/retrigger-core/out/hash.o",
+    "/home/dev/project/docs/README.md",
+    "/home/dev/project/src/daemon/retrigger-daemon/Cargo.toml",
+    "/home/dev/project/.git/objects/ab/cdef0123456789",
+    "/home/dev/project/assets/logo.png",
+];
+
+/// Regex-per-call matching as `SystemWatcher` did before precompilation
+fn glob_match(pattern: &str, path: &str) -> bool {
+    let regex_pattern = pattern
+        .replace("**", "DOUBLE_STAR")
+        .replace('*', "[^/]*")
+        .replace("DOUBLE_STAR", ".*")
+        .replace('?', "[^/]");
+
+    if let Ok(regex) = regex::Regex::new(&format!("^{}$", regex_pattern)) {
+        regex.is_match(path)
+    } else {
+        path.contains(&pattern.replace('*', ""))
+    }
+}
+
+fn admits_uncompiled(path: &Path) -> bool {
+    let path_str = path.to_string_lossy();
+    if EXCLUDE.iter().any(|pattern| glob_match(pattern, &path_str)) {
+        return false;
+    }
+    INCLUDE.iter().any(|pattern| glob_match(pattern, &path_str))
+}
+
+fn bench_event_filter(c: &mut Criterion) {
+    let paths: Vec<PathBuf> = PATHS.iter().map(PathBuf::from).collect();
+    let owned = |patterns: &[&str]| patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+    let patterns = PathPatterns::new(&owned(INCLUDE), &owned(EXCLUDE)).unwrap();
+
+    let mut group = c.benchmark_group("event_filter");
+    group.throughput(Throughput::Elements(paths.len() as u64));
+
+    group.bench_function("regex_per_event", |b| {
+        b.iter(|| {
+            for path in &paths {
+                black_box(admits_uncompiled(black_box(path)));
+            }
+        })
+    });
+
+    group.bench_function("precompiled_globset", |b| {
+        b.iter(|| {
+            for path in &paths {
+                black_box(patterns.admits(black_box(path)));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_event_filter);
+criterion_main!(benches);
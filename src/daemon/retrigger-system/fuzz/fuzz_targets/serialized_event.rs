@@ -0,0 +1,38 @@
+//! Decoding arbitrary wire records must never panic
+//!
+//! Run from `src/daemon/retrigger-system` with
+//! `cargo +nightly fuzz run serialized_event`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use retrigger_system::{EnhancedFileEvent, SerializedFileEvent, SERIALIZED_EVENT_SIZE};
+
+fuzz_target!(|data: &[u8]| {
+    let strict = EnhancedFileEvent::from_bytes(data);
+
+    let Ok(ser) = SerializedFileEvent::from_bytes(data) else {
+        assert!(strict.is_err());
+        return;
+    };
+    assert_eq!(strict.is_ok(), ser.validate().is_ok());
+
+    // The best-effort conversion the IPC consumer uses on every slot
+    let salvaged = EnhancedFileEvent::from(&ser);
+    let _ = salvaged.to_bytes();
+
+    // Valid records survive a round trip unchanged
+    if let Some(event) = ser.decode() {
+        let again = EnhancedFileEvent::from_bytes(&event.to_bytes()).expect("re-encoded record");
+        assert_eq!(again.system_event.path, event.system_event.path);
+        assert_eq!(again.system_event.event_type, event.system_event.event_type);
+        assert_eq!(again.hash, event.hash);
+    }
+
+    // The consumer reads slots as the in-memory struct, not through from_bytes
+    if data.len() >= SERIALIZED_EVENT_SIZE {
+        let raw = unsafe { std::ptr::read_unaligned(data.as_ptr() as *const SerializedFileEvent) };
+        let _ = EnhancedFileEvent::from(&raw);
+        let _ = raw.decode();
+    }
+});
@@ -0,0 +1,95 @@
+//! Time source for debouncing, cache TTLs and stability windows
+//!
+//! `SystemWatcher` and `FileEventProcessor` read the current time through a
+//! [`Clock`] instead of calling `SystemTime::now()` directly, so tests can
+//! drive time-dependent behavior with a [`MockClock`] rather than sleeping.
+
+use std::fmt::Debug;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of the current wall-clock time
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> SystemTime;
+
+    /// Nanoseconds since the Unix epoch
+    fn unix_time_ns(&self) -> u64 {
+        self.now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64
+    }
+
+    /// Milliseconds since the Unix epoch
+    fn unix_time_ms(&self) -> u64 {
+        self.now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64
+    }
+}
+
+/// The real system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+#[cfg(any(test, feature = "testing"))]
+pub use mock::MockClock;
+
+#[cfg(any(test, feature = "testing"))]
+mod mock {
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use super::Clock;
+
+    /// A clock that only moves when told to
+    ///
+    /// Clones share the same time, so a test can keep one handle and inject
+    /// another.
+    #[derive(Debug, Clone)]
+    pub struct MockClock {
+        now: Arc<Mutex<SystemTime>>,
+    }
+
+    impl Default for MockClock {
+        fn default() -> Self {
+            // Any fixed point works; this one is well clear of the epoch
+            Self::at(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        }
+    }
+
+    impl MockClock {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// A clock stopped at `time`
+        pub fn at(time: SystemTime) -> Self {
+            Self {
+                now: Arc::new(Mutex::new(time)),
+            }
+        }
+
+        /// Move the clock forward by `by`
+        pub fn advance(&self, by: Duration) {
+            *self.now.lock().unwrap() += by;
+        }
+
+        /// Jump the clock to `time`, which may be in its past
+        pub fn set(&self, time: SystemTime) {
+            *self.now.lock().unwrap() = time;
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> SystemTime {
+            *self.now.lock().unwrap()
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Debounce timestamps with bounded memory
+//!
+//! Debouncing remembers when each path (or coalescing key) last had an event
+//! delivered. An entry only matters while it is younger than `debounce_ms`,
+//! yet without eviction every path ever seen would stay in the table for the
+//! life of the daemon, a slow leak on a tree that keeps creating new files.
+//!
+//! [`DebounceTable`] sweeps out entries older than [`RETENTION_WINDOWS`]
+//! windows once per that period; keeping a few windows rather than one
+//! covers a filter override that lengthens `debounce_ms`. With a quota set,
+//! exceeding it first drops entries older than one window, which cannot
+//! suppress anything, and then the oldest live entries. Dropping a live
+//! entry costs at most one event that would otherwise have been debounced.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use dashmap::DashMap;
+
+/// Entries are kept for this many debounce windows after their last event
+pub const RETENTION_WINDOWS: u64 = 4;
+
+/// Last delivery time per debounce key
+#[derive(Debug, Default)]
+pub struct DebounceTable {
+    last_events: DashMap<PathBuf, u64>,
+    /// Cap on remembered keys; 0 is unlimited
+    max_entries: usize,
+    /// When expired entries were last swept, in ms
+    last_sweep_ms: AtomicU64,
+}
+
+impl DebounceTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A table that remembers at most `max_entries` keys; 0 is unlimited
+    pub fn with_max_entries(max_entries: usize) -> Self {
+        Self {
+            max_entries,
+            ..Self::default()
+        }
+    }
+
+    /// Whether an event for `key` at `now_ms` is outside its debounce window;
+    /// records the event time if so. Always true when `debounce_ms` is 0.
+    pub fn admit(&self, key: &Path, debounce_ms: u64, now_ms: u64) -> bool {
+        if debounce_ms == 0 {
+            return true;
+        }
+
+        if let Some(last_time) = self.last_events.get(key) {
+            if now_ms.saturating_sub(*last_time) < debounce_ms {
+                return false;
+            }
+        }
+
+        self.last_events.insert(key.to_path_buf(), now_ms);
+        self.evict(debounce_ms, now_ms);
+        true
+    }
+
+    /// Keys currently remembered
+    pub fn len(&self) -> usize {
+        self.last_events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.last_events.is_empty()
+    }
+
+    fn evict(&self, debounce_ms: u64, now_ms: u64) {
+        let max_entries = self.max_entries;
+        let retention_ms = debounce_ms.saturating_mul(RETENTION_WINDOWS);
+        let last_sweep = self.last_sweep_ms.load(Ordering::Relaxed);
+        if now_ms.saturating_sub(last_sweep) >= retention_ms {
+            self.last_sweep_ms.store(now_ms, Ordering::Relaxed);
+            self.expire(retention_ms, now_ms);
+        }
+
+        if max_entries == 0 || self.last_events.len() <= max_entries {
+            return;
+        }
+        self.expire(debounce_ms, now_ms);
+
+        let excess = self.last_events.len().saturating_sub(max_entries);
+        if excess == 0 {
+            return;
+        }
+        // Trim a tenth below the quota so the sort is not repeated on every
+        // new key while the table sits at the limit
+        let mut by_age: Vec<(u64, PathBuf)> = self
+            .last_events
+            .iter()
+            .map(|entry| (*entry.value(), entry.key().clone()))
+            .collect();
+        by_age.sort_unstable();
+        for (_, key) in by_age.into_iter().take(excess + max_entries / 10) {
+            self.last_events.remove(&key);
+        }
+    }
+
+    /// Drop entries at least `age_ms` old
+    fn expire(&self, age_ms: u64, now_ms: u64) {
+        self.last_events
+            .retain(|_, last_time| now_ms.saturating_sub(*last_time) < age_ms);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_expire_and_respect_quota() {
+        let table = DebounceTable::new();
+        let path = |i: usize| PathBuf::from(format!("/project/file{i}.rs"));
+
+        // Debounces within the window
+        assert!(table.admit(&path(0), 100, 1_000));
+        assert!(!table.admit(&path(0), 100, 1_099));
+        assert!(table.admit(&path(0), 100, 1_100));
+
+        // Unlimited, but swept once entries are several windows old
+        for i in 1..50 {
+            assert!(table.admit(&path(i), 100, 1_100));
+        }
+        assert_eq!(table.len(), 50);
+        assert!(table.admit(&path(50), 100, 1_500));
+        assert_eq!(table.len(), 1);
+
+        // Over the quota the oldest entries go first
+        let table = DebounceTable::with_max_entries(50);
+        for i in 0..100 {
+            assert!(table.admit(&path(i), 1_000, 10_000 + i as u64));
+        }
+        assert!(table.len() <= 50);
+        // The newest key is still debounced; the oldest was forgotten
+        assert!(!table.admit(&path(99), 1_000, 10_200));
+        assert!(table.admit(&path(0), 1_000, 10_200));
+    }
+}
@@ -0,0 +1,149 @@
+//! Why events were dropped
+//!
+//! An event can be discarded at several points between the native layer and
+//! a consumer: outside every watch, swallowed by normalization, rejected by
+//! the size or path filters, debounced, cancelled as a transient file,
+//! skipped by a consumer that fell behind, or lost to a full IPC ring. A
+//! single "dropped" total cannot tell these apart, so every drop site
+//! records a [`DropReason`] and the counts are kept per reason. The watcher reports its own in
+//! `WatcherStats::dropped_by_reason`; the daemon adds the reasons only it can
+//! see (lag, full ring, daemon-side patterns) to the same metric.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// Where and why an event was discarded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DropReason {
+    /// The native event had no path, or one that is not valid UTF-8
+    InvalidPath,
+    /// The path is not under an active watch
+    OutOfScope,
+    /// Normalization decided the event was noise
+    Normalized,
+    /// Smaller than `EventFilter::min_file_size`
+    TooSmall,
+    /// Larger than `EventFilter::max_event_size`
+    TooLarge,
+    /// Excluded, or not included, by path patterns
+    Excluded,
+    /// Another event for the same debounce key arrived within `debounce_ms`
+    Debounced,
+    /// Created and deleted again within `transient_window_ms`; see
+    /// [`transient`](crate::transient)
+    Transient,
+    /// The reader fell behind the event channel and the event was overwritten
+    Lagged,
+    /// The IPC ring had no free slot
+    RingFull,
+    /// No IPC ring was available to deliver to
+    RingUnavailable,
+}
+
+impl DropReason {
+    /// Every reason, in declaration order
+    pub const ALL: [DropReason; 11] = [
+        DropReason::InvalidPath,
+        DropReason::OutOfScope,
+        DropReason::Normalized,
+        DropReason::TooSmall,
+        DropReason::TooLarge,
+        DropReason::Excluded,
+        DropReason::Debounced,
+        DropReason::Transient,
+        DropReason::Lagged,
+        DropReason::RingFull,
+        DropReason::RingUnavailable,
+    ];
+
+    /// Stable label used in metrics and stats output
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DropReason::InvalidPath => "invalid_path",
+            DropReason::OutOfScope => "out_of_scope",
+            DropReason::Normalized => "normalized",
+            DropReason::TooSmall => "too_small",
+            DropReason::TooLarge => "too_large",
+            DropReason::Excluded => "excluded",
+            DropReason::Debounced => "debounced",
+            DropReason::Transient => "transient",
+            DropReason::Lagged => "lagged",
+            DropReason::RingFull => "ring_full",
+            DropReason::RingUnavailable => "ring_unavailable",
+        }
+    }
+}
+
+impl std::fmt::Display for DropReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Lock-free drop counts, one per [`DropReason`]
+#[derive(Debug, Default)]
+pub struct DropCounters {
+    counts: [AtomicU64; DropReason::ALL.len()],
+}
+
+impl DropCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, reason: DropReason) {
+        self.record_many(reason, 1);
+    }
+
+    pub fn record_many(&self, reason: DropReason, count: u64) {
+        self.counts[reason as usize].fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn get(&self, reason: DropReason) -> u64 {
+        self.counts[reason as usize].load(Ordering::Relaxed)
+    }
+
+    /// Drops across all reasons
+    pub fn total(&self) -> u64 {
+        DropReason::ALL.iter().map(|&reason| self.get(reason)).sum()
+    }
+
+    /// Non-zero counts keyed by reason
+    pub fn snapshot(&self) -> BTreeMap<DropReason, u64> {
+        DropReason::ALL
+            .iter()
+            .map(|&reason| (reason, self.get(reason)))
+            .filter(|&(_, count)| count > 0)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_per_reason() {
+        let counters = DropCounters::new();
+        counters.record(DropReason::Debounced);
+        counters.record(DropReason::Debounced);
+        counters.record_many(DropReason::Lagged, 5);
+
+        assert_eq!(counters.get(DropReason::Debounced), 2);
+        assert_eq!(counters.get(DropReason::Excluded), 0);
+        assert_eq!(counters.total(), 7);
+
+        let snapshot = counters.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[&DropReason::Lagged], 5);
+
+        // Labels are unique and index the counter array
+        for (index, reason) in DropReason::ALL.iter().enumerate() {
+            assert_eq!(*reason as usize, index);
+        }
+        assert_eq!(DropReason::RingFull.to_string(), "ring_full");
+    }
+}
@@ -0,0 +1,281 @@
+//! File-type specific metadata extracted from content
+//!
+//! A media or asset pipeline often wants more than a size: the dimensions of
+//! an image, the line count of a source file. [`FileEventProcessor`] runs the
+//! [`MetadataExtractor`]s registered for a file's content type on the bytes
+//! it already read to hash the file, and attaches what they return to
+//! `EnhancedFileEvent::metadata`. The file is never read a second time; a
+//! file whose hash came from the cache is read once, for extraction only.
+//!
+//! Extraction is bounded: files above [`ExtractorRegistry::max_bytes`] are
+//! skipped, and each extractor gets a deadline it must honour, returning
+//! nothing rather than running over.
+//!
+//! Built-in extractors sit behind the `image-metadata` and `text-metadata`
+//! features.
+//!
+//! [`FileEventProcessor`]: crate::FileEventProcessor
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tracing::debug;
+
+/// Fields extracted from one file, e.g. `{"width": 640, "height": 480}`
+pub type ExtractedMetadata = BTreeMap<String, serde_json::Value>;
+
+/// Default time each extractor may spend on one file
+pub const DEFAULT_EXTRACT_BUDGET: Duration = Duration::from_millis(5);
+
+/// Files larger than this are not read for extraction by default
+pub const DEFAULT_EXTRACT_MAX_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Computes metadata from the content of files of some content types
+pub trait MetadataExtractor: Send + Sync {
+    /// Content types handled, as returned by [`content_type`]
+    fn content_types(&self) -> &[&'static str];
+
+    /// Add fields for `data` to `out`
+    ///
+    /// Long-running extractors must check `deadline` and give up once it is
+    /// reached, leaving `out` untouched.
+    fn extract(&self, data: &[u8], deadline: Instant, out: &mut ExtractedMetadata);
+}
+
+/// Content type of `path`, guessed from its extension
+pub fn content_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    let content_type = match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "txt" | "log" => "text/plain",
+        "md" | "markdown" => "text/markdown",
+        "csv" => "text/csv",
+        "json" => "application/json",
+        "toml" | "yaml" | "yml" | "ini" => "text/config",
+        "rs" | "zig" | "c" | "h" | "cpp" | "hpp" | "go" | "py" | "rb" | "java" | "js"
+        | "jsx" | "ts" | "tsx" | "css" | "scss" | "html" | "sh" => "text/source",
+        _ => return None,
+    };
+    Some(content_type)
+}
+
+/// Extractors keyed by the content type they handle
+#[derive(Clone)]
+pub struct ExtractorRegistry {
+    by_type: HashMap<&'static str, Vec<Arc<dyn MetadataExtractor>>>,
+    budget: Duration,
+    max_bytes: u64,
+}
+
+impl Default for ExtractorRegistry {
+    fn default() -> Self {
+        Self {
+            by_type: HashMap::new(),
+            budget: DEFAULT_EXTRACT_BUDGET,
+            max_bytes: DEFAULT_EXTRACT_MAX_BYTES,
+        }
+    }
+}
+
+impl std::fmt::Debug for ExtractorRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut types: Vec<_> = self.by_type.keys().collect();
+        types.sort();
+        f.debug_struct("ExtractorRegistry")
+            .field("content_types", &types)
+            .field("budget", &self.budget)
+            .field("max_bytes", &self.max_bytes)
+            .finish()
+    }
+}
+
+impl ExtractorRegistry {
+    /// An empty registry; extraction is off until an extractor is registered
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry holding every built-in extractor compiled in
+    pub fn with_builtin() -> Self {
+        #[allow(unused_mut)]
+        let mut registry = Self::new();
+        #[cfg(feature = "image-metadata")]
+        registry.register(Arc::new(ImageDimensions));
+        #[cfg(feature = "text-metadata")]
+        registry.register(Arc::new(LineCount));
+        registry
+    }
+
+    /// Run `extractor` on files of each of its content types
+    pub fn register(&mut self, extractor: Arc<dyn MetadataExtractor>) {
+        for content_type in extractor.content_types() {
+            self.by_type
+                .entry(content_type)
+                .or_default()
+                .push(Arc::clone(&extractor));
+        }
+    }
+
+    /// Time each extractor may spend on one file
+    pub fn with_budget(mut self, budget: Duration) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Skip files larger than `max_bytes` rather than read them
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_type.is_empty()
+    }
+
+    /// Whether any extractor handles `path`
+    pub fn handles(&self, path: &Path) -> bool {
+        !self.extractors_for(path).is_empty()
+    }
+
+    fn extractors_for(&self, path: &Path) -> &[Arc<dyn MetadataExtractor>] {
+        content_type(path)
+            .and_then(|content_type| self.by_type.get(content_type))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Run the extractors for `path` over its content; `None` if none
+    /// produced anything
+    pub fn extract(&self, path: &Path, data: &[u8]) -> Option<ExtractedMetadata> {
+        let mut metadata = ExtractedMetadata::new();
+        for extractor in self.extractors_for(path) {
+            let deadline = Instant::now() + self.budget;
+            extractor.extract(data, deadline, &mut metadata);
+            if Instant::now() > deadline {
+                debug!("Metadata extraction over budget for {}", path.display());
+            }
+        }
+        (!metadata.is_empty()).then_some(metadata)
+    }
+}
+
+/// `width` and `height` of PNG, JPEG, GIF, WebP and BMP images, read from
+/// the header without decoding pixels
+#[cfg(feature = "image-metadata")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImageDimensions;
+
+#[cfg(feature = "image-metadata")]
+impl MetadataExtractor for ImageDimensions {
+    fn content_types(&self) -> &[&'static str] {
+        &["image/png", "image/jpeg", "image/gif", "image/webp", "image/bmp"]
+    }
+
+    fn extract(&self, data: &[u8], _deadline: Instant, out: &mut ExtractedMetadata) {
+        let reader = image::ImageReader::new(std::io::Cursor::new(data));
+        let Ok(reader) = reader.with_guessed_format() else {
+            return;
+        };
+        if let Ok((width, height)) = reader.into_dimensions() {
+            out.insert("width".to_string(), width.into());
+            out.insert("height".to_string(), height.into());
+        }
+    }
+}
+
+/// `line_count` of text files; a final line without a newline counts
+#[cfg(feature = "text-metadata")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LineCount;
+
+#[cfg(feature = "text-metadata")]
+impl MetadataExtractor for LineCount {
+    fn content_types(&self) -> &[&'static str] {
+        &[
+            "text/plain",
+            "text/markdown",
+            "text/csv",
+            "text/config",
+            "text/source",
+            "application/json",
+        ]
+    }
+
+    fn extract(&self, data: &[u8], deadline: Instant, out: &mut ExtractedMetadata) {
+        const CHECK_EVERY: usize = 64 * 1024;
+
+        let mut lines = 0u64;
+        for chunk in data.chunks(CHECK_EVERY) {
+            if Instant::now() >= deadline {
+                return;
+            }
+            lines += chunk.iter().filter(|&&byte| byte == b'\n').count() as u64;
+        }
+        if data.last().is_some_and(|&byte| byte != b'\n') {
+            lines += 1;
+        }
+        out.insert("line_count".to_string(), lines.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FirstByte;
+
+    impl MetadataExtractor for FirstByte {
+        fn content_types(&self) -> &[&'static str] {
+            &["text/plain"]
+        }
+
+        fn extract(&self, data: &[u8], _deadline: Instant, out: &mut ExtractedMetadata) {
+            if let Some(&byte) = data.first() {
+                out.insert("first_byte".to_string(), byte.into());
+            }
+        }
+    }
+
+    #[test]
+    fn test_registry_dispatches_by_content_type() {
+        let mut registry = ExtractorRegistry::new();
+        assert!(registry.is_empty());
+        registry.register(Arc::new(FirstByte));
+
+        assert!(registry.handles(Path::new("/notes/todo.TXT")));
+        assert!(!registry.handles(Path::new("/notes/photo.png")));
+        assert!(!registry.handles(Path::new("/notes/README")));
+
+        let metadata = registry.extract(Path::new("/notes/todo.txt"), b"abc").unwrap();
+        assert_eq!(metadata["first_byte"], 97);
+        assert!(registry.extract(Path::new("/notes/todo.txt"), b"").is_none());
+    }
+
+    #[cfg(feature = "text-metadata")]
+    #[test]
+    fn test_line_count() {
+        let registry = ExtractorRegistry::with_builtin();
+        let count = |data: &[u8]| {
+            registry
+                .extract(Path::new("/src/main.rs"), data)
+                .map(|metadata| metadata["line_count"].clone())
+        };
+        assert_eq!(count(b"a\nb\nc\n").unwrap(), 3);
+        assert_eq!(count(b"a\nb\nc").unwrap(), 3);
+        assert_eq!(count(b"").unwrap(), 0);
+
+        // Out of time: nothing rather than a partial count
+        let registry = ExtractorRegistry::with_builtin().with_budget(Duration::ZERO);
+        let data = vec![b'\n'; 1024 * 1024];
+        assert!(registry.extract(Path::new("/src/main.rs"), &data).is_none());
+    }
+}
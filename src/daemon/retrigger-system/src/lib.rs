@@ -3,18 +3,51 @@
 //! Rust wrapper around the high-performance Zig system layer.
 //! Provides async interfaces for file system monitoring.
 
-use std::ffi::{CStr, CString};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
+use std::ffi::{CStr, CString, OsString};
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, Result};
 use dashmap::DashMap;
-use retrigger_core::{FastHash, HashEngine, HashResult};
+use retrigger_core::{
+    find_watch_boundaries, walk_directory, BoundaryOptions, HashEngine, HashResult, HashStrategy,
+    WalkOptions,
+};
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 use tracing::{debug, info, warn};
 
+pub mod clock;
+pub mod debounce;
+pub mod drops;
+pub mod extract;
+pub mod normalize;
+pub mod patterns;
+pub mod permissions;
+pub mod stability;
+pub mod transient;
+pub mod wire;
+
+#[cfg(any(test, feature = "testing"))]
+pub use clock::MockClock;
+pub use clock::{Clock, SystemClock};
+pub use debounce::DebounceTable;
+pub use drops::{DropCounters, DropReason};
+pub use extract::{ExtractedMetadata, ExtractorRegistry, MetadataExtractor};
+pub use normalize::{EventNormalization, EventNormalizer};
+pub use patterns::{PathPatterns, PatternPrecedence};
+pub use permissions::{
+    Change, FileAttributes, FilePermissions, PermissionDelta, PermissionTracker,
+};
+pub use stability::{StabilityTracker, TrailingTimer};
+pub use transient::{TransientFilter, TransientOutcome};
+pub use wire::{SerializedFileEvent, SERIALIZED_EVENT_SIZE, WIRE_FORMAT_VERSION};
+
 /// File system event from the native layer
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemEvent {
@@ -23,6 +56,95 @@ pub struct SystemEvent {
     pub timestamp: u64,
     pub size: u64,
     pub is_directory: bool,
+    /// Extra file metadata, present only when capture is enabled
+    #[serde(default)]
+    pub metadata: Option<EventMetadata>,
+}
+
+/// Optional file metadata captured when an event is ingested
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventMetadata {
+    /// Stable identity of the file the path pointed to at capture time
+    pub file_id: Option<FileId>,
+    /// Mode bits and owner at capture time (Unix only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<FilePermissions>,
+    /// Set on a `MetadataChanged` event that changed only permissions or
+    /// ownership; see [`permissions`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permission_delta: Option<PermissionDelta>,
+}
+
+impl EventMetadata {
+    /// Stat `path` and capture its metadata (follows symlinks, like hashing)
+    pub fn capture(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self::from_metadata(path, &metadata))
+    }
+
+    /// Metadata of the file at `path`, given its already read `metadata`
+    pub fn from_metadata(path: &Path, metadata: &std::fs::Metadata) -> Self {
+        Self {
+            file_id: FileId::of(path, metadata),
+            permissions: FilePermissions::from_metadata(metadata),
+            permission_delta: None,
+        }
+    }
+}
+
+/// Filesystem identity of a file, shared by all of its hardlinks
+///
+/// On Unix this is `(st_dev, st_ino)`; on Windows the volume serial number
+/// and the 64-bit file index. Other platforms have no identity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FileId {
+    pub device: u64,
+    pub inode: u64,
+}
+
+impl FileId {
+    /// Identity of the file at `path`, whose stat is `metadata`
+    #[cfg(unix)]
+    pub fn of(_path: &Path, metadata: &std::fs::Metadata) -> Option<Self> {
+        use std::os::unix::fs::MetadataExt;
+        Some(Self {
+            device: metadata.dev(),
+            inode: metadata.ino(),
+        })
+    }
+
+    /// Identity of the file at `path`, whose stat is `metadata`
+    ///
+    /// The file index is not part of std's stable `Metadata`, so the file is
+    /// opened (for no access, which any reader may do) and asked for it.
+    #[cfg(windows)]
+    pub fn of(path: &Path, _metadata: &std::fs::Metadata) -> Option<Self> {
+        use std::os::windows::fs::OpenOptionsExt;
+        use std::os::windows::io::AsRawHandle;
+        use windows_sys::Win32::Storage::FileSystem::{
+            GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION, FILE_FLAG_BACKUP_SEMANTICS,
+        };
+
+        // Backup semantics allow opening directories too
+        let file = std::fs::OpenOptions::new()
+            .access_mode(0)
+            .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+            .open(path)
+            .ok()?;
+        let mut info: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+        if unsafe { GetFileInformationByHandle(file.as_raw_handle() as _, &mut info) } == 0 {
+            return None;
+        }
+        Some(Self {
+            device: u64::from(info.dwVolumeSerialNumber),
+            inode: (u64::from(info.nFileIndexHigh) << 32) | u64::from(info.nFileIndexLow),
+        })
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    pub fn of(_path: &Path, _metadata: &std::fs::Metadata) -> Option<Self> {
+        None
+    }
 }
 
 /// System event types matching the Zig layer
@@ -33,6 +155,14 @@ pub enum SystemEventType {
     Deleted = 3,
     Moved = 4,
     MetadataChanged = 5,
+    /// Synthetic: the file has not changed for `stability_window_ms`; see
+    /// [`stability`]
+    StabilizedModified = 6,
+    /// Synthetic: the daemon is alive but has seen no events for its
+    /// heartbeat interval. `path` is empty, `size` is 0 and `timestamp` is
+    /// the current time; the event count is in
+    /// [`EnhancedFileEvent::events_processed`].
+    Heartbeat = 7,
 }
 
 /// File system watcher statistics
@@ -40,9 +170,29 @@ pub enum SystemEventType {
 pub struct WatcherStats {
     pub pending_events: u32,
     pub buffer_capacity: u32,
+    /// Events discarded by the watcher, across all reasons
     pub dropped_events: u64,
     pub total_events: u64,
     pub watched_directories: usize,
+    /// Mount points under active recursive watches that were not descended
+    /// into because the watch does not cross filesystems
+    #[serde(default)]
+    pub skipped_mount_points: usize,
+    /// Directories under active recursive watches that were not descended
+    /// into because they exceed `max_entries_per_dir`
+    #[serde(default)]
+    pub skipped_large_dirs: Vec<PathBuf>,
+    /// Paths (or coalescing keys) currently remembered for debouncing
+    #[serde(default)]
+    pub debounce_entries: usize,
+    /// `dropped_events` broken down by why; reasons never seen are omitted.
+    /// See [`drops`]
+    #[serde(default)]
+    pub dropped_by_reason: BTreeMap<DropReason, u64>,
+    /// `Created`/`Deleted` pairs cancelled by `transient_window_ms`; see
+    /// [`transient`]
+    #[serde(default)]
+    pub transient_pairs_suppressed: u64,
 }
 
 /// FFI bindings to the Zig layer
@@ -71,6 +221,12 @@ mod ffi {
             watcher: *mut FileWatcher,
             path: *const c_char,
             recursive: bool,
+            cross_filesystem: bool,
+            max_entries_per_dir: usize,
+        ) -> c_int;
+        pub fn fw_watcher_unwatch_directory(
+            watcher: *mut FileWatcher,
+            path: *const c_char,
         ) -> c_int;
         pub fn fw_watcher_start(watcher: *mut FileWatcher) -> c_int;
         #[allow(dead_code)]
@@ -99,14 +255,82 @@ impl WatcherPtr {
 }
 
 
+/// Keeps a scoped watch alive; see [`SystemWatcher::watch_scoped`]
+///
+/// When the last guard drops, the watch and its native watch are removed
+/// synchronously (events under the path stop immediately); since `Drop`
+/// cannot await, only the `watched_directories`
+/// statistic is refreshed in a task spawned on the current Tokio runtime.
+/// Outside a runtime the statistic is updated if its lock is free.
+#[must_use = "the watch is removed as soon as the guard is dropped"]
+pub struct WatchGuard {
+    watcher: Arc<SystemWatcher>,
+    path: PathBuf,
+    /// Registration this guard counts toward; see `WatchEntry::generation`
+    generation: u64,
+}
+
+impl WatchGuard {
+    /// The watched path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for WatchGuard {
+    fn drop(&mut self) {
+        if !self.watcher.release_scoped(&self.path, self.generation) {
+            return;
+        }
+        debug!("Scoped watch released: {}", self.path.display());
+
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                let watcher = Arc::clone(&self.watcher);
+                handle.spawn(async move { watcher.update_watched_count().await });
+            }
+            Err(_) => {
+                if let Ok(mut stats) = self.watcher.stats.try_write() {
+                    self.watcher.refresh_watch_stats(&mut stats);
+                }
+            }
+        }
+    }
+}
+
+/// Maps an event to the group it is debounced with
+pub type CoalesceKey = Arc<dyn Fn(&SystemEvent) -> String + Send + Sync>;
+
 /// Event filtering configuration
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct EventFilter {
     pub include_patterns: Vec<String>,
     pub exclude_patterns: Vec<String>,
     pub debounce_ms: u64,
     pub min_file_size: u64,
-    pub max_file_size: Option<u64>,
+    /// Events for files larger than this are dropped entirely. To keep the
+    /// event but skip hashing, use `FileEventProcessor::set_max_hash_size`.
+    pub max_event_size: Option<u64>,
+    /// How a path matched by both an include and an exclude pattern is decided
+    pub precedence: PatternPrecedence,
+    /// Group events for debouncing by this key instead of by path: only the
+    /// first event of a group within `debounce_ms` is delivered. `None`
+    /// debounces each path on its own.
+    pub coalesce_key: Option<CoalesceKey>,
+}
+
+impl std::fmt::Debug for EventFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventFilter")
+            .field("include_patterns", &self.include_patterns)
+            .field("exclude_patterns", &self.exclude_patterns)
+            .field("debounce_ms", &self.debounce_ms)
+            .field("min_file_size", &self.min_file_size)
+            .field("max_event_size", &self.max_event_size)
+            .field("precedence", &self.precedence)
+            .field("coalesce_key", &self.coalesce_key.as_ref().map(|_| "<fn>"))
+            .finish()
+    }
 }
 
 impl Default for EventFilter {
@@ -122,21 +346,553 @@ impl Default for EventFilter {
             ],
             debounce_ms: 100,
             min_file_size: 0,
-            max_file_size: None,
+            max_event_size: None,
+            precedence: PatternPrecedence::default(),
+            coalesce_key: None,
+        }
+    }
+}
+
+impl EventFilter {
+    /// Compile the include and exclude patterns for per-event matching
+    pub fn compile_patterns(&self) -> Result<PathPatterns> {
+        PathPatterns::with_precedence(
+            &self.include_patterns,
+            &self.exclude_patterns,
+            self.precedence,
+        )
+    }
+
+    /// Key `event` is debounced under
+    fn debounce_key<'a>(&self, event: &'a SystemEvent) -> Cow<'a, Path> {
+        match &self.coalesce_key {
+            Some(key) => Cow::Owned(PathBuf::from(key(event))),
+            None => Cow::Borrowed(&event.path),
+        }
+    }
+}
+
+/// Settings for a single watched root
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WatchSettings {
+    pub recursive: bool,
+    /// Glob patterns an event path must match; empty admits everything
+    pub include_patterns: Vec<String>,
+    /// Glob patterns that reject an event path
+    pub exclude_patterns: Vec<String>,
+    /// Hash strategy for files under this root; `None` uses the engine default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash_strategy: Option<HashStrategy>,
+    /// Descend into directories on other filesystems. When false, a
+    /// recursive watch stops at mount points (like `find -xdev`) so NFS
+    /// mounts or `/proc` underneath are never watched. Mount points are
+    /// detected on Unix only; on Windows this has no effect and volumes
+    /// mounted into folders are watched.
+    #[serde(default)]
+    pub cross_filesystem: bool,
+}
+
+impl WatchSettings {
+    /// Non-recursive or recursive watch without per-root patterns
+    pub fn new(recursive: bool) -> Self {
+        Self {
+            recursive,
+            ..Default::default()
+        }
+    }
+}
+
+/// What keeps a registration alive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WatchHold {
+    /// Added explicitly; stays until unwatched
+    Pinned,
+    /// Held by one more `WatchGuard`
+    Scoped,
+}
+
+/// Source of `WatchEntry::generation`
+static NEXT_WATCH_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+/// Book-keeping for a root registered with the native layer
+///
+/// Unwatching removes the entry together with its native watch, unless
+/// another root overlaps it; see [`SystemWatcher::unwatch_directory`].
+#[derive(Debug, Clone)]
+struct WatchEntry {
+    settings: WatchSettings,
+    /// `settings`' include and exclude globs, compiled
+    patterns: PathPatterns,
+    /// Whether the native registration covers subdirectories
+    native_recursive: bool,
+    /// Watched explicitly, so it outlives any scoped guards
+    pinned: bool,
+    /// Live `WatchGuard`s holding this watch
+    scoped_guards: usize,
+    /// Distinguishes this registration from earlier ones of the same path,
+    /// so guards left over from an unwatched registration cannot release it
+    generation: u64,
+    /// Mount points below the root that this watch does not cross into
+    skipped_mounts: Vec<PathBuf>,
+    /// Directories below the root too large to descend into; only their
+    /// direct children are watched
+    skipped_large_dirs: Vec<PathBuf>,
+    /// Set when the root was watched through `watch_files`: only these
+    /// direct children are admitted
+    files: Option<HashSet<OsString>>,
+}
+
+impl WatchEntry {
+    /// Whether `path` under `root` falls within this watch's settings
+    fn admits(&self, root: &Path, path: &Path) -> bool {
+        let relative = match path.strip_prefix(root) {
+            Ok(relative) => relative,
+            Err(_) => return false,
+        };
+        if !self.settings.recursive && relative.components().count() > 1 {
+            return false;
+        }
+        if let Some(files) = &self.files {
+            if !files.contains(relative.as_os_str()) {
+                return false;
+            }
+        }
+        // Backends that watch whole subtrees may still report these
+        if self.skipped_mounts.iter().any(|mount| path.starts_with(mount)) {
+            return false;
+        }
+        let below_large_dir = self.skipped_large_dirs.iter().any(|dir| {
+            path.strip_prefix(dir)
+                .is_ok_and(|rest| rest.components().count() > 1)
+        });
+        if below_large_dir {
+            return false;
+        }
+        self.patterns.admits(path)
+    }
+}
+
+/// Whether an event path belongs to an active watch
+///
+/// Paths under no watched root pass through untouched, since the native
+/// layer may report them in a different (e.g. canonicalized) form. Like
+/// [`RootHashStrategies::strategy_for`], this looks up each ancestor of
+/// `path` rather than scanning every watch.
+fn in_watch_scope(path: &Path, watches: &DashMap<PathBuf, WatchEntry>) -> bool {
+    let mut under_known_root = false;
+    for root in path.ancestors() {
+        let Some(entry) = watches.get(root) else {
+            continue;
+        };
+        if entry.admits(root, path) {
+            return true;
+        }
+        under_known_root = true;
+    }
+    !under_known_root
+}
+
+/// Per-root hash strategies, shared with a `FileEventProcessor`
+///
+/// Reads the watcher's live watch table, so roots added, updated or
+/// unwatched later are picked up without re-wiring.
+#[derive(Debug, Clone)]
+pub struct RootHashStrategies {
+    watches: Arc<DashMap<PathBuf, WatchEntry>>,
+}
+
+impl RootHashStrategies {
+    /// Strategy of the deepest active root containing `path` that sets one
+    ///
+    /// Looks up each ancestor of `path`, deepest first, so the cost grows
+    /// with the path's depth rather than the number of watches.
+    pub fn strategy_for(&self, path: &Path) -> Option<HashStrategy> {
+        path.ancestors().find_map(|ancestor| {
+            self.watches
+                .get(ancestor)
+                .and_then(|entry| entry.settings.hash_strategy)
+        })
+    }
+}
+
+/// Event ingestion options for the system watcher
+#[derive(Debug, Clone, Default)]
+pub struct WatcherOptions {
+    /// Stat each event path to capture its inode/device (`EventMetadata::file_id`)
+    /// and permissions, reporting permission-only changes as a
+    /// `permission_delta`. Costs one extra `stat` per delivered event, plus
+    /// an open on Windows to read the file index. The first event for a path
+    /// carries no delta; see [`permissions`]
+    pub capture_file_ids: bool,
+    /// Mapping applied to native event types; see [`normalize`]. Canonical
+    /// costs one extra `stat` per `Created` event.
+    pub normalization: EventNormalization,
+    /// Emit `StabilizedModified` once a file has gone this long without
+    /// changing; 0 disables. See [`stability`]
+    pub stability_window_ms: u64,
+    /// Hold `Created` events this long and drop them together with a
+    /// `Deleted` for the same path arriving in time; 0 disables. See
+    /// [`transient`]
+    pub transient_window_ms: u64,
+    /// Recursive watches do not descend into directories with more entries
+    /// than this, so one huge directory cannot stall registration; 0 is
+    /// unlimited. Skipped directories are listed in `WatcherStats`.
+    pub max_entries_per_dir: usize,
+    /// Paths (or coalescing keys) remembered for debouncing; the oldest are
+    /// forgotten beyond this. 0 is unlimited; entries several debounce
+    /// windows old are dropped either way. See [`debounce`]. The same cap
+    /// applies to the paths whose permissions are remembered for
+    /// `capture_file_ids`.
+    pub max_debounce_entries: usize,
+}
+
+/// Compiled patterns of `EventFilter::default()`
+fn default_filter_patterns() -> PathPatterns {
+    EventFilter::default()
+        .compile_patterns()
+        .expect("default filter patterns are valid globs")
+}
+
+/// An `EventFilter` with its globs compiled
+#[derive(Debug)]
+struct CompiledFilter {
+    /// Lets a temporary override's timer remove exactly its own layer
+    id: u64,
+    filter: EventFilter,
+    patterns: PathPatterns,
+}
+
+impl CompiledFilter {
+    /// Why the size and path filters reject `event`, if they do
+    fn verdict(&self, event: &SystemEvent) -> Result<(), DropReason> {
+        // Skip if file is too small
+        if event.size < self.filter.min_file_size {
+            return Err(DropReason::TooSmall);
+        }
+
+        // Skip if file is too large
+        if let Some(max_size) = self.filter.max_event_size {
+            if event.size > max_size {
+                return Err(DropReason::TooLarge);
+            }
+        }
+
+        // Apply path-based filtering
+        if !self.patterns.admits(&event.path) {
+            return Err(DropReason::Excluded);
+        }
+        Ok(())
+    }
+}
+
+/// The configured filter plus overrides pushed on top of it; only the top
+/// layer applies
+#[derive(Debug)]
+struct FilterStack {
+    /// Never empty; index 0 is the base filter
+    layers: Vec<Arc<CompiledFilter>>,
+    next_id: u64,
+}
+
+impl FilterStack {
+    fn new() -> Self {
+        Self {
+            layers: vec![Arc::new(CompiledFilter {
+                id: 0,
+                filter: EventFilter::default(),
+                patterns: default_filter_patterns(),
+            })],
+            next_id: 1,
+        }
+    }
+
+    fn active(&self) -> Arc<CompiledFilter> {
+        let top = self.layers.last().expect("base layer is never popped");
+        Arc::clone(top)
+    }
+
+    fn set_base(&mut self, filter: EventFilter, patterns: PathPatterns) {
+        self.layers[0] = Arc::new(CompiledFilter {
+            id: 0,
+            filter,
+            patterns,
+        });
+    }
+
+    fn push(&mut self, filter: EventFilter, patterns: PathPatterns) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.layers.push(Arc::new(CompiledFilter {
+            id,
+            filter,
+            patterns,
+        }));
+        id
+    }
+
+    fn pop(&mut self) -> Option<EventFilter> {
+        if self.layers.len() == 1 {
+            return None;
+        }
+        self.layers.pop().map(|layer| layer.filter.clone())
+    }
+
+    /// Remove the override `id` wherever it sits; false if already popped
+    fn remove(&mut self, id: u64) -> bool {
+        match self.layers.iter().skip(1).position(|layer| layer.id == id) {
+            Some(index) => {
+                self.layers.remove(index + 1);
+                true
+            }
+            None => false,
         }
     }
 }
 
+/// The stages an event goes through between its source and subscribers
+///
+/// Native polling, [`SystemWatcher::poll_events`] and injected events all
+/// run here, in this order: watch scope, normalization, transient
+/// suppression, filters and debounce, metadata capture, then stability
+/// tracking, stats and broadcast. Every rejection is counted under its drop
+/// reason. Metadata is only captured for events that will be delivered, so
+/// a filtered-out path costs no `stat` and is never tracked.
+struct EventPipeline<'a> {
+    filter: &'a CompiledFilter,
+    options: &'a WatcherOptions,
+    last_events: &'a DebounceTable,
+    drops: &'a DropCounters,
+    watched_paths: &'a DashMap<PathBuf, WatchEntry>,
+    normalizer: &'a EventNormalizer,
+    stability: &'a StabilityTracker,
+    transient: &'a TransientFilter,
+    permissions: &'a PermissionTracker,
+    clock: &'a dyn Clock,
+    stats: &'a tokio::sync::RwLock<WatcherStats>,
+    event_sender: &'a broadcast::Sender<SystemEvent>,
+}
+
+impl EventPipeline<'_> {
+    /// Deliver one batch: creations that outlived the transient window,
+    /// then up to 10 native events, then files that have settled. Returns
+    /// the delivered events.
+    async fn poll(&self, watcher: &WatcherPtr) -> Vec<SystemEvent> {
+        // Released creations go first, ahead of anything newer
+        let mut events: Vec<SystemEvent> = self
+            .release_transients()
+            .into_iter()
+            .filter_map(|event| self.admit(event))
+            .collect();
+
+        for event in SystemWatcher::read_native_events(watcher, self.drops) {
+            let ready = self.accept(event);
+            events.extend(ready.into_iter().filter_map(|event| self.admit(event)));
+        }
+
+        self.deliver(&mut events).await;
+        events
+    }
+
+    /// Scope and normalize a new event, then offer it to transient
+    /// suppression. Returns the events ready for filtering; a creation this
+    /// event releases comes before it.
+    fn accept(&self, mut event: SystemEvent) -> Vec<SystemEvent> {
+        if !in_watch_scope(&event.path, self.watched_paths) {
+            debug!("SystemWatcher: Out of scope: {}", event.path.display());
+            self.drops.record(DropReason::OutOfScope);
+            return vec![];
+        }
+
+        event.event_type = match self.normalizer.normalize(
+            self.options.normalization,
+            &event.path,
+            event.event_type,
+            event.timestamp,
+        ) {
+            Some(event_type) => event_type,
+            None => {
+                debug!("SystemWatcher: Normalized away: {}", event.path.display());
+                self.drops.record(DropReason::Normalized);
+                return vec![];
+            }
+        };
+
+        self.hold_transient(event)
+    }
+
+    /// Apply the size and path filters and debouncing, counting the reason
+    /// `event` was dropped if it was; an admitted event gets its metadata
+    /// captured unless it already carries some
+    fn admit(&self, mut event: SystemEvent) -> Option<SystemEvent> {
+        let verdict = self.filter.verdict(&event).and_then(|()| {
+            let admitted = self.last_events.admit(
+                &self.filter.filter.debounce_key(&event),
+                self.filter.filter.debounce_ms,
+                self.clock.unix_time_ms(),
+            );
+            if admitted {
+                Ok(())
+            } else {
+                Err(DropReason::Debounced)
+            }
+        });
+        if let Err(reason) = verdict {
+            debug!("SystemWatcher: {reason}: {}", event.path.display());
+            self.drops.record(reason);
+            return None;
+        }
+
+        if event.metadata.is_none() {
+            event.metadata = self.capture_metadata(&event.path, event.event_type);
+        }
+        Some(event)
+    }
+
+    /// Add the files that have settled, count and broadcast `events`
+    async fn deliver(&self, events: &mut Vec<SystemEvent>) {
+        let stable = self.track_stability(events);
+        events.extend(stable);
+
+        if !events.is_empty() {
+            let mut stats = self.stats.write().await;
+            stats.total_events += events.len() as u64;
+            stats.debounce_entries = self.last_events.len();
+        }
+        for event in events.iter() {
+            if self.event_sender.send(event.clone()).is_err() {
+                debug!("No event subscribers, event dropped");
+            }
+        }
+        SystemWatcher::refresh_drop_stats(self.stats, self.drops).await;
+    }
+
+    /// Stat `path` for an event's metadata when `capture_file_ids` is on,
+    /// reporting a permission-only change as `permission_delta`
+    fn capture_metadata(&self, path: &Path, event_type: SystemEventType) -> Option<EventMetadata> {
+        if !self.options.capture_file_ids {
+            return None;
+        }
+
+        let stat = std::fs::metadata(path).ok();
+        let delta = self.permissions.observe(
+            path,
+            event_type,
+            stat.as_ref().and_then(FileAttributes::from_metadata),
+        );
+        let mut metadata = EventMetadata::from_metadata(path, &stat?);
+        metadata.permission_delta = delta;
+        Some(metadata)
+    }
+
+    /// Offer an event to transient suppression when `transient_window_ms`
+    /// is set, returning the events to filter and deliver now
+    fn hold_transient(&self, event: SystemEvent) -> Vec<SystemEvent> {
+        if self.options.transient_window_ms == 0 {
+            return vec![event];
+        }
+
+        let now_ns = self.clock.unix_time_ns();
+        let window_ns = self.options.transient_window_ms.saturating_mul(1_000_000);
+        match self.transient.observe(event, now_ns, window_ns) {
+            TransientOutcome::Release(events) => events,
+            TransientOutcome::Held => vec![],
+            TransientOutcome::Suppressed => {
+                self.drops.record_many(DropReason::Transient, 2);
+                vec![]
+            }
+        }
+    }
+
+    /// Held `Created` events whose transient window elapsed without a delete
+    fn release_transients(&self) -> Vec<SystemEvent> {
+        if self.options.transient_window_ms == 0 {
+            return vec![];
+        }
+        self.transient.take_due(self.clock.unix_time_ns())
+    }
+
+    /// Feed delivered events to the stability tracker and collect the files
+    /// that have now gone `stability_window_ms` without changing
+    fn track_stability(&self, events: &[SystemEvent]) -> Vec<SystemEvent> {
+        if self.options.stability_window_ms == 0 {
+            return vec![];
+        }
+
+        let now_ns = self.clock.unix_time_ns();
+        let window_ns = self.options.stability_window_ms.saturating_mul(1_000_000);
+        for event in events {
+            self.stability.observe(event, now_ns, window_ns);
+        }
+        self.stability.take_stable(now_ns)
+    }
+}
+
+/// Pacing for [`SystemWatcher::watch_many`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegistrationThrottle {
+    /// Registrations started back to back before pausing (at least 1); this
+    /// bounds how many new native watches appear at once
+    pub batch_size: usize,
+    /// Pause between batches; zero only yields to other tasks
+    pub batch_delay: Duration,
+    /// Registrations in flight at once (at least 1). Their tree walks run in
+    /// parallel; the native calls themselves are serialized, since the
+    /// native layer is not thread-safe.
+    pub max_concurrent: usize,
+}
+
+impl Default for RegistrationThrottle {
+    fn default() -> Self {
+        Self {
+            batch_size: 256,
+            batch_delay: Duration::ZERO,
+            max_concurrent: 8,
+        }
+    }
+}
+
+/// Bulk watch registration progress; see [`SystemWatcher::watch_many`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegistrationProgress {
+    /// Watches requested so far
+    pub total: usize,
+    /// Watches registered
+    pub completed: usize,
+    /// Watches that failed to register
+    pub failed: usize,
+}
+
+impl RegistrationProgress {
+    /// Whether every requested watch has been attempted
+    pub fn is_done(&self) -> bool {
+        self.completed + self.failed >= self.total
+    }
+}
+
+/// Events buffered per subscriber by `SystemWatcher::new`
+pub const DEFAULT_EVENT_CHANNEL_CAPACITY: usize = 10_000;
+
 /// High-level system file watcher
 pub struct SystemWatcher {
     watcher: WatcherPtr,
     #[allow(dead_code)]
     hash_engine: Arc<HashEngine>,
-    watched_paths: DashMap<PathBuf, bool>, // path -> recursive
+    watched_paths: Arc<DashMap<PathBuf, WatchEntry>>,
     event_sender: broadcast::Sender<SystemEvent>,
     stats: Arc<tokio::sync::RwLock<WatcherStats>>,
-    event_filter: EventFilter,
-    last_events: Arc<DashMap<PathBuf, u64>>, // path -> timestamp for debouncing
+    /// Event filter and any overrides of it, compiled whenever set
+    filters: Arc<std::sync::RwLock<FilterStack>>,
+    options: WatcherOptions,
+    last_events: Arc<DebounceTable>, // path -> timestamp for debouncing
+    drops: Arc<DropCounters>,
+    normalizer: Arc<EventNormalizer>,
+    stability: Arc<StabilityTracker>,
+    transient: Arc<TransientFilter>,
+    permissions: Arc<PermissionTracker>,
+    clock: Arc<dyn Clock>,
+    registration: Arc<std::sync::Mutex<RegistrationProgress>>,
+    /// Held while adding a native watch; the native layer is not thread-safe
+    native_watch: Arc<std::sync::Mutex<()>>,
     // Background polling task management
     polling_handle: Arc<tokio::sync::RwLock<Option<tokio::task::JoinHandle<()>>>>,
     shutdown_signal: Arc<tokio::sync::Notify>,
@@ -148,13 +904,13 @@ unsafe impl Sync for SystemWatcher {}
 impl SystemWatcher {
     /// Create a stub system watcher for testing/fallback
     pub fn stub() -> Self {
-        let (event_sender, _) = broadcast::channel(10_000);
+        let (event_sender, _) = broadcast::channel(DEFAULT_EVENT_CHANNEL_CAPACITY);
         let hash_engine = Arc::new(HashEngine::new());
 
         SystemWatcher {
             watcher: WatcherPtr::new(std::ptr::null_mut()),
             hash_engine,
-            watched_paths: DashMap::new(),
+            watched_paths: Arc::new(DashMap::new()),
             event_sender,
             stats: Arc::new(tokio::sync::RwLock::new(WatcherStats {
                 pending_events: 0,
@@ -162,9 +918,23 @@ impl SystemWatcher {
                 dropped_events: 0,
                 total_events: 0,
                 watched_directories: 0,
+                skipped_mount_points: 0,
+                skipped_large_dirs: Vec::new(),
+                debounce_entries: 0,
+                dropped_by_reason: BTreeMap::new(),
+                transient_pairs_suppressed: 0,
             })),
-            event_filter: EventFilter::default(),
-            last_events: Arc::new(DashMap::new()),
+            filters: Arc::new(std::sync::RwLock::new(FilterStack::new())),
+            options: WatcherOptions::default(),
+            last_events: Arc::new(DebounceTable::new()),
+            drops: Arc::new(DropCounters::new()),
+            normalizer: Arc::new(EventNormalizer::new()),
+            stability: Arc::new(StabilityTracker::new()),
+            transient: Arc::new(TransientFilter::new()),
+            permissions: Arc::new(PermissionTracker::new()),
+            clock: Arc::new(SystemClock),
+            registration: Arc::new(std::sync::Mutex::new(RegistrationProgress::default())),
+            native_watch: Arc::new(std::sync::Mutex::new(())),
             polling_handle: Arc::new(tokio::sync::RwLock::new(None)),
             shutdown_signal: Arc::new(tokio::sync::Notify::new()),
         }
@@ -172,12 +942,17 @@ impl SystemWatcher {
     
     /// Create a new system watcher
     pub fn new() -> Result<Self> {
+        Self::with_channel_capacity(DEFAULT_EVENT_CHANNEL_CAPACITY)
+    }
+
+    /// Create a new system watcher whose event channel buffers `capacity` events
+    pub fn with_channel_capacity(capacity: usize) -> Result<Self> {
         let watcher = unsafe { ffi::fw_watcher_create() };
         if watcher.is_null() {
             anyhow::bail!("Failed to create system watcher");
         }
 
-        let (event_sender, _) = broadcast::channel(10_000);
+        let (event_sender, _) = broadcast::channel(capacity);
         let hash_engine = Arc::new(HashEngine::new());
 
         info!(
@@ -188,7 +963,7 @@ impl SystemWatcher {
         Ok(SystemWatcher {
             watcher: WatcherPtr::new(watcher),
             hash_engine,
-            watched_paths: DashMap::new(),
+            watched_paths: Arc::new(DashMap::new()),
             event_sender,
             stats: Arc::new(tokio::sync::RwLock::new(WatcherStats {
                 pending_events: 0,
@@ -196,9 +971,23 @@ impl SystemWatcher {
                 dropped_events: 0,
                 total_events: 0,
                 watched_directories: 0,
+                skipped_mount_points: 0,
+                skipped_large_dirs: Vec::new(),
+                debounce_entries: 0,
+                dropped_by_reason: BTreeMap::new(),
+                transient_pairs_suppressed: 0,
             })),
-            event_filter: EventFilter::default(),
-            last_events: Arc::new(DashMap::new()),
+            filters: Arc::new(std::sync::RwLock::new(FilterStack::new())),
+            options: WatcherOptions::default(),
+            last_events: Arc::new(DebounceTable::new()),
+            drops: Arc::new(DropCounters::new()),
+            normalizer: Arc::new(EventNormalizer::new()),
+            stability: Arc::new(StabilityTracker::new()),
+            transient: Arc::new(TransientFilter::new()),
+            permissions: Arc::new(PermissionTracker::new()),
+            clock: Arc::new(SystemClock),
+            registration: Arc::new(std::sync::Mutex::new(RegistrationProgress::default())),
+            native_watch: Arc::new(std::sync::Mutex::new(())),
             polling_handle: Arc::new(tokio::sync::RwLock::new(None)),
             shutdown_signal: Arc::new(tokio::sync::Notify::new()),
         })
@@ -206,48 +995,503 @@ impl SystemWatcher {
 
     /// Watch a directory for file system changes
     pub async fn watch_directory<P: AsRef<Path>>(&self, path: P, recursive: bool) -> Result<()> {
+        self.watch_directory_with(path, WatchSettings::new(recursive)).await
+    }
+
+    /// Watch a directory with per-root settings
+    ///
+    /// Watching a path that is already watched replaces its settings instead
+    /// of failing, so callers can use this to update a watch in place.
+    pub async fn watch_directory_with<P: AsRef<Path>>(
+        &self,
+        path: P,
+        settings: WatchSettings,
+    ) -> Result<()> {
+        self.register_watch(path.as_ref(), settings, WatchHold::Pinned, None)
+            .await?;
+        Ok(())
+    }
+
+    /// Register many watches, paced by `throttle`
+    ///
+    /// At most `batch_size` registrations run back to back before the task
+    /// sleeps for `batch_delay`, so starting up on a huge tree ramps the
+    /// native watch count smoothly instead of spiking it (and running into
+    /// per-user limits such as `max_user_instances`). Progress is readable
+    /// through [`Self::registration_progress`] while this runs.
+    ///
+    /// Up to `max_concurrent` registrations run at once, bounded by a
+    /// semaphore, so the tree walks of large roots overlap.
+    ///
+    /// Returns the watches that failed, in input order; all others are
+    /// registered.
+    pub async fn watch_many<I>(
+        self: &Arc<Self>,
+        watches: I,
+        throttle: RegistrationThrottle,
+    ) -> Vec<(PathBuf, anyhow::Error)>
+    where
+        I: IntoIterator<Item = (PathBuf, WatchSettings)>,
+    {
+        let watches: Vec<_> = watches.into_iter().collect();
+        self.lock_registration().total += watches.len();
+
+        let batch_size = throttle.batch_size.max(1);
+        let permits = Arc::new(tokio::sync::Semaphore::new(throttle.max_concurrent.max(1)));
+        let mut registrations = tokio::task::JoinSet::new();
+        for (index, (path, settings)) in watches.into_iter().enumerate() {
+            if index > 0 && index % batch_size == 0 {
+                if throttle.batch_delay.is_zero() {
+                    tokio::task::yield_now().await;
+                } else {
+                    tokio::time::sleep(throttle.batch_delay).await;
+                }
+            }
+
+            let permit = Arc::clone(&permits)
+                .acquire_owned()
+                .await
+                .expect("registration semaphore is never closed");
+            let watcher = Arc::clone(self);
+            registrations.spawn(async move {
+                let result = watcher
+                    .register_watch(&path, settings, WatchHold::Pinned, None)
+                    .await;
+                drop(permit);
+
+                let mut progress = watcher.lock_registration();
+                match result {
+                    Ok(_) => {
+                        progress.completed += 1;
+                        None
+                    }
+                    Err(e) => {
+                        progress.failed += 1;
+                        Some((index, path, e))
+                    }
+                }
+            });
+        }
+
+        let mut failures = Vec::new();
+        while let Some(joined) = registrations.join_next().await {
+            match joined {
+                Ok(Some(failure)) => failures.push(failure),
+                Ok(None) => {}
+                Err(e) => warn!("Watch registration task failed: {}", e),
+            }
+        }
+        failures.sort_by_key(|(index, ..)| *index);
+        failures.into_iter().map(|(_, path, e)| (path, e)).collect()
+    }
+
+    /// Progress of all `watch_many` calls so far
+    pub fn registration_progress(&self) -> RegistrationProgress {
+        *self.lock_registration()
+    }
+
+    fn lock_registration(&self) -> std::sync::MutexGuard<'_, RegistrationProgress> {
+        self.registration.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Watch a directory for as long as the returned guard is alive
+    ///
+    /// Scoped watches on the same path are reference counted, and a path
+    /// that is already watched keeps its settings; the watch is removed when
+    /// the last guard drops unless it was also added with `watch_directory`.
+    pub async fn watch_scoped<P: AsRef<Path>>(
+        self: &Arc<Self>,
+        path: P,
+        recursive: bool,
+    ) -> Result<WatchGuard> {
         let path = path.as_ref().to_path_buf();
-        
-        // Handle stub watcher
-        if self.watcher.is_null() {
-            info!("Stub watcher: would watch {} (recursive: {})", path.display(), recursive);
-            self.watched_paths.insert(path.clone(), recursive);
-            
-            // Update stats
-            {
-                let mut stats = self.stats.write().await;
-                stats.watched_directories = self.watched_paths.len();
+        // Count the guard under the entry's lock, so an unwatch or the last
+        // guard of a live watch cannot remove it in between
+        let live = self.watched_paths.get_mut(&path).map(|mut entry| {
+            entry.scoped_guards += 1;
+            entry.generation
+        });
+        let generation = match live {
+            Some(generation) => generation,
+            None => {
+                self.register_watch(
+                    &path,
+                    WatchSettings::new(recursive),
+                    WatchHold::Scoped,
+                    None,
+                )
+                .await?
+            }
+        };
+
+        Ok(WatchGuard {
+            watcher: Arc::clone(self),
+            path,
+            generation,
+        })
+    }
+
+    /// Watch an explicit list of files, ignoring their siblings
+    ///
+    /// Files are grouped by parent directory and each parent gets a single
+    /// non-recursive watch (one inotify watch on Linux), filtered to the
+    /// listed names. Listing more files in an already listed directory
+    /// extends its list, and a directory watched as a whole stays unfiltered.
+    /// A later `watch_directory` on a listed directory updates its settings
+    /// but keeps the list; unwatch it first to watch all of its children.
+    /// Files need not exist yet, but their parent must.
+    ///
+    /// Returns one result per path, in order, so callers can handle files
+    /// whose directory could not be watched.
+    pub async fn watch_files(&self, paths: &[PathBuf]) -> Vec<Result<()>> {
+        let mut results: Vec<Option<Result<()>>> = paths.iter().map(|_| None).collect();
+        let mut by_parent: BTreeMap<PathBuf, Vec<(usize, OsString)>> = BTreeMap::new();
+        for (index, path) in paths.iter().enumerate() {
+            match (path.parent(), path.file_name()) {
+                (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => {
+                    by_parent
+                        .entry(parent.to_path_buf())
+                        .or_default()
+                        .push((index, name.to_os_string()));
+                }
+                _ => {
+                    results[index] = Some(Err(anyhow::anyhow!(
+                        "Not a file path: {}",
+                        path.display()
+                    )));
+                }
+            }
+        }
+
+        for (parent, files) in by_parent {
+            let outcome = self.watch_parent_of_files(&parent, &files).await;
+            for (index, _) in files {
+                results[index] = Some(match &outcome {
+                    Ok(()) => Ok(()),
+                    Err(e) => Err(anyhow::anyhow!("{e:#}")),
+                });
+            }
+        }
+
+        results
+            .into_iter()
+            .map(|result| result.expect("every path gets a result"))
+            .collect()
+    }
+
+    /// Admit `files` in `parent`, watching it if it is not watched yet
+    async fn watch_parent_of_files(&self, parent: &Path, files: &[(usize, OsString)]) -> Result<()> {
+        let names: HashSet<OsString> = files.iter().map(|(_, name)| name.clone()).collect();
+        // Extend a live list under the entry's lock
+        let watched = self.watched_paths.get_mut(parent).map(|mut entry| {
+            // The whole directory is already watched when there is no list
+            if let Some(listed) = entry.files.as_mut() {
+                listed.extend(names.iter().cloned());
+            }
+        });
+
+        if watched.is_none() {
+            // The list goes in with the registration, so siblings are never
+            // admitted; a registration racing this one merges its list
+            self.register_watch(
+                parent,
+                WatchSettings::new(false),
+                WatchHold::Pinned,
+                Some(names),
+            )
+            .await
+            .with_context(|| format!("Failed to watch files in {}", parent.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Register or update a watch; returns the registration's generation
+    ///
+    /// `files` limits a new watch to those direct children. A live watch
+    /// keeps its list across updates, merged with `files`, and one watching
+    /// the whole directory stays that way.
+    async fn register_watch(
+        &self,
+        path: &Path,
+        settings: WatchSettings,
+        hold: WatchHold,
+        files: Option<HashSet<OsString>>,
+    ) -> Result<u64> {
+        let path = path.to_path_buf();
+        let recursive = settings.recursive;
+        let cross_filesystem = settings.cross_filesystem;
+        let patterns = PathPatterns::new(&settings.include_patterns, &settings.exclude_patterns)
+            .with_context(|| format!("Invalid patterns for watch: {}", path.display()))?;
+        let existing = self
+            .watched_paths
+            .get(&path)
+            .map(|entry| entry.native_recursive);
+
+        // Only touch the native layer for new roots or when widening to recursive
+        let needs_native = match existing {
+            Some(native_recursive) => recursive && !native_recursive,
+            None => true,
+        };
+
+        let max_entries_per_dir = self.options.max_entries_per_dir;
+        // Mount points are only detected on Unix; elsewhere there is nothing
+        // to walk the tree for unless directories are size-limited
+        let find_mounts = cfg!(unix) && !cross_filesystem;
+        // An update that leaves the native watch alone keeps the boundaries
+        // found when it was set up, so the tree is only walked then
+        let scan = needs_native && recursive;
+        let boundaries = if scan && (find_mounts || max_entries_per_dir > 0) {
+            let options = BoundaryOptions {
+                cross_filesystem,
+                max_entries_per_dir,
+            };
+            // The walk visits every directory of the tree; keep it off the
+            // async worker threads
+            let root = path.clone();
+            tokio::task::spawn_blocking(move || find_watch_boundaries(&root, &options))
+                .await
+                .context("Watch boundary scan panicked")?
+                .unwrap_or_default()
+        } else {
+            Default::default()
+        };
+        for mount in &boundaries.mount_points {
+            info!(
+                "Not crossing filesystem boundary at {} (watch: {})",
+                mount.display(),
+                path.display()
+            );
+        }
+        for dir in &boundaries.large_dirs {
+            warn!(
+                "Not descending into {}: more than {} entries (watch: {})",
+                dir.display(),
+                max_entries_per_dir,
+                path.display()
+            );
+        }
+
+        if needs_native {
+            if self.watcher.is_null() {
+                // Handle stub watcher
+                info!("Stub watcher: would watch {} (recursive: {})", path.display(), recursive);
+            } else {
+                let path_str = path
+                    .to_str()
+                    .with_context(|| format!("Invalid path: {}", path.display()))?;
+
+                let c_path = CString::new(path_str)?;
+
+                let _native = self.native_watch.lock().unwrap_or_else(|e| e.into_inner());
+                let result = unsafe {
+                    ffi::fw_watcher_watch_directory(
+                        self.watcher.as_ptr(),
+                        c_path.as_ptr(),
+                        recursive,
+                        cross_filesystem,
+                        max_entries_per_dir,
+                    )
+                };
+
+                if result != 0 {
+                    anyhow::bail!("Failed to watch directory: {}", path.display());
+                }
+            }
+        }
+
+        let mut entry = WatchEntry {
+            settings,
+            patterns,
+            native_recursive: recursive,
+            pinned: hold == WatchHold::Pinned,
+            scoped_guards: usize::from(hold == WatchHold::Scoped),
+            generation: 0,
+            skipped_mounts: boundaries.mount_points,
+            skipped_large_dirs: boundaries.large_dirs,
+            files,
+        };
+        // Read and replace the previous entry under one lock, so guards
+        // counted concurrently by `watch_scoped` are not lost
+        let generation = match self.watched_paths.entry(path.clone()) {
+            dashmap::mapref::entry::Entry::Occupied(mut occupied) => {
+                let previous = occupied.get();
+                entry.native_recursive |= previous.native_recursive;
+                entry.pinned |= previous.pinned;
+                entry.scoped_guards += previous.scoped_guards;
+                entry.generation = previous.generation;
+                if !needs_native {
+                    entry.skipped_mounts = previous.skipped_mounts.clone();
+                    entry.skipped_large_dirs = previous.skipped_large_dirs.clone();
+                }
+                entry.files = match (&previous.files, entry.files.take()) {
+                    (Some(listed), Some(mut added)) => {
+                        added.extend(listed.iter().cloned());
+                        Some(added)
+                    }
+                    (Some(listed), None) => Some(listed.clone()),
+                    (None, _) => None,
+                };
+                let generation = entry.generation;
+                occupied.insert(entry);
+                generation
             }
-            return Ok(());
+            dashmap::mapref::entry::Entry::Vacant(vacant) => {
+                entry.generation = NEXT_WATCH_GENERATION.fetch_add(1, Ordering::Relaxed);
+                let generation = entry.generation;
+                vacant.insert(entry);
+                generation
+            }
+        };
+        self.update_watched_count().await;
+
+        if existing.is_some() {
+            info!(
+                "Updated watch settings: {} (recursive: {})",
+                path.display(),
+                recursive
+            );
+        } else {
+            info!(
+                "Watching directory: {} (recursive: {})",
+                path.display(),
+                recursive
+            );
         }
-        
-        let path_str = path
-            .to_str()
-            .with_context(|| format!("Invalid path: {}", path.display()))?;
+        Ok(generation)
+    }
 
-        let c_path = CString::new(path_str)?;
+    /// Stop watching a directory, removing its native watch
+    ///
+    /// A root nested in or containing another watched root shares native
+    /// watches with it, so its native watch stays until the overlap is gone.
+    /// Returns `false` if the path was not being watched.
+    pub async fn unwatch_directory<P: AsRef<Path>>(&self, path: P) -> bool {
+        let path = path.as_ref();
+        let was_active = self.watched_paths.remove(path).is_some();
+        if was_active {
+            self.remove_native_watch(path);
+            self.update_watched_count().await;
+            info!("Stopped watching directory: {}", path.display());
+        }
+        was_active
+    }
 
-        // Call the FFI function with a timeout to prevent infinite hanging
-        let result = unsafe { ffi::fw_watcher_watch_directory(self.watcher.as_ptr(), c_path.as_ptr(), recursive) };
+    /// Drop the native watch of `path`, whose entry was just removed
+    fn remove_native_watch(&self, path: &Path) {
+        let overlapping = self
+            .watched_paths
+            .iter()
+            .any(|entry| entry.key().starts_with(path) || path.starts_with(entry.key()));
+        if overlapping {
+            debug!(
+                "Keeping native watch of {}: another root overlaps it",
+                path.display()
+            );
+            return;
+        }
+        if self.watcher.is_null() {
+            info!("Stub watcher: would unwatch {}", path.display());
+            return;
+        }
 
+        let Some(c_path) = path.to_str().and_then(|path| CString::new(path).ok()) else {
+            warn!("Cannot unwatch invalid path: {}", path.display());
+            return;
+        };
+        let _native = self.native_watch.lock().unwrap_or_else(|e| e.into_inner());
+        let result =
+            unsafe { ffi::fw_watcher_unwatch_directory(self.watcher.as_ptr(), c_path.as_ptr()) };
         if result != 0 {
-            anyhow::bail!("Failed to watch directory: {}", path.display());
+            warn!("Failed to remove native watch: {}", path.display());
         }
+    }
 
-        self.watched_paths.insert(path.clone(), recursive);
+    /// Whether `path` is a watched root
+    pub fn is_watched<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.watched_paths.contains_key(path.as_ref())
+    }
 
-        // Update stats
-        {
-            let mut stats = self.stats.write().await;
-            stats.watched_directories = self.watched_paths.len();
+    /// Watched roots and their settings, sorted by path
+    pub fn watched_paths(&self) -> Vec<(PathBuf, WatchSettings)> {
+        let mut watches: Vec<_> = self
+            .watched_paths
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.settings.clone()))
+            .collect();
+        watches.sort_by(|a, b| a.0.cmp(&b.0));
+        watches
+    }
+
+    /// Handle for resolving the hash strategy configured for an event path
+    pub fn root_hash_strategies(&self) -> RootHashStrategies {
+        RootHashStrategies {
+            watches: Arc::clone(&self.watched_paths),
         }
+    }
 
-        info!(
-            "Watching directory: {} (recursive: {})",
-            path.display(),
-            recursive
-        );
-        Ok(())
+    async fn update_watched_count(&self) {
+        let mut stats = self.stats.write().await;
+        self.refresh_watch_stats(&mut stats);
+    }
+
+    fn refresh_watch_stats(&self, stats: &mut WatcherStats) {
+        let (active, skipped_mounts) = self.watch_counts();
+        stats.watched_directories = active;
+        stats.skipped_mount_points = skipped_mounts;
+        stats.skipped_large_dirs = self.skipped_large_dirs();
+    }
+
+    /// Watches, and mount points those watches did not cross into
+    fn watch_counts(&self) -> (usize, usize) {
+        self.watched_paths
+            .iter()
+            .fold((0, 0), |(active, mounts), entry| {
+                (active + 1, mounts + entry.skipped_mounts.len())
+            })
+    }
+
+    /// Mount points under active watches that were not descended into
+    pub fn skipped_mount_points(&self) -> Vec<PathBuf> {
+        let mut mounts: Vec<PathBuf> = self
+            .watched_paths
+            .iter()
+            .flat_map(|entry| entry.skipped_mounts.clone())
+            .collect();
+        mounts.sort();
+        mounts.dedup();
+        mounts
+    }
+
+    /// Directories under active watches too large to descend into
+    pub fn skipped_large_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = self
+            .watched_paths
+            .iter()
+            .flat_map(|entry| entry.skipped_large_dirs.clone())
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+        dirs
+    }
+
+    /// Drop one scoped reference held on registration `generation`; returns
+    /// true if that was the last one and the watch was removed
+    fn release_scoped(&self, path: &Path, generation: u64) -> bool {
+        // Count down and remove under one lock, so a guard added meanwhile
+        // by `watch_scoped` either keeps the watch or registers a new one
+        let removed = self.watched_paths.remove_if_mut(path, |_, entry| {
+            // A guard from before an unwatch no longer counts toward the watch
+            if entry.generation != generation {
+                return false;
+            }
+            entry.scoped_guards = entry.scoped_guards.saturating_sub(1);
+            entry.scoped_guards == 0 && !entry.pinned
+        });
+        if removed.is_none() {
+            return false;
+        }
+        self.remove_native_watch(path);
+        true
     }
 
     /// Start the file system monitoring  
@@ -282,9 +1526,17 @@ impl SystemWatcher {
         let event_sender = self.event_sender.clone();
         let stats = Arc::clone(&self.stats);
         let last_events = Arc::clone(&self.last_events);
+        let drops = Arc::clone(&self.drops);
+        let watched_paths = Arc::clone(&self.watched_paths);
+        let normalizer = Arc::clone(&self.normalizer);
+        let stability = Arc::clone(&self.stability);
+        let transient = Arc::clone(&self.transient);
+        let permissions = Arc::clone(&self.permissions);
         let shutdown_signal = Arc::clone(&self.shutdown_signal);
         let watcher_ptr = WatcherPtr::new(self.watcher.as_ptr()); // Clone the pointer
-        let event_filter = self.event_filter.clone();
+        let filters = Arc::clone(&self.filters);
+        let options = self.options.clone();
+        let clock = Arc::clone(&self.clock);
 
         let handle = tokio::spawn(async move {
             info!("SystemWatcher: Starting background polling loop...");
@@ -293,8 +1545,16 @@ impl SystemWatcher {
                 event_sender,
                 stats,
                 last_events,
+                drops,
+                watched_paths,
+                normalizer,
+                stability,
+                transient,
+                permissions,
                 shutdown_signal,
-                event_filter,
+                filters,
+                options,
+                clock,
             ).await;
             info!("SystemWatcher: Background polling loop ended");
         });
@@ -305,13 +1565,22 @@ impl SystemWatcher {
     }
 
     /// Background polling loop - this is the missing link!
+    #[allow(clippy::too_many_arguments)]
     async fn polling_loop(
         watcher: WatcherPtr,
         event_sender: broadcast::Sender<SystemEvent>,
         stats: Arc<tokio::sync::RwLock<WatcherStats>>,
-        last_events: Arc<DashMap<PathBuf, u64>>,
+        last_events: Arc<DebounceTable>,
+        drops: Arc<DropCounters>,
+        watched_paths: Arc<DashMap<PathBuf, WatchEntry>>,
+        normalizer: Arc<EventNormalizer>,
+        stability: Arc<StabilityTracker>,
+        transient: Arc<TransientFilter>,
+        permissions: Arc<PermissionTracker>,
         shutdown_signal: Arc<tokio::sync::Notify>,
-        event_filter: EventFilter,
+        filters: Arc<std::sync::RwLock<FilterStack>>,
+        options: WatcherOptions,
+        clock: Arc<dyn Clock>,
     ) {
         info!("SystemWatcher: Polling loop started - begin monitoring for events...");
         let mut interval = tokio::time::interval(Duration::from_millis(5)); // 5ms for production performance
@@ -327,28 +1596,25 @@ impl SystemWatcher {
             // Simple approach: wait for tick, then check shutdown
             tokio::select! {
                 _ = interval.tick() => {
-                    // Poll for events from the Zig layer
-                    let events = Self::poll_events_internal(
-                        &watcher,
-                        &event_filter,
-                        &last_events
-                    ).await;
-
+                    // Pick up filter changes made since the last tick
+                    let active = filters.read().unwrap_or_else(|e| e.into_inner()).active();
+                    let pipeline = EventPipeline {
+                        filter: &active,
+                        options: &options,
+                        last_events: &last_events,
+                        drops: &drops,
+                        watched_paths: &watched_paths,
+                        normalizer: &normalizer,
+                        stability: &stability,
+                        transient: &transient,
+                        permissions: &permissions,
+                        clock: &*clock,
+                        stats: &stats,
+                        event_sender: &event_sender,
+                    };
+
+                    let events = pipeline.poll(&watcher).await;
                     if !events.is_empty() {
-                        info!("SystemWatcher: 🎉 FOUND {} EVENTS! Processing...", events.len());
-                        
-                        // Update stats
-                        {
-                            let mut stats_guard = stats.write().await;
-                            stats_guard.total_events += events.len() as u64;
-                        }
-
-                        // Send events to subscribers
-                        for event in events.iter() {
-                            if let Err(_) = event_sender.send(event.clone()) {
-                                debug!("No event subscribers, event dropped");
-                            }
-                        }
                         info!("SystemWatcher: Processed {} file events successfully", events.len());
                     }
                 }
@@ -361,12 +1627,8 @@ impl SystemWatcher {
         }
     }
 
-    /// Internal polling function (static to work in async task)
-    async fn poll_events_internal(
-        watcher: &WatcherPtr,
-        event_filter: &EventFilter,
-        last_events: &DashMap<PathBuf, u64>,
-    ) -> Vec<SystemEvent> {
+    /// Read up to 10 events from the Zig layer, without filtering them
+    fn read_native_events(watcher: &WatcherPtr, drops: &DropCounters) -> Vec<SystemEvent> {
         if watcher.is_null() {
             return vec![];
         }
@@ -396,6 +1658,7 @@ impl SystemWatcher {
             // Convert FFI event to Rust event
             let path = if ffi_event.path.is_null() {
                 warn!("SystemWatcher: FFI event has NULL path, skipping");
+                drops.record(DropReason::InvalidPath);
                 continue;
             } else {
                 let path_cstr = unsafe { CStr::from_ptr(ffi_event.path) };
@@ -403,6 +1666,7 @@ impl SystemWatcher {
                     Ok(path_str) => PathBuf::from(path_str),
                     Err(e) => {
                         warn!("SystemWatcher: Invalid path in event: {}", e);
+                        drops.record(DropReason::InvalidPath);
                         continue;
                     }
                 }
@@ -420,249 +1684,283 @@ impl SystemWatcher {
                 },
             };
 
-            let system_event = SystemEvent {
-                path: path.clone(),
+            events.push(SystemEvent {
+                path,
                 event_type,
                 timestamp: ffi_event.timestamp,
                 size: ffi_event.size,
                 is_directory: ffi_event.is_directory,
-            };
-
-            // Apply filtering and debouncing
-            info!("SystemWatcher: Processing event: path={:?}, size={}, type={:?}", 
-                   system_event.path, system_event.size, system_event.event_type);
-            if Self::should_process_event_static(&system_event, event_filter, last_events) {
-                info!("SystemWatcher: ✅ Event passed filters, adding to results");
-                events.push(system_event);
-            } else {
-                info!("SystemWatcher: ❌ Event rejected by filters");
-            }
+                metadata: None,
+            });
         }
         
         debug!("SystemWatcher: Polled {} events from Zig layer", events.len());
         events
     }
 
-    /// Static version of should_process_event for use in async task
-    fn should_process_event_static(
-        event: &SystemEvent,
-        event_filter: &EventFilter,
-        last_events: &DashMap<PathBuf, u64>,
-    ) -> bool {
-        info!("SystemWatcher: Filtering event - path={:?}, size={}, min_size={}", 
-               event.path, event.size, event_filter.min_file_size);
-        
-        // Skip if file is too small
-        if event.size < event_filter.min_file_size {
-            info!("SystemWatcher: ❌ Event rejected - file too small ({} < {})", 
-                   event.size, event_filter.min_file_size);
-            return false;
+    /// The event pipeline as currently configured, applying `filter`
+    fn pipeline<'a>(&'a self, filter: &'a CompiledFilter) -> EventPipeline<'a> {
+        EventPipeline {
+            filter,
+            options: &self.options,
+            last_events: &self.last_events,
+            drops: &self.drops,
+            watched_paths: &self.watched_paths,
+            normalizer: &self.normalizer,
+            stability: &self.stability,
+            transient: &self.transient,
+            permissions: &self.permissions,
+            clock: &*self.clock,
+            stats: &self.stats,
+            event_sender: &self.event_sender,
         }
+    }
 
-        // Skip if file is too large
-        if let Some(max_size) = event_filter.max_file_size {
-            if event.size > max_size {
-                return false;
-            }
+    /// Copy the drop counters into the shared stats
+    async fn refresh_drop_stats(
+        stats: &tokio::sync::RwLock<WatcherStats>,
+        drops: &DropCounters,
+    ) {
+        let dropped = drops.total();
+        if stats.read().await.dropped_events == dropped {
+            return;
         }
+        let mut stats = stats.write().await;
+        stats.dropped_events = dropped;
+        stats.dropped_by_reason = drops.snapshot();
+        // Each suppressed pair is recorded as two transient drops
+        stats.transient_pairs_suppressed = drops.get(DropReason::Transient) / 2;
+    }
 
-        // Apply path-based filtering
-        let path_str = event.path.to_string_lossy();
-        info!("SystemWatcher: Checking path patterns - exclude: {:?}, include: {:?}", 
-               event_filter.exclude_patterns, event_filter.include_patterns);
-        
-        // Check exclude patterns first (more common)
-        for pattern in &event_filter.exclude_patterns {
-            if glob_match(pattern, &path_str) {
-                info!("SystemWatcher: ❌ Event rejected - excluded by pattern '{}'", pattern);
-                return false;
-            }
-        }
+    /// Subscribe to file system events
+    pub fn subscribe(&self) -> broadcast::Receiver<SystemEvent> {
+        self.event_sender.subscribe()
+    }
 
-        // Check include patterns (if any specified)
-        if !event_filter.include_patterns.is_empty() {
-            let mut included = false;
-            for pattern in &event_filter.include_patterns {
-                if glob_match(pattern, &path_str) {
-                    included = true;
-                    break;
-                }
-            }
-            if !included {
-                info!("SystemWatcher: ❌ Event rejected - not included by any include pattern");
-                return false;
-            }
-        }
+    /// Events held in the event channel for subscribers that have not read
+    /// them yet
+    pub fn buffered_events(&self) -> usize {
+        self.event_sender.len()
+    }
 
-        // Apply debouncing
-        if event_filter.debounce_ms > 0 {
-            let current_time = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_millis() as u64;
+    /// Subscribe, then list the current files of every watched tree
+    ///
+    /// The receiver is created before anything is read from disk, so a
+    /// change made while the snapshot is taken is always delivered on it and
+    /// nothing falls between the two. Such a change may also be reflected in
+    /// the snapshot, so consumers should apply the snapshot first and treat
+    /// the replayed events as idempotent updates.
+    ///
+    /// The snapshot holds one synthetic `Created` event per regular file the
+    /// watches would report on (same scope, patterns and size filter), sorted
+    /// by path. Symlinked directories are not followed. The filesystem is
+    /// read synchronously, so use `spawn_blocking` for large trees.
+    pub fn subscribe_with_snapshot(&self) -> (Vec<SystemEvent>, broadcast::Receiver<SystemEvent>) {
+        let receiver = self.event_sender.subscribe();
+        let timestamp = self.clock.unix_time_ns();
+
+        let roots: Vec<(PathBuf, bool, Vec<PathBuf>, Vec<PathBuf>)> = self
+            .watched_paths
+            .iter()
+            .map(|entry| {
+                (
+                    entry.key().clone(),
+                    entry.settings.recursive,
+                    entry.skipped_mounts.clone(),
+                    entry.skipped_large_dirs.clone(),
+                )
+            })
+            .collect();
+
+        let mut seen = HashSet::new();
+        let mut snapshot = Vec::new();
+        for (root, recursive, skipped_mounts, skipped_large_dirs) in roots {
+            let mut stack = vec![root];
+            while let Some(dir) = stack.pop() {
+                let Ok(entries) = std::fs::read_dir(&dir) else {
+                    continue; // Unreadable directories are skipped
+                };
+                let descend = recursive && !skipped_large_dirs.contains(&dir);
+
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                        if descend && !skipped_mounts.contains(&path) {
+                            stack.push(path);
+                        }
+                        continue;
+                    }
+
+                    let Ok(metadata) = std::fs::metadata(&path) else {
+                        continue;
+                    };
+                    if !metadata.is_file() || seen.contains(&path) {
+                        continue;
+                    }
 
-            if let Some(last_time) = last_events.get(&event.path) {
-                if current_time - *last_time < event_filter.debounce_ms {
-                    return false;
+                    let event_metadata = self
+                        .options
+                        .capture_file_ids
+                        .then(|| EventMetadata::from_metadata(&path, &metadata));
+                    let event = SystemEvent {
+                        path,
+                        event_type: SystemEventType::Created,
+                        timestamp,
+                        size: metadata.len(),
+                        is_directory: false,
+                        metadata: event_metadata,
+                    };
+                    let in_scope = in_watch_scope(&event.path, &self.watched_paths);
+                    if in_scope && self.passes_filter(&event) {
+                        seen.insert(event.path.clone());
+                        snapshot.push(event);
+                    }
                 }
             }
-
-            // Update last event time
-            last_events.insert(event.path.clone(), current_time);
         }
 
-        info!("SystemWatcher: ✅ Event passed all filters!");
-        true
-    }
-
-    /// Subscribe to file system events
-    pub fn subscribe(&self) -> broadcast::Receiver<SystemEvent> {
-        self.event_sender.subscribe()
+        snapshot.sort_by(|a, b| a.path.cmp(&b.path));
+        (snapshot, receiver)
     }
 
     /// Update event filter from config patterns
-    pub fn update_event_filter(&mut self, include_patterns: Vec<String>, exclude_patterns: Vec<String>) {
+    ///
+    /// Fails without changing the filter if any pattern is not a valid glob.
+    pub fn update_event_filter(&mut self, include_patterns: Vec<String>, exclude_patterns: Vec<String>) -> Result<()> {
         info!("SystemWatcher: Updating event filters - include: {:?}, exclude: {:?}", include_patterns, exclude_patterns);
-        self.event_filter.include_patterns = include_patterns;
-        self.event_filter.exclude_patterns = exclude_patterns;
+        let mut filters = self.write_filters();
+        let mut filter = filters.layers[0].filter.clone();
+        filter.include_patterns = include_patterns;
+        filter.exclude_patterns = exclude_patterns;
+        let patterns = filter.compile_patterns()?;
+        filters.set_base(filter, patterns);
+        Ok(())
     }
 
     /// Poll for events manually (non-blocking)
     pub async fn poll_events(&self) -> Result<Vec<SystemEvent>> {
-        if self.watcher.is_null() {
-            return Ok(vec![]);
-        }
+        let active = self.active_filter();
+        Ok(self.pipeline(&active).poll(&self.watcher).await)
+    }
 
+    /// Push a synthetic event through the watcher as if the native layer had
+    /// reported it: watch scope, normalization, filters, debounce, metadata
+    /// capture (unless the event carries metadata), stats and broadcast all
+    /// apply. Returns whether subscribers were sent the event; a `Created`
+    /// held by transient suppression has not been, yet.
+    ///
+    /// Canonical normalization drops a `Created` event for a path that does
+    /// not exist, so tests without real files should use
+    /// `EventNormalization::Raw`.
+    #[cfg(any(test, feature = "testing"))]
+    pub async fn inject_event(&self, event: SystemEvent) -> bool {
+        let active = self.active_filter();
+        let pipeline = self.pipeline(&active);
+
+        // A held creation released by this event is delivered ahead of it,
+        // so the injected event is always the last one ready
+        let mut sent = false;
         let mut events = Vec::new();
-        
-        // Poll up to 10 events at a time to avoid blocking too long
-        for _ in 0..10 {
-            let mut ffi_event = ffi::FileEvent {
-                path: std::ptr::null(),
-                event_type: 0,
-                timestamp: 0,
-                size: 0,
-                is_directory: false,
-            };
+        for event in pipeline.accept(event) {
+            let admitted = pipeline.admit(event);
+            sent = admitted.is_some();
+            events.extend(admitted);
+        }
+        pipeline.deliver(&mut events).await;
+        sent
+    }
 
-            let has_event = unsafe { ffi::fw_watcher_poll_event(self.watcher.as_ptr(), &mut ffi_event) };
-            
-            if !has_event {
-                break;
-            }
+    /// Set event filter configuration
+    ///
+    /// The patterns are compiled here, once, rather than on every event. Fails
+    /// without changing the filter if any pattern is not a valid glob. While
+    /// an override is pushed, the new filter applies once it is removed.
+    pub fn set_event_filter(&mut self, filter: EventFilter) -> Result<()> {
+        let patterns = filter.compile_patterns()?;
+        self.write_filters().set_base(filter, patterns);
+        Ok(())
+    }
 
-            // Convert FFI event to Rust event
-            let path = if ffi_event.path.is_null() {
-                continue;
-            } else {
-                let path_cstr = unsafe { CStr::from_ptr(ffi_event.path) };
-                match path_cstr.to_str() {
-                    Ok(path_str) => PathBuf::from(path_str),
-                    Err(e) => {
-                        warn!("Invalid path in event: {}", e);
-                        continue;
-                    }
-                }
-            };
+    /// Override the event filter until the matching [`pop_filter`](Self::pop_filter)
+    ///
+    /// Overrides nest: each pop restores the filter that was active before
+    /// the corresponding push. Takes effect on a running watcher at its next
+    /// poll. Fails without changing anything if a pattern is not a valid glob.
+    pub fn push_filter(&self, filter: EventFilter) -> Result<()> {
+        let patterns = filter.compile_patterns()?;
+        self.write_filters().push(filter, patterns);
+        Ok(())
+    }
 
-            let event_type = match ffi_event.event_type {
-                1 => SystemEventType::Created,
-                2 => SystemEventType::Modified,
-                3 => SystemEventType::Deleted,
-                4 => SystemEventType::Moved,
-                5 => SystemEventType::MetadataChanged,
-                _ => continue,
-            };
+    /// Remove the most recent override, returning it; `None` if only the
+    /// configured filter is left, which is never popped
+    pub fn pop_filter(&self) -> Option<EventFilter> {
+        self.write_filters().pop()
+    }
 
-            let system_event = SystemEvent {
-                path: path.clone(),
-                event_type,
-                timestamp: ffi_event.timestamp,
-                size: ffi_event.size,
-                is_directory: ffi_event.is_directory,
+    /// Override the event filter for `duration`, then restore the previous one
+    ///
+    /// Behaves like [`push_filter`](Self::push_filter) with a timer that
+    /// removes this override, and only this one, wherever it sits in the
+    /// stack by then; if it was already popped, the timer does nothing. Must
+    /// be called within a Tokio runtime.
+    pub fn with_temporary_filter(&self, filter: EventFilter, duration: Duration) -> Result<()> {
+        let patterns = filter.compile_patterns()?;
+        let id = self.write_filters().push(filter, patterns);
+
+        let filters = Arc::downgrade(&self.filters);
+        tokio::spawn(async move {
+            tokio::time::sleep(duration).await;
+            let Some(filters) = filters.upgrade() else {
+                return;
             };
-
-            // Apply filtering and debouncing
-            if self.should_process_event(&system_event) {
-                // Send to subscribers
-                if let Err(_) = self.event_sender.send(system_event.clone()) {
-                    debug!("No event subscribers");
-                }
-
-                events.push(system_event);
+            let mut filters = filters.write().unwrap_or_else(|e| e.into_inner());
+            if filters.remove(id) {
+                debug!("SystemWatcher: Temporary filter expired after {duration:?}");
             }
-        }
-
-        // Update stats
-        if !events.is_empty() {
-            let mut stats_guard = self.stats.write().await;
-            stats_guard.total_events += events.len() as u64;
-        }
-
-        Ok(events)
+        });
+        Ok(())
     }
 
-    /// Set event filter configuration
-    pub fn set_event_filter(&mut self, filter: EventFilter) {
-        self.event_filter = filter;
+    /// The event filter currently in effect
+    pub fn event_filter(&self) -> EventFilter {
+        self.active_filter().filter.clone()
     }
 
-    /// Check if an event should be processed based on filters
-    fn should_process_event(&self, event: &SystemEvent) -> bool {
-        // Skip if file is too small
-        if event.size < self.event_filter.min_file_size {
-            return false;
-        }
-
-        // Skip if file is too large
-        if let Some(max_size) = self.event_filter.max_file_size {
-            if event.size > max_size {
-                return false;
-            }
-        }
-
-        // Apply path-based filtering
-        let path_str = event.path.to_string_lossy();
-        
-        // Check exclude patterns first (more common)
-        for pattern in &self.event_filter.exclude_patterns {
-            if glob_match(pattern, &path_str) {
-                return false;
-            }
-        }
+    fn active_filter(&self) -> Arc<CompiledFilter> {
+        let filters = self.filters.read().unwrap_or_else(|e| e.into_inner());
+        filters.active()
+    }
 
-        // Check include patterns (if any specified)
-        if !self.event_filter.include_patterns.is_empty() {
-            let mut included = false;
-            for pattern in &self.event_filter.include_patterns {
-                if glob_match(pattern, &path_str) {
-                    included = true;
-                    break;
-                }
-            }
-            if !included {
-                return false;
-            }
-        }
+    fn write_filters(&self) -> std::sync::RwLockWriteGuard<'_, FilterStack> {
+        self.filters.write().unwrap_or_else(|e| e.into_inner())
+    }
 
-        // Apply debouncing
-        if self.event_filter.debounce_ms > 0 {
-            let current_time = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_millis() as u64;
+    /// Set event ingestion options (takes effect when the watcher is started)
+    pub fn set_options(&mut self, options: WatcherOptions) {
+        self.last_events = Arc::new(DebounceTable::with_max_entries(
+            options.max_debounce_entries,
+        ));
+        self.permissions = Arc::new(PermissionTracker::with_max_entries(
+            options.max_debounce_entries,
+        ));
+        self.options = options;
+    }
 
-            if let Some(last_time) = self.last_events.get(&event.path) {
-                if current_time - *last_time < self.event_filter.debounce_ms {
-                    return false;
-                }
-            }
+    /// Read the time for debouncing and stability windows from `clock`
+    /// (takes effect when the watcher is started)
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
 
-            // Update last event time
-            self.last_events.insert(event.path.clone(), current_time);
-        }
+    /// Get current event ingestion options
+    pub fn options(&self) -> &WatcherOptions {
+        &self.options
+    }
 
-        true
+    /// Size and path filters, without debouncing
+    fn passes_filter(&self, event: &SystemEvent) -> bool {
+        self.active_filter().verdict(event).is_ok()
     }
 
     /// Get current watcher statistics
@@ -670,6 +1968,16 @@ impl SystemWatcher {
         self.stats.read().await.clone()
     }
 
+    /// Read watcher statistics while holding the stats lock
+    ///
+    /// The polling task cannot update the counters until `f` returns, so other
+    /// counters read inside `f` line up with the watcher's view. `f` must not
+    /// block; it runs with the lock held.
+    pub async fn with_stats<R>(&self, f: impl FnOnce(&WatcherStats) -> R) -> R {
+        let stats = self.stats.read().await;
+        f(&stats)
+    }
+
     /// Stop the file system monitoring and cleanup
     pub async fn stop(&self) -> Result<()> {
         info!("Stopping system watcher...");
@@ -686,6 +1994,9 @@ impl SystemWatcher {
                 info!("Event polling task stopped successfully");
             }
         }
+        self.stability.clear();
+        self.transient.clear();
+        self.permissions.clear();
         
         info!("System watcher stopped");
         Ok(())
@@ -715,6 +2026,14 @@ pub struct EnhancedFileEvent {
     pub system_event: SystemEvent,
     pub hash: Option<HashResult>,
     pub processing_time_ns: u64,
+    /// Content-derived fields such as image dimensions, set only when a
+    /// registered extractor handles the file; see [`extract`]
+    #[serde(default)]
+    pub metadata: Option<ExtractedMetadata>,
+    /// Events the daemon has processed since startup, set on `Heartbeat`
+    /// events only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub events_processed: Option<u64>,
 }
 
 /// Enhanced cache entry with hierarchy info (2025 best practice)
@@ -725,14 +2044,29 @@ struct CacheEntry {
     access_count: u32,
     #[allow(dead_code)]
     directory_level: usize,
+    file_id: Option<FileId>,
+    /// Size of the file when hashed; `hash.size` saturates at 4 GiB
+    file_size: u64,
+    /// Strategy the hash was computed with
+    strategy: HashStrategy,
+    /// Extracted from the content that was hashed, so a cache hit can carry
+    /// it without reading the file again
+    metadata: Option<ExtractedMetadata>,
 }
 
+/// Approximate memory held per hash cache entry, including its path in the
+/// hierarchy and identity indexes
+pub const CACHE_ENTRY_SIZE_ESTIMATE: usize =
+    std::mem::size_of::<(PathBuf, CacheEntry)>() + 2 * std::mem::size_of::<PathBuf>() + 3 * 128;
+
 /// Configuration for the enhanced cache
 #[derive(Debug, Clone)]
 pub struct CacheConfig {
     pub max_entries: usize,
     pub ttl_seconds: u64,
     pub enable_hierarchy: bool,
+    /// Approximate memory limit; lowers `max_entries` to fit if set
+    pub max_bytes: Option<usize>,
 }
 
 impl Default for CacheConfig {
@@ -741,16 +2075,65 @@ impl Default for CacheConfig {
             max_entries: 1_000_000,
             ttl_seconds: 3600,
             enable_hierarchy: true,
+            max_bytes: None,
         }
     }
 }
 
+/// How `FileEventProcessor` hashes symlinks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymlinkHashMode {
+    /// Hash the content of the file the link points to
+    #[default]
+    FollowTarget,
+    /// Hash the link's target path (`read_link`), so repointing a link
+    /// changes its hash even when both targets have the same content
+    HashLinkPath,
+}
+
+/// Output format for [`FileEventProcessor::export_manifest`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ManifestFormat {
+    /// `# ` header lines, then one `"path"  hash  size  algorithm` line per
+    /// file. Paths are quoted and escaped as JSON strings, so spaces and
+    /// newlines in them cannot break a line apart.
+    Text,
+    /// `{"version": 2, "entries": [{"path", "hash", "size", "algorithm"}, ...]}`
+    Json,
+}
+
+/// Version written in manifest headers
+pub const MANIFEST_VERSION: u32 = 2;
+
+#[derive(Serialize)]
+struct Manifest {
+    version: u32,
+    entries: Vec<ManifestEntry>,
+}
+
+#[derive(Serialize)]
+struct ManifestEntry {
+    path: String,
+    hash: String,
+    size: u64,
+    algorithm: &'static str,
+}
+
 /// Enhanced file event processor with hierarchical caching
 pub struct FileEventProcessor {
     hash_engine: Arc<HashEngine>,
     hash_cache: Arc<DashMap<PathBuf, CacheEntry>>,
     directory_cache: Arc<DashMap<PathBuf, Vec<PathBuf>>>,
+    identity_index: Arc<DashMap<FileId, PathBuf>>, // file id -> last known path
+    root_strategies: Option<RootHashStrategies>,
+    symlink_mode: SymlinkHashMode,
+    /// Files larger than this are reported with `hash: None`
+    max_hash_size: Option<u64>,
+    extractors: ExtractorRegistry,
     config: CacheConfig,
+    clock: Arc<dyn Clock>,
 }
 
 impl FileEventProcessor {
@@ -758,46 +2141,135 @@ impl FileEventProcessor {
         Self::with_config(CacheConfig::default())
     }
 
-    pub fn with_config(config: CacheConfig) -> Self {
+    pub fn with_config(mut config: CacheConfig) -> Self {
+        if let Some(max_bytes) = config.max_bytes {
+            let fits = (max_bytes / CACHE_ENTRY_SIZE_ESTIMATE).max(1);
+            config.max_entries = config.max_entries.min(fits);
+        }
+
         Self {
             hash_engine: Arc::new(HashEngine::new()),
             hash_cache: Arc::new(DashMap::with_capacity(config.max_entries)),
             directory_cache: Arc::new(DashMap::new()),
+            identity_index: Arc::new(DashMap::new()),
+            root_strategies: None,
+            symlink_mode: SymlinkHashMode::default(),
+            max_hash_size: None,
+            extractors: ExtractorRegistry::new(),
             config,
+            clock: Arc::new(SystemClock),
         }
     }
 
+    /// Read the time for cache TTLs and entry ages from `clock`
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Choose whether symlinks are hashed by content or by target path
+    pub fn set_symlink_hash_mode(&mut self, mode: SymlinkHashMode) {
+        self.symlink_mode = mode;
+    }
+
+    /// Leave files larger than `max_hash_size` bytes unhashed
+    ///
+    /// Their events are still emitted, with `hash: None`, so a change to a
+    /// huge file is reported without reading it. To drop such events
+    /// entirely, set `EventFilter::max_event_size` on the watcher instead.
+    pub fn set_max_hash_size(&mut self, max_hash_size: Option<u64>) {
+        self.max_hash_size = max_hash_size;
+    }
+
+    /// Attach metadata from `extractors` to events for files they handle
+    pub fn set_metadata_extractors(&mut self, extractors: ExtractorRegistry) {
+        self.extractors = extractors;
+    }
+
+    /// Hash files with the strategy of the watch root they fall under
+    ///
+    /// Paths under no root with a strategy use the engine default.
+    pub fn set_root_strategies(&mut self, roots: RootHashStrategies) {
+        self.root_strategies = Some(roots);
+    }
+
+    /// Strategy used to hash `path`
+    pub fn strategy_for(&self, path: &Path) -> HashStrategy {
+        self.root_strategies
+            .as_ref()
+            .and_then(|roots| roots.strategy_for(path))
+            .unwrap_or_else(|| self.hash_engine.strategy())
+    }
+
     /// Process a system event and add hash information
+    ///
+    /// When the event carries a `FileId`, the cache is also keyed by identity:
+    /// hardlinks reuse each other's fresh hash, and a `Moved` event transfers
+    /// the cached hash from the old path instead of re-hashing.
     pub async fn process_event(&self, event: SystemEvent) -> Result<EnhancedFileEvent> {
         let start_time = std::time::Instant::now();
+        let file_id = event.metadata.as_ref().and_then(|m| m.file_id);
+        let mut metadata = None;
 
-        let hash = if !event.is_directory
+        let hashes = !event.is_directory
             && matches!(
                 event.event_type,
-                SystemEventType::Created | SystemEventType::Modified
-            ) {
+                SystemEventType::Created
+                    | SystemEventType::Modified
+                    | SystemEventType::StabilizedModified
+            );
+        let too_large_to_hash = self
+            .max_hash_size
+            .is_some_and(|max_size| event.size > max_size);
+
+        let hash = if hashes && too_large_to_hash {
+            debug!(
+                "Not hashing {}: {} bytes exceeds max_hash_size",
+                event.path.display(),
+                event.size
+            );
+            None
+        } else if hashes {
+            let event_time = UNIX_EPOCH + Duration::from_nanos(event.timestamp);
+            let strategy = self.strategy_for(&event.path);
+            let link_target = self.hashed_link_target(&event.path);
+            // A link hashed by its target path shares no identity with the target
+            let file_id = file_id.filter(|_| link_target.is_none());
+
             // Check hierarchical cache first
-            if let Some(mut entry) = self.hash_cache.get_mut(&event.path) {
-                let event_time = UNIX_EPOCH + Duration::from_nanos(event.timestamp);
-
-                // Check TTL
-                let age = SystemTime::now()
-                    .duration_since(entry.timestamp)
-                    .unwrap_or(Duration::ZERO);
-
-                if age.as_secs() <= self.config.ttl_seconds && entry.timestamp >= event_time {
-                    // Update access count for LRU
-                    entry.access_count += 1;
-                    Some(entry.hash.clone())
-                } else {
-                    drop(entry); // Release lock before computing new hash
-                    self.compute_and_cache_hash(&event.path).await
-                }
-            } else {
+            let cached = self
+                .fresh_cached_hash(&event.path, strategy, event_time)
+                .or_else(|| {
+                    file_id.and_then(|id| {
+                        self.hash_from_identity(id, &event.path, strategy, event.size, event_time)
+                    })
+                });
+            let hash = match cached {
+                Some(hash) => Some(hash),
                 // Compute new hash
-                self.compute_and_cache_hash(&event.path).await
+                None => {
+                    self.compute_and_cache_hash(
+                        &event.path,
+                        strategy,
+                        event.size,
+                        file_id,
+                        link_target.as_deref(),
+                    )
+                    .await
+                }
+            };
+
+            // Extracted alongside the hash, so only a recomputed hash reads the file
+            if hash.is_some() {
+                metadata = self.cached_metadata(&event.path);
             }
+            hash
+        } else if !event.is_directory && matches!(event.event_type, SystemEventType::Moved) {
+            file_id.and_then(|id| self.transfer_by_identity(id, &event))
         } else {
+            if !event.is_directory && matches!(event.event_type, SystemEventType::Deleted) {
+                self.forget_identity(&event.path);
+            }
+
             // Handle directory events for hierarchy
             if event.is_directory && matches!(event.event_type, SystemEventType::Deleted) {
                 self.invalidate_directory(&event.path);
@@ -807,16 +2279,167 @@ impl FileEventProcessor {
 
         let processing_time_ns = start_time.elapsed().as_nanos() as u64;
 
-        Ok(EnhancedFileEvent {
-            system_event: event,
-            hash,
-            processing_time_ns,
-        })
+        Ok(EnhancedFileEvent {
+            system_event: event,
+            hash,
+            processing_time_ns,
+            metadata,
+            events_processed: None,
+        })
+    }
+
+    /// Content of `path` if an extractor handles it and it is small enough
+    fn read_for_extraction(&self, path: &Path) -> Option<Vec<u8>> {
+        if !self.extractors.handles(path) {
+            return None;
+        }
+        if std::fs::metadata(path).ok()?.len() > self.extractors.max_bytes() {
+            return None;
+        }
+        std::fs::read(path).ok()
+    }
+
+    /// Metadata cached with the hash of `path`
+    fn cached_metadata(&self, path: &Path) -> Option<ExtractedMetadata> {
+        self.hash_cache.get(path)?.metadata.clone()
+    }
+
+    /// Return the cached hash for `path` if it is within TTL, newer than the
+    /// event and computed with `strategy`
+    fn fresh_cached_hash(
+        &self,
+        path: &Path,
+        strategy: HashStrategy,
+        event_time: SystemTime,
+    ) -> Option<HashResult> {
+        let mut entry = self.hash_cache.get_mut(path)?;
+
+        // A hash from another algorithm is useless once the root's strategy changed
+        if entry.strategy != strategy {
+            return None;
+        }
+
+        // Check TTL
+        let age = self
+            .clock
+            .now()
+            .duration_since(entry.timestamp)
+            .unwrap_or(Duration::ZERO);
+
+        if age.as_secs() <= self.config.ttl_seconds && entry.timestamp >= event_time {
+            // Update access count for LRU
+            entry.access_count += 1;
+            Some(entry.hash.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Reuse a fresh hash computed for another hardlink of the same file
+    ///
+    /// The link's entry must have been hashed with `strategy`, the strategy
+    /// of `path`'s root.
+    fn hash_from_identity(
+        &self,
+        id: FileId,
+        path: &Path,
+        strategy: HashStrategy,
+        file_size: u64,
+        event_time: SystemTime,
+    ) -> Option<HashResult> {
+        let linked_path = self.identity_index.get(&id)?.clone();
+        if linked_path == path {
+            return None;
+        }
+
+        let hash = self.fresh_cached_hash(&linked_path, strategy, event_time)?;
+        debug!(
+            "Reusing hash of hardlink {} for {}",
+            linked_path.display(),
+            path.display()
+        );
+        let metadata = self.cached_metadata(&linked_path);
+        self.insert_cache_entry(path, hash.clone(), strategy, file_size, Some(id), metadata);
+        Some(hash)
+    }
+
+    /// Move the cache entry of a renamed file to its new path
+    fn transfer_by_identity(&self, id: FileId, event: &SystemEvent) -> Option<HashResult> {
+        let old_path = self.identity_index.get(&id)?.clone();
+
+        if old_path == event.path {
+            return self.hash_cache.get(&old_path).map(|entry| entry.hash.clone());
+        }
+
+        let (_, entry) = self.hash_cache.remove(&old_path)?;
+        self.remove_from_hierarchy(&old_path);
+
+        // Guard against inode reuse: a rename never changes the size. Moving
+        // into a root with another strategy needs a fresh hash.
+        let strategy = self.strategy_for(&event.path);
+        if entry.file_size != event.size || entry.strategy != strategy {
+            self.identity_index.remove(&id);
+            return None;
+        }
+
+        debug!(
+            "Transferring cached hash {} -> {}",
+            old_path.display(),
+            event.path.display()
+        );
+        let hash = entry.hash.clone();
+        self.insert_cache_entry(
+            &event.path,
+            hash.clone(),
+            strategy,
+            event.size,
+            Some(id),
+            entry.metadata,
+        );
+        Some(hash)
+    }
+
+    /// Drop the identity mapping of a deleted file so a reused inode can't match it
+    fn forget_identity(&self, path: &Path) {
+        let file_id = self.hash_cache.get(path).and_then(|entry| entry.file_id);
+        if let Some(id) = file_id {
+            self.identity_index.remove_if(&id, |_, known| known == path);
+        }
     }
 
     /// Compute and cache file hash with hierarchical awareness
-    async fn compute_and_cache_hash(&self, path: &Path) -> Option<HashResult> {
-        let hash_result = match self.hash_engine.hash_file(path) {
+    ///
+    /// `strategy` is the strategy of `path`'s root and `file_size` the size
+    /// the event reported. `link_target` is `path`'s [`Self::hashed_link_target`],
+    /// hashed instead of the content when set. A file the extractors handle
+    /// is read once for them and hashed from the same bytes, and the
+    /// extracted metadata is cached with the hash.
+    async fn compute_and_cache_hash(
+        &self,
+        path: &Path,
+        strategy: HashStrategy,
+        file_size: u64,
+        file_id: Option<FileId>,
+        link_target: Option<&Path>,
+    ) -> Option<HashResult> {
+        let content = self.read_for_extraction(path);
+        let metadata = content
+            .as_deref()
+            .and_then(|data| self.extractors.extract(path, data));
+
+        let (hashed, file_id) = match (link_target, content.as_deref()) {
+            (Some(target), _) => (
+                self.hash_engine
+                    .hash_bytes_with_strategy(target.as_os_str().as_encoded_bytes(), strategy),
+                None,
+            ),
+            (None, Some(data)) => (
+                self.hash_engine.hash_file_data_with_strategy(data, strategy),
+                file_id,
+            ),
+            (None, None) => (self.hash_engine.hash_file_with_strategy(path, strategy), file_id),
+        };
+        let hash_result = match hashed {
             Ok(result) => result,
             Err(e) => {
                 warn!("Failed to hash file {}: {}", path.display(), e);
@@ -824,17 +2447,55 @@ impl FileEventProcessor {
             }
         };
 
+        self.insert_cache_entry(
+            path,
+            hash_result.clone(),
+            strategy,
+            file_size,
+            file_id,
+            metadata,
+        );
+
+        Some(hash_result)
+    }
+
+    /// Target of `path` if it is a symlink that should be hashed by link path
+    fn hashed_link_target(&self, path: &Path) -> Option<PathBuf> {
+        match self.symlink_mode {
+            SymlinkHashMode::FollowTarget => None,
+            SymlinkHashMode::HashLinkPath => std::fs::read_link(path).ok(),
+        }
+    }
+
+    /// Insert a cache entry, updating the hierarchy and identity indexes
+    fn insert_cache_entry(
+        &self,
+        path: &Path,
+        hash: HashResult,
+        strategy: HashStrategy,
+        file_size: u64,
+        file_id: Option<FileId>,
+        metadata: Option<ExtractedMetadata>,
+    ) {
         // Create enhanced cache entry
         let entry = CacheEntry {
-            hash: hash_result.clone(),
-            timestamp: SystemTime::now(),
+            hash,
+            timestamp: self.clock.now(),
             access_count: 1,
             directory_level: path.components().count(),
+            file_id,
+            file_size,
+            strategy,
+            metadata,
         };
 
         // Insert into cache
         self.hash_cache.insert(path.to_path_buf(), entry);
 
+        if let Some(id) = file_id {
+            self.identity_index.insert(id, path.to_path_buf());
+        }
+
         // Update directory hierarchy if enabled
         if self.config.enable_hierarchy {
             if let Some(parent) = path.parent() {
@@ -849,8 +2510,15 @@ impl FileEventProcessor {
         if self.hash_cache.len() > self.config.max_entries {
             self.evict_lru();
         }
+    }
 
-        Some(hash_result)
+    /// Remove a file from its parent's hierarchy entry
+    fn remove_from_hierarchy(&self, path: &Path) {
+        if let Some(parent) = path.parent() {
+            if let Some(mut files) = self.directory_cache.get_mut(parent) {
+                files.retain(|p| p != path);
+            }
+        }
     }
 
     /// Invalidate directory hierarchy
@@ -894,7 +2562,11 @@ impl FileEventProcessor {
 
         // Remove the least used entries
         for (path, _) in to_evict.into_iter().take(entries_to_remove) {
-            self.hash_cache.remove(&path);
+            if let Some((_, entry)) = self.hash_cache.remove(&path) {
+                if let Some(id) = entry.file_id {
+                    self.identity_index.remove_if(&id, |_, known| known == &path);
+                }
+            }
             // Also clean up from directory hierarchy
             if let Some(parent) = path.parent() {
                 if let Some(mut files) = self.directory_cache.get_mut(parent) {
@@ -924,14 +2596,105 @@ impl FileEventProcessor {
         }
     }
 
+    /// Approximate bytes held by the hash cache and its identity index,
+    /// counting each entry's actual path length
+    pub fn hash_cache_memory_bytes(&self) -> usize {
+        let entries: usize = self
+            .hash_cache
+            .iter()
+            .map(|entry| {
+                std::mem::size_of::<(PathBuf, CacheEntry)>() + entry.key().as_os_str().len()
+            })
+            .sum();
+        let identities: usize = self
+            .identity_index
+            .iter()
+            .map(|entry| std::mem::size_of::<(FileId, PathBuf)>() + entry.value().as_os_str().len())
+            .sum();
+        entries + identities
+    }
+
+    /// Approximate bytes held by the directory cache
+    pub fn directory_cache_memory_bytes(&self) -> usize {
+        self.directory_cache
+            .iter()
+            .map(|entry| {
+                let children: usize = entry
+                    .value()
+                    .iter()
+                    .map(|child| std::mem::size_of::<PathBuf>() + child.as_os_str().len())
+                    .sum();
+                std::mem::size_of::<(PathBuf, Vec<PathBuf>)>()
+                    + entry.key().as_os_str().len()
+                    + children
+            })
+            .sum()
+    }
+
+    /// Write the cached hashes as a manifest for inspection or diffing
+    ///
+    /// Entries are sorted by path so manifests from different runs diff
+    /// cleanly. Each hash is the full hex digest where one was computed (64
+    /// chars, BLAKE3) and the 16-char `u64` otherwise, alongside the
+    /// algorithm that computed it; under `hybrid` or `auto` roots that differs
+    /// from file to file. The size is the file's, in bytes, when it was
+    /// hashed, not the length of what was hashed: for a symlink hashed by
+    /// link path that is still the size of the file behind it. Meant for
+    /// people and other tools, not for reloading the cache.
+    pub fn export_manifest<W: Write>(&self, mut writer: W, format: ManifestFormat) -> Result<()> {
+        let mut cached: Vec<(PathBuf, HashResult, u64)> = self
+            .hash_cache
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.hash.clone(), entry.file_size))
+            .collect();
+        cached.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let entries = cached.into_iter().map(|(path, hash, size)| ManifestEntry {
+            path: path.to_string_lossy().into_owned(),
+            hash: hash.to_hex(),
+            size,
+            algorithm: hash.algorithm(),
+        });
+
+        match format {
+            ManifestFormat::Text => {
+                writeln!(writer, "# retrigger manifest v{MANIFEST_VERSION}")?;
+                writeln!(writer, "# \"path\"  hash  size  algorithm")?;
+                for entry in entries {
+                    writeln!(
+                        writer,
+                        "{}  {}  {}  {}",
+                        serde_json::to_string(&entry.path)?,
+                        entry.hash,
+                        entry.size,
+                        entry.algorithm
+                    )?;
+                }
+            }
+            ManifestFormat::Json => {
+                let manifest = Manifest {
+                    version: MANIFEST_VERSION,
+                    entries: entries.collect(),
+                };
+                serde_json::to_writer_pretty(&mut writer, &manifest)?;
+                writeln!(writer)?;
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
     /// Clear expired cache entries
     pub async fn cleanup_cache(&self, max_age: Duration) {
-        let cutoff = SystemTime::now() - max_age;
+        let cutoff = self.clock.now() - max_age;
         let mut removed_count = 0;
 
         self.hash_cache.retain(|path, entry| {
             if entry.timestamp < cutoff {
                 removed_count += 1;
+                if let Some(id) = entry.file_id {
+                    self.identity_index.remove_if(&id, |_, known| known == path);
+                }
                 // Clean up from directory hierarchy
                 if let Some(parent) = path.parent() {
                     if let Some(mut files) = self.directory_cache.get_mut(parent) {
@@ -952,10 +2715,65 @@ impl FileEventProcessor {
         }
     }
 
+    /// Pre-populate the hash cache from every file under `root`
+    ///
+    /// `max_depth` limits the walk (root = 0, `None` for unlimited); deeper
+    /// directories are skipped and reported in the returned stats.
+    pub async fn warm_from_directory<P: AsRef<Path>>(
+        &self,
+        root: P,
+        max_depth: Option<usize>,
+    ) -> Result<WarmStats> {
+        let root = root.as_ref();
+        let walk = walk_directory(root, &WalkOptions { max_depth })
+            .with_context(|| format!("Failed to walk directory: {}", root.display()))?;
+
+        let mut stats = WarmStats {
+            skipped_deep_dirs: walk.skipped_deep_dirs,
+            ..Default::default()
+        };
+
+        for path in walk.files {
+            let Ok(stat) = std::fs::metadata(&path) else {
+                stats.files_failed += 1;
+                continue;
+            };
+            let file_id = FileId::of(&path, &stat);
+            let strategy = self.strategy_for(&path);
+            let link_target = self.hashed_link_target(&path);
+            if self
+                .compute_and_cache_hash(
+                    &path,
+                    strategy,
+                    stat.len(),
+                    file_id,
+                    link_target.as_deref(),
+                )
+                .await
+                .is_some()
+            {
+                stats.files_hashed += 1;
+            } else {
+                stats.files_failed += 1;
+            }
+        }
+
+        if !stats.skipped_deep_dirs.is_empty() {
+            warn!(
+                "Cache warm-up of {} skipped {} directories beyond depth limit",
+                root.display(),
+                stats.skipped_deep_dirs.len()
+            );
+        }
+
+        Ok(stats)
+    }
+
     /// Clear all cache entries
     pub fn clear_cache(&self) {
         self.hash_cache.clear();
         self.directory_cache.clear();
+        self.identity_index.clear();
     }
 }
 
@@ -965,6 +2783,15 @@ impl Default for FileEventProcessor {
     }
 }
 
+/// Outcome of a cache warm-up
+#[derive(Debug, Clone, Default)]
+pub struct WarmStats {
+    pub files_hashed: usize,
+    pub files_failed: usize,
+    /// Directories not entered because they exceed the depth limit
+    pub skipped_deep_dirs: Vec<PathBuf>,
+}
+
 /// Detailed cache statistics for monitoring
 #[derive(Debug, Clone)]
 pub struct DetailedCacheStats {
@@ -975,26 +2802,10 @@ pub struct DetailedCacheStats {
     pub ttl_seconds: u64,
 }
 
-/// Simple glob pattern matching for file paths
-fn glob_match(pattern: &str, path: &str) -> bool {
-    // Simple implementation - convert glob to regex
-    let regex_pattern = pattern
-        .replace("**", "DOUBLE_STAR")
-        .replace("*", "[^/]*")
-        .replace("DOUBLE_STAR", ".*")
-        .replace("?", "[^/]");
-    
-    if let Ok(regex) = regex::Regex::new(&format!("^{}$", regex_pattern)) {
-        regex.is_match(path)
-    } else {
-        // Fallback to simple string matching
-        path.contains(&pattern.replace("*", ""))
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::AtomicUsize;
     #[allow(unused_imports)]
     use tempfile::tempdir;
 
@@ -1021,10 +2832,1154 @@ mod tests {
                 .as_nanos() as u64,
             size: 1024,
             is_directory: false,
+            metadata: None,
         };
 
         // Processing should complete without error (even if file doesn't exist)
         let enhanced = processor.process_event(test_event).await;
         assert!(enhanced.is_ok());
     }
+
+    fn file_event(path: &Path, event_type: SystemEventType) -> SystemEvent {
+        SystemEvent {
+            path: path.to_path_buf(),
+            event_type,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos() as u64,
+            size: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+            is_directory: false,
+            metadata: EventMetadata::capture(path),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_hash_size_keeps_event_without_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let small = dir.path().join("small.bin");
+        let large = dir.path().join("large.bin");
+        std::fs::write(&small, vec![1u8; 64]).unwrap();
+        std::fs::write(&large, vec![2u8; 4096]).unwrap();
+
+        let mut processor = FileEventProcessor::new();
+        processor.set_max_hash_size(Some(1024));
+        let small = processor
+            .process_event(file_event(&small, SystemEventType::Modified))
+            .await
+            .unwrap();
+        assert!(small.hash.is_some());
+        let large = processor
+            .process_event(file_event(&large, SystemEventType::Modified))
+            .await
+            .unwrap();
+        assert!(large.hash.is_none());
+        assert_eq!(large.system_event.size, 4096);
+
+        // max_event_size is what drops the event itself
+        let mut watcher = SystemWatcher::stub();
+        watcher.set_options(WatcherOptions {
+            normalization: EventNormalization::Raw,
+            ..Default::default()
+        });
+        watcher
+            .set_event_filter(EventFilter {
+                max_event_size: Some(1024 * 1024),
+                ..Default::default()
+            })
+            .unwrap();
+        watcher.watch_directory("/project", true).await.unwrap();
+        let event = |path: &str, size| SystemEvent {
+            path: PathBuf::from(path),
+            event_type: SystemEventType::Modified,
+            timestamp: 1,
+            size,
+            is_directory: false,
+            metadata: None,
+        };
+        assert!(watcher.inject_event(event("/project/a.bin", 4096)).await);
+        assert!(!watcher.inject_event(event("/project/b.bin", 2 * 1024 * 1024)).await);
+        let stats = watcher.get_stats().await;
+        assert_eq!(stats.dropped_by_reason[&DropReason::TooLarge], 1);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_extractors_share_the_hash_read() {
+        struct ByteCount(Arc<AtomicUsize>);
+
+        impl MetadataExtractor for ByteCount {
+            fn content_types(&self) -> &[&'static str] {
+                &["text/plain"]
+            }
+
+            fn extract(
+                &self,
+                data: &[u8],
+                _deadline: std::time::Instant,
+                out: &mut ExtractedMetadata,
+            ) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                out.insert("bytes".to_string(), data.len().into());
+            }
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes.txt");
+        let image = dir.path().join("image.png");
+        std::fs::write(&notes, "two\nlines\n").unwrap();
+        std::fs::write(&image, "not really a png").unwrap();
+
+        let extracted = Arc::new(AtomicUsize::new(0));
+        let mut registry = ExtractorRegistry::new();
+        registry.register(Arc::new(ByteCount(Arc::clone(&extracted))));
+        let mut processor = FileEventProcessor::new();
+        processor.set_metadata_extractors(registry);
+
+        let duplicate = file_event(&notes, SystemEventType::Modified);
+        let enhanced = processor
+            .process_event(file_event(&notes, SystemEventType::Created))
+            .await
+            .unwrap();
+        assert_eq!(enhanced.metadata.unwrap()["bytes"], 10);
+        // Hashed from the bytes read for extraction, same as from disk
+        let expected = HashEngine::new()
+            .hash_file_with_strategy(&notes, processor.strategy_for(&notes))
+            .unwrap();
+        assert_eq!(enhanced.hash, Some(expected));
+
+        // A cache hit carries the cached metadata without reading the file
+        let enhanced = processor.process_event(duplicate).await.unwrap();
+        assert_eq!(enhanced.metadata.unwrap()["bytes"], 10);
+        assert_eq!(extracted.load(Ordering::Relaxed), 1);
+
+        // No extractor for the content type, and none for deletions
+        let enhanced = processor
+            .process_event(file_event(&image, SystemEventType::Created))
+            .await
+            .unwrap();
+        assert!(enhanced.metadata.is_none());
+        assert!(enhanced.hash.is_some());
+        let enhanced = processor
+            .process_event(file_event(&notes, SystemEventType::Deleted))
+            .await
+            .unwrap();
+        assert!(enhanced.metadata.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rewatch_updates_settings_and_unwatch() {
+        let watcher = SystemWatcher::stub();
+        let root = PathBuf::from("/tmp/project");
+
+        watcher.watch_directory(&root, false).await.unwrap();
+        let settings = WatchSettings {
+            recursive: true,
+            include_patterns: vec!["**/*.rs".to_string()],
+            ..Default::default()
+        };
+        watcher
+            .watch_directory_with(&root, settings.clone())
+            .await
+            .unwrap();
+
+        assert!(watcher.is_watched(&root));
+        assert_eq!(watcher.watched_paths(), vec![(root.clone(), settings)]);
+        assert_eq!(watcher.get_stats().await.watched_directories, 1);
+
+        assert!(watcher.unwatch_directory(&root).await);
+        assert!(!watcher.unwatch_directory(&root).await);
+        assert!(!watcher.is_watched(&root));
+        assert!(watcher.watched_paths().is_empty());
+        // The entry is gone, not just deactivated
+        assert!(watcher.watched_paths.is_empty());
+        assert_eq!(watcher.get_stats().await.watched_directories, 0);
+    }
+
+    #[tokio::test]
+    async fn test_inject_event_runs_pipeline() {
+        let mut watcher = SystemWatcher::stub();
+        watcher.set_options(WatcherOptions {
+            normalization: EventNormalization::Raw,
+            ..Default::default()
+        });
+        watcher.set_event_filter(EventFilter {
+            exclude_patterns: vec!["**/.git/**".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        watcher.watch_directory("/project", true).await.unwrap();
+        let mut events = watcher.subscribe();
+
+        let event = |path: &str| SystemEvent {
+            path: PathBuf::from(path),
+            event_type: SystemEventType::Modified,
+            timestamp: 1,
+            size: 10,
+            is_directory: false,
+            metadata: None,
+        };
+
+        assert!(watcher.inject_event(event("/project/src/lib.rs")).await);
+        // Debounced, excluded by the default filter, and outside the watch
+        assert!(!watcher.inject_event(event("/project/src/lib.rs")).await);
+        assert!(!watcher.inject_event(event("/project/.git/HEAD")).await);
+        watcher.unwatch_directory("/project").await;
+        assert!(!watcher.inject_event(event("/project/src/main.rs")).await);
+
+        let received = events.try_recv().unwrap();
+        assert_eq!(received.path, PathBuf::from("/project/src/lib.rs"));
+        assert!(events.try_recv().is_err());
+        let stats = watcher.get_stats().await;
+        assert_eq!(stats.total_events, 1);
+
+        // Each rejection is counted under its reason
+        assert_eq!(stats.dropped_events, 3);
+        for reason in [DropReason::Debounced, DropReason::Excluded, DropReason::OutOfScope] {
+            assert_eq!(stats.dropped_by_reason[&reason], 1, "{reason}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stability_window_emits_stabilized_event() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("artifact.tar");
+        std::fs::write(&path, b"complete").unwrap();
+
+        let clock = MockClock::new();
+        let mut watcher = SystemWatcher::stub();
+        watcher.set_options(WatcherOptions {
+            normalization: EventNormalization::Raw,
+            stability_window_ms: 50,
+            ..Default::default()
+        });
+        watcher.set_clock(Arc::new(clock.clone()));
+        watcher.watch_directory(dir.path(), true).await.unwrap();
+        watcher.start_polling_task().await.unwrap();
+        let mut events = watcher.subscribe();
+
+        assert!(
+            watcher
+                .inject_event(SystemEvent {
+                    path: path.clone(),
+                    event_type: SystemEventType::Modified,
+                    timestamp: 1,
+                    size: 0,
+                    is_directory: false,
+                    metadata: None,
+                })
+                .await
+        );
+
+        let modified = events.recv().await.unwrap();
+        assert_eq!(modified.event_type, SystemEventType::Modified);
+
+        // Nothing settles until the clock has moved through the whole window
+        clock.advance(Duration::from_millis(49));
+        let early = tokio::time::timeout(Duration::from_millis(50), events.recv()).await;
+        assert!(early.is_err());
+
+        clock.advance(Duration::from_millis(1));
+        let stable = tokio::time::timeout(Duration::from_secs(2), events.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stable.event_type, SystemEventType::StabilizedModified);
+        assert_eq!(stable.path, path);
+        assert_eq!(stable.size, 8);
+        watcher.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_transient_window_suppresses_create_delete_pairs() {
+        let dir = tempdir().unwrap();
+        let event = |name: &str, event_type| SystemEvent {
+            path: dir.path().join(name),
+            event_type,
+            timestamp: 1,
+            size: 0,
+            is_directory: false,
+            metadata: None,
+        };
+
+        let clock = MockClock::new();
+        let mut watcher = SystemWatcher::stub();
+        watcher.set_options(WatcherOptions {
+            normalization: EventNormalization::Raw,
+            transient_window_ms: 50,
+            ..Default::default()
+        });
+        watcher
+            .set_event_filter(EventFilter {
+                debounce_ms: 0,
+                ..Default::default()
+            })
+            .unwrap();
+        watcher.set_clock(Arc::new(clock.clone()));
+        watcher.watch_directory(dir.path(), true).await.unwrap();
+        watcher.start_polling_task().await.unwrap();
+        let mut events = watcher.subscribe();
+
+        // A lock file deleted within the window is never reported
+        assert!(!watcher.inject_event(event(".#lock", SystemEventType::Created)).await);
+        clock.advance(Duration::from_millis(10));
+        assert!(!watcher.inject_event(event(".#lock", SystemEventType::Deleted)).await);
+
+        // A file that outlives the window is reported once it has
+        assert!(!watcher.inject_event(event("kept.rs", SystemEventType::Created)).await);
+        clock.advance(Duration::from_millis(49));
+        let early = tokio::time::timeout(Duration::from_millis(50), events.recv()).await;
+        assert!(early.is_err());
+
+        clock.advance(Duration::from_millis(1));
+        let created = tokio::time::timeout(Duration::from_secs(2), events.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(created.event_type, SystemEventType::Created);
+        assert_eq!(created.path, dir.path().join("kept.rs"));
+
+        // Its deletion is now an ordinary one
+        assert!(watcher.inject_event(event("kept.rs", SystemEventType::Deleted)).await);
+        assert_eq!(events.recv().await.unwrap().event_type, SystemEventType::Deleted);
+
+        let stats = watcher.get_stats().await;
+        assert_eq!(stats.transient_pairs_suppressed, 1);
+        assert_eq!(stats.dropped_by_reason[&DropReason::Transient], 2);
+        watcher.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_with_snapshot() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src/nested")).unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), b"lib").unwrap();
+        std::fs::write(dir.path().join("src/nested/mod.rs"), b"mod").unwrap();
+        std::fs::write(dir.path().join("src/.env"), b"secret").unwrap();
+        std::fs::write(dir.path().join("src/build.log"), b"log").unwrap();
+
+        let mut watcher = SystemWatcher::stub();
+        watcher.set_options(WatcherOptions {
+            normalization: EventNormalization::Raw,
+            ..Default::default()
+        });
+        let settings = WatchSettings {
+            exclude_patterns: vec!["**/*.log".to_string()],
+            ..WatchSettings::new(true)
+        };
+        watcher
+            .watch_directory_with(dir.path(), settings)
+            .await
+            .unwrap();
+
+        let (snapshot, mut live) = watcher.subscribe_with_snapshot();
+        let paths: Vec<&Path> = snapshot.iter().map(|event| event.path.as_path()).collect();
+        let lib = dir.path().join("src/lib.rs");
+        let nested = dir.path().join("src/nested/mod.rs");
+        assert_eq!(paths, vec![lib.as_path(), nested.as_path()]);
+        assert!(snapshot
+            .iter()
+            .all(|event| event.event_type == SystemEventType::Created));
+        assert_eq!(snapshot[0].size, 3);
+
+        // Changes after the snapshot arrive on the receiver
+        let modified = SystemEvent {
+            path: lib.clone(),
+            event_type: SystemEventType::Modified,
+            timestamp: 1,
+            size: 4,
+            is_directory: false,
+            metadata: None,
+        };
+        assert!(watcher.inject_event(modified).await);
+        assert_eq!(live.recv().await.unwrap().path, lib);
+    }
+
+    #[tokio::test]
+    async fn test_debounce_follows_clock() {
+        let clock = MockClock::new();
+        let mut watcher = SystemWatcher::stub();
+        watcher.set_options(WatcherOptions {
+            normalization: EventNormalization::Raw,
+            ..Default::default()
+        });
+        watcher.set_clock(Arc::new(clock.clone()));
+        watcher.watch_directory("/project", true).await.unwrap();
+
+        let event = || SystemEvent {
+            path: PathBuf::from("/project/src/lib.rs"),
+            event_type: SystemEventType::Modified,
+            timestamp: 1,
+            size: 10,
+            is_directory: false,
+            metadata: None,
+        };
+
+        // The default filter debounces each path for 100ms
+        assert!(watcher.inject_event(event()).await);
+        clock.advance(Duration::from_millis(99));
+        assert!(!watcher.inject_event(event()).await);
+        clock.advance(Duration::from_millis(1));
+        assert!(watcher.inject_event(event()).await);
+    }
+
+    #[tokio::test]
+    async fn test_coalesce_key_groups_debounce() {
+        let clock = MockClock::new();
+        let mut watcher = SystemWatcher::stub();
+        watcher.set_options(WatcherOptions {
+            normalization: EventNormalization::Raw,
+            ..Default::default()
+        });
+        watcher.set_clock(Arc::new(clock.clone()));
+        watcher
+            .set_event_filter(EventFilter {
+                coalesce_key: Some(Arc::new(|event: &SystemEvent| {
+                    event
+                        .path
+                        .parent()
+                        .map(|dir| dir.to_string_lossy().into_owned())
+                        .unwrap_or_default()
+                })),
+                ..Default::default()
+            })
+            .unwrap();
+        watcher.watch_directory("/project", true).await.unwrap();
+
+        let event = |path: &str| SystemEvent {
+            path: PathBuf::from(path),
+            event_type: SystemEventType::Modified,
+            timestamp: 1,
+            size: 10,
+            is_directory: false,
+            metadata: None,
+        };
+
+        // One event per directory per window, whatever the file
+        assert!(watcher.inject_event(event("/project/src/a.rs")).await);
+        assert!(!watcher.inject_event(event("/project/src/b.rs")).await);
+        assert!(watcher.inject_event(event("/project/docs/a.md")).await);
+        clock.advance(Duration::from_millis(100));
+        assert!(watcher.inject_event(event("/project/src/b.rs")).await);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_chmod_reports_permission_delta() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("deploy.sh");
+        std::fs::write(&path, b"#!/bin/sh").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut watcher = SystemWatcher::stub();
+        watcher.set_options(WatcherOptions {
+            capture_file_ids: true,
+            normalization: EventNormalization::Raw,
+            ..Default::default()
+        });
+        watcher
+            .set_event_filter(EventFilter {
+                exclude_patterns: vec![],
+                debounce_ms: 0,
+                ..Default::default()
+            })
+            .unwrap();
+        watcher.watch_directory(dir.path(), true).await.unwrap();
+        let mut receiver = watcher.subscribe();
+
+        let event = |event_type| SystemEvent {
+            path: path.clone(),
+            event_type,
+            timestamp: 1,
+            size: 9,
+            is_directory: false,
+            metadata: None,
+        };
+
+        assert!(watcher.inject_event(event(SystemEventType::Created)).await);
+        let created = receiver.recv().await.unwrap().metadata.unwrap();
+        assert_eq!(created.permissions.unwrap().mode, 0o755);
+        assert!(created.permission_delta.is_none());
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o777)).unwrap();
+        let chmod = event(SystemEventType::MetadataChanged);
+        assert!(watcher.inject_event(chmod).await);
+        let delta = receiver
+            .recv()
+            .await
+            .unwrap()
+            .metadata
+            .unwrap()
+            .permission_delta
+            .unwrap();
+        let mode = delta.mode.unwrap();
+        assert_eq!((mode.old, mode.new), (0o755, 0o777));
+        assert!(delta.became_world_writable());
+        assert!(delta.uid.is_none() && delta.gid.is_none());
+
+        // Deleting forgets the file
+        std::fs::remove_file(&path).unwrap();
+        assert!(watcher.inject_event(event(SystemEventType::Deleted)).await);
+        assert!(watcher.permissions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_filtered_events_are_not_tracked() {
+        let dir = tempdir().unwrap();
+        let log = dir.path().join("debug.log");
+        std::fs::write(&log, b"noise").unwrap();
+
+        let mut watcher = SystemWatcher::stub();
+        watcher.set_options(WatcherOptions {
+            capture_file_ids: true,
+            normalization: EventNormalization::Raw,
+            ..Default::default()
+        });
+        watcher
+            .set_event_filter(EventFilter {
+                exclude_patterns: vec!["**/*.log".to_string()],
+                debounce_ms: 0,
+                ..Default::default()
+            })
+            .unwrap();
+        watcher.watch_directory(dir.path(), true).await.unwrap();
+
+        let excluded = SystemEvent {
+            path: log,
+            event_type: SystemEventType::Modified,
+            timestamp: 1,
+            size: 5,
+            is_directory: false,
+            metadata: None,
+        };
+        assert!(!watcher.inject_event(excluded).await);
+        assert!(watcher.permissions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cache_ttl_follows_clock() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("cached.txt");
+        std::fs::write(&path, b"cached").unwrap();
+
+        let clock = MockClock::new();
+        let mut processor = FileEventProcessor::with_config(CacheConfig {
+            ttl_seconds: 60,
+            ..Default::default()
+        });
+        processor.set_clock(Arc::new(clock.clone()));
+        processor
+            .process_event(file_event(&path, SystemEventType::Created))
+            .await
+            .unwrap();
+
+        let strategy = processor.strategy_for(&path);
+        clock.advance(Duration::from_secs(60));
+        assert!(processor
+            .fresh_cached_hash(&path, strategy, UNIX_EPOCH)
+            .is_some());
+        clock.advance(Duration::from_secs(1));
+        assert!(processor
+            .fresh_cached_hash(&path, strategy, UNIX_EPOCH)
+            .is_none());
+
+        processor.cleanup_cache(Duration::from_secs(120)).await;
+        assert_eq!(processor.cache_stats().0, 1);
+        clock.advance(Duration::from_secs(60));
+        processor.cleanup_cache(Duration::from_secs(120)).await;
+        assert_eq!(processor.cache_stats().0, 0);
+    }
+
+    #[tokio::test]
+    async fn test_compiled_event_filter() {
+        let mut watcher = SystemWatcher::stub();
+        watcher.set_options(WatcherOptions {
+            normalization: EventNormalization::Raw,
+            ..Default::default()
+        });
+        watcher.watch_directory("/project", true).await.unwrap();
+
+        let event = |path: &str| SystemEvent {
+            path: PathBuf::from(path),
+            event_type: SystemEventType::Modified,
+            timestamp: 1,
+            size: 10,
+            is_directory: false,
+            metadata: None,
+        };
+
+        // The default filter drops hidden files and node_modules only
+        assert!(watcher.inject_event(event("/project/src/lib.rs")).await);
+        assert!(!watcher.inject_event(event("/project/.env")).await);
+        assert!(!watcher.inject_event(event("/project/node_modules/a/index.js")).await);
+
+        // An invalid glob is rejected and the previous filter stays in place
+        let invalid = EventFilter {
+            include_patterns: vec!["src/[".to_string()],
+            ..Default::default()
+        };
+        assert!(watcher.set_event_filter(invalid).is_err());
+        assert!(watcher.event_filter().include_patterns.is_empty());
+        assert!(watcher.inject_event(event("/project/src/main.rs")).await);
+
+        let settings = WatchSettings {
+            exclude_patterns: vec!["[".to_string()],
+            ..WatchSettings::new(true)
+        };
+        assert!(watcher.watch_directory_with("/other", settings).await.is_err());
+        assert!(!watcher.is_watched("/other"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_filter_overrides_nest_and_expire() {
+        let mut watcher = SystemWatcher::stub();
+        watcher.set_options(WatcherOptions {
+            normalization: EventNormalization::Raw,
+            ..Default::default()
+        });
+        watcher.watch_directory("/project", true).await.unwrap();
+
+        let event = |path: &str| SystemEvent {
+            path: PathBuf::from(path),
+            event_type: SystemEventType::Modified,
+            timestamp: 1,
+            size: 10,
+            is_directory: false,
+            metadata: None,
+        };
+        let excluding = |pattern: &str| EventFilter {
+            exclude_patterns: vec![pattern.to_string()],
+            ..Default::default()
+        };
+
+        // The configured filter is never popped
+        assert!(watcher.pop_filter().is_none());
+
+        watcher.push_filter(excluding("**/*.log")).unwrap();
+        watcher.push_filter(excluding("**/*.tmp")).unwrap();
+        assert!(!watcher.inject_event(event("/project/a.tmp")).await);
+        assert!(watcher.inject_event(event("/project/a.log")).await);
+
+        let popped = watcher.pop_filter().unwrap();
+        assert_eq!(popped.exclude_patterns, vec!["**/*.tmp".to_string()]);
+        assert!(watcher.inject_event(event("/project/b.tmp")).await);
+        assert!(!watcher.inject_event(event("/project/b.log")).await);
+
+        // A base filter set under an override applies once the override goes
+        watcher.set_event_filter(excluding("**/*.bak")).unwrap();
+        assert!(watcher.inject_event(event("/project/a.bak")).await);
+        assert!(watcher.pop_filter().is_some());
+        assert!(!watcher.inject_event(event("/project/b.bak")).await);
+
+        // An invalid override is rejected without being pushed
+        assert!(watcher.push_filter(excluding("[")).is_err());
+        assert!(watcher.pop_filter().is_none());
+
+        // A temporary override removes only itself, even under a later push
+        watcher
+            .with_temporary_filter(excluding("**/*.log"), Duration::from_millis(50))
+            .unwrap();
+        watcher.push_filter(excluding("**/*.tmp")).unwrap();
+        tokio::time::advance(Duration::from_millis(150)).await;
+        tokio::task::yield_now().await;
+        assert!(!watcher.inject_event(event("/project/c.tmp")).await);
+        assert!(watcher.pop_filter().is_some());
+        assert!(watcher.inject_event(event("/project/c.log")).await);
+        assert!(!watcher.inject_event(event("/project/c.bak")).await);
+        assert!(watcher.pop_filter().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_large_dirs_are_skipped_and_reported() {
+        let dir = tempdir().unwrap();
+        let cache = dir.path().join("cache");
+        std::fs::create_dir_all(cache.join("nested")).unwrap();
+        for i in 0..3 {
+            std::fs::write(cache.join(format!("entry-{i}")), b"").unwrap();
+        }
+
+        let mut watcher = SystemWatcher::stub();
+        watcher.set_options(WatcherOptions {
+            max_entries_per_dir: 3,
+            ..Default::default()
+        });
+        watcher.watch_directory(dir.path(), true).await.unwrap();
+
+        assert_eq!(
+            watcher.get_stats().await.skipped_large_dirs,
+            vec![cache.clone()]
+        );
+        assert_eq!(watcher.skipped_large_dirs(), vec![cache.clone()]);
+
+        // Updating the watch leaves the native one alone and does not walk
+        // the tree again; the boundaries found at setup still apply
+        std::fs::remove_file(cache.join("entry-0")).unwrap();
+        watcher.watch_directory(dir.path(), true).await.unwrap();
+        assert_eq!(watcher.skipped_large_dirs(), vec![cache]);
+    }
+
+    #[tokio::test]
+    async fn test_watch_many_is_paced_and_reports_progress() {
+        let watcher = Arc::new(SystemWatcher::stub());
+        let mut watches: Vec<_> = (0..4)
+            .map(|i| (PathBuf::from(format!("/bulk/{i}")), WatchSettings::new(true)))
+            .collect();
+        watches.push((
+            PathBuf::from("/bulk/invalid"),
+            WatchSettings {
+                include_patterns: vec!["[".to_string()],
+                ..WatchSettings::new(true)
+            },
+        ));
+
+        let throttle = RegistrationThrottle {
+            batch_size: 2,
+            batch_delay: Duration::from_millis(20),
+            max_concurrent: 2,
+        };
+        let started = std::time::Instant::now();
+        let failures = watcher.watch_many(watches, throttle).await;
+
+        // Five registrations in batches of two pause twice
+        assert!(started.elapsed() >= Duration::from_millis(40));
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, PathBuf::from("/bulk/invalid"));
+        assert!(watcher.is_watched("/bulk/3"));
+
+        let progress = watcher.registration_progress();
+        assert_eq!(
+            progress,
+            RegistrationProgress {
+                total: 5,
+                completed: 4,
+                failed: 1,
+            }
+        );
+        assert!(progress.is_done());
+    }
+
+    #[tokio::test]
+    async fn test_watch_scoped_guard() {
+        let watcher = Arc::new(SystemWatcher::stub());
+        let scoped = PathBuf::from("/tmp/scoped");
+        let pinned = PathBuf::from("/tmp/pinned");
+
+        let first = watcher.watch_scoped(&scoped, true).await.unwrap();
+        let second = watcher.watch_scoped(&scoped, true).await.unwrap();
+        drop(first);
+        assert!(watcher.is_watched(&scoped));
+        drop(second);
+        assert!(!watcher.is_watched(&scoped));
+
+        // A guard never removes a watch that was added explicitly
+        watcher.watch_directory(&pinned, true).await.unwrap();
+        drop(watcher.watch_scoped(&pinned, false).await.unwrap());
+        assert!(watcher.is_watched(&pinned));
+
+        tokio::task::yield_now().await;
+        assert_eq!(watcher.get_stats().await.watched_directories, 1);
+    }
+
+    #[tokio::test]
+    async fn test_scoped_watches_do_not_leak_entries() {
+        let watcher = Arc::new(SystemWatcher::stub());
+        for i in 0..100 {
+            let path = PathBuf::from(format!("/tmp/scoped-{i}"));
+            let first = watcher.watch_scoped(&path, true).await.unwrap();
+            let second = watcher.watch_scoped(&path, false).await.unwrap();
+            drop(first);
+            drop(second);
+        }
+        assert!(watcher.watched_paths.is_empty());
+
+        tokio::task::yield_now().await;
+        assert_eq!(watcher.get_stats().await.watched_directories, 0);
+    }
+
+    #[tokio::test]
+    async fn test_stale_watch_guard_does_not_release_new_registration() {
+        let watcher = Arc::new(SystemWatcher::stub());
+        let path = PathBuf::from("/tmp/rewatched");
+
+        let stale = watcher.watch_scoped(&path, true).await.unwrap();
+        assert!(watcher.unwatch_directory(&path).await);
+
+        // The guard from before the unwatch must not end the new watch
+        let current = watcher.watch_scoped(&path, true).await.unwrap();
+        drop(stale);
+        assert!(watcher.is_watched(&path));
+        drop(current);
+        assert!(!watcher.is_watched(&path));
+    }
+
+    #[tokio::test]
+    async fn test_watch_files_filters_siblings() {
+        let mut watcher = SystemWatcher::stub();
+        watcher.set_options(WatcherOptions {
+            normalization: EventNormalization::Raw,
+            ..Default::default()
+        });
+        watcher
+            .set_event_filter(EventFilter {
+                debounce_ms: 0,
+                ..Default::default()
+            })
+            .unwrap();
+        watcher.watch_directory("/project/docs", false).await.unwrap();
+
+        let results = watcher
+            .watch_files(&[
+                PathBuf::from("/project/src/a.rs"),
+                PathBuf::from("/project/src/b.rs"),
+                PathBuf::from("/project/docs/guide.md"),
+                PathBuf::from("/"),
+            ])
+            .await;
+        assert!(results[..3].iter().all(|result| result.is_ok()));
+        assert!(results[3].is_err());
+        // Listing more files extends the directory's list
+        let results = watcher.watch_files(&[PathBuf::from("/project/src/c.rs")]).await;
+        assert!(results[0].is_ok());
+
+        // One watch per parent
+        let watched: Vec<_> = watcher.watched_paths().into_iter().map(|(path, _)| path).collect();
+        assert_eq!(
+            watched,
+            vec![PathBuf::from("/project/docs"), PathBuf::from("/project/src")]
+        );
+
+        let event = |path: &str| SystemEvent {
+            path: PathBuf::from(path),
+            event_type: SystemEventType::Modified,
+            timestamp: 1,
+            size: 10,
+            is_directory: false,
+            metadata: None,
+        };
+        assert!(watcher.inject_event(event("/project/src/a.rs")).await);
+        assert!(watcher.inject_event(event("/project/src/c.rs")).await);
+        assert!(!watcher.inject_event(event("/project/src/unrelated.rs")).await);
+        assert!(!watcher.inject_event(event("/project/src/sub/a.rs")).await);
+        // The directory watched as a whole is not narrowed to the listed file
+        assert!(watcher.inject_event(event("/project/docs/other.md")).await);
+
+        // Updating a listed directory's watch keeps its list
+        watcher.watch_directory("/project/src", false).await.unwrap();
+        assert!(watcher.inject_event(event("/project/src/b.rs")).await);
+        assert!(!watcher.inject_event(event("/project/src/unrelated.rs")).await);
+
+        // Only a fresh watch after unwatching covers all of its children
+        assert!(watcher.unwatch_directory("/project/src").await);
+        watcher.watch_directory("/project/src", false).await.unwrap();
+        assert!(watcher.inject_event(event("/project/src/unrelated.rs")).await);
+    }
+
+    #[test]
+    fn test_watch_scope() {
+        let watches = DashMap::new();
+        let entry = |recursive| WatchEntry {
+            settings: WatchSettings::new(recursive),
+            patterns: PathPatterns::new(&[], &["**/*.log".to_string()]).unwrap(),
+            native_recursive: true,
+            pinned: true,
+            scoped_guards: 0,
+            generation: 0,
+            skipped_mounts: vec![PathBuf::from("/w/deep/nfs")],
+            skipped_large_dirs: vec![PathBuf::from("/w/deep/cache")],
+            files: None,
+        };
+        watches.insert(PathBuf::from("/w/flat"), entry(false));
+
+        assert!(in_watch_scope(Path::new("/w/flat/a.txt"), &watches));
+        assert!(!in_watch_scope(Path::new("/w/flat/sub/a.txt"), &watches));
+        assert!(!in_watch_scope(Path::new("/w/flat/a.log"), &watches));
+        assert!(in_watch_scope(Path::new("/elsewhere/a.txt"), &watches));
+
+        // Events from a mount point the watch did not cross into are dropped
+        watches.insert(PathBuf::from("/w/deep"), entry(true));
+        assert!(in_watch_scope(Path::new("/w/deep/src/a.txt"), &watches));
+        assert!(!in_watch_scope(Path::new("/w/deep/nfs/a.txt"), &watches));
+
+        // A directory too large to descend into still reports its own entries
+        assert!(in_watch_scope(Path::new("/w/deep/cache/a.txt"), &watches));
+        assert!(!in_watch_scope(
+            Path::new("/w/deep/cache/sub/a.txt"),
+            &watches
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_warm_from_directory_respects_depth() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("deep/deeper")).unwrap();
+        std::fs::write(dir.path().join("root.txt"), b"root").unwrap();
+        std::fs::write(dir.path().join("deep/deeper/hidden.txt"), b"deep").unwrap();
+
+        let processor = FileEventProcessor::new();
+        let stats = processor
+            .warm_from_directory(dir.path(), Some(1))
+            .await
+            .unwrap();
+
+        assert_eq!(stats.files_hashed, 1);
+        assert_eq!(stats.skipped_deep_dirs, vec![dir.path().join("deep/deeper")]);
+        assert!(processor.hash_cache.contains_key(&dir.path().join("root.txt")));
+    }
+
+    #[tokio::test]
+    async fn test_hash_strategy_per_root() {
+        let dir = tempdir().unwrap();
+        let assets = dir.path().join("assets");
+        std::fs::create_dir_all(&assets).unwrap();
+        std::fs::write(assets.join("logo.svg"), b"<svg/>").unwrap();
+        std::fs::write(dir.path().join("main.rs"), b"fn main() {}").unwrap();
+
+        let watcher = SystemWatcher::stub();
+        let strategy = |hash_strategy| WatchSettings {
+            hash_strategy: Some(hash_strategy),
+            ..WatchSettings::new(true)
+        };
+        watcher
+            .watch_directory_with(dir.path(), strategy(HashStrategy::Blake3Only))
+            .await
+            .unwrap();
+        watcher
+            .watch_directory_with(&assets, strategy(HashStrategy::Xxh3Only))
+            .await
+            .unwrap();
+
+        let mut processor = FileEventProcessor::new();
+        processor.set_root_strategies(watcher.root_hash_strategies());
+
+        // The deepest root wins; paths outside every root use the engine default
+        assert_eq!(
+            processor.strategy_for(&assets.join("logo.svg")),
+            HashStrategy::Xxh3Only
+        );
+        assert_eq!(
+            processor.strategy_for(&dir.path().join("main.rs")),
+            HashStrategy::Blake3Only
+        );
+        assert_eq!(
+            processor.strategy_for(Path::new("/elsewhere/file.txt")),
+            HashStrategy::Hybrid
+        );
+
+        let asset = processor
+            .process_event(file_event(&assets.join("logo.svg"), SystemEventType::Created))
+            .await
+            .unwrap();
+        let source = processor
+            .process_event(file_event(&dir.path().join("main.rs"), SystemEventType::Created))
+            .await
+            .unwrap();
+        // Only BLAKE3 results carry a full digest
+        assert!(asset.hash.unwrap().digest.is_none());
+        assert!(source.hash.unwrap().digest.is_some());
+
+        // Unwatching the inner root falls back to the outer root's strategy
+        watcher.unwatch_directory(&assets).await;
+        assert_eq!(
+            processor.strategy_for(&assets.join("logo.svg")),
+            HashStrategy::Blake3Only
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_manifest_is_sorted_and_names_algorithm() {
+        let dir = tempdir().unwrap();
+        // Windows forbids newlines in file names
+        let name = if cfg!(unix) {
+            "b  two\nlines.txt"
+        } else {
+            "b  two spaces.txt"
+        };
+        let later = dir.path().join(name);
+        let earlier = dir.path().join("a.txt");
+        std::fs::write(&later, b"second file").unwrap();
+        std::fs::write(&earlier, b"first").unwrap();
+
+        let processor = FileEventProcessor::new();
+        for path in [&later, &earlier] {
+            processor
+                .process_event(file_event(path, SystemEventType::Created))
+                .await
+                .unwrap();
+        }
+
+        let mut text = Vec::new();
+        processor
+            .export_manifest(&mut text, ManifestFormat::Text)
+            .unwrap();
+        let text = String::from_utf8(text).unwrap();
+        let lines: Vec<&str> = text.lines().filter(|l| !l.starts_with('#')).collect();
+        assert_eq!(lines.len(), 2);
+        let quoted = |path: &Path| serde_json::to_string(&path.to_string_lossy()).unwrap();
+        assert!(lines[0].starts_with(&quoted(&earlier)));
+        // Small files under the default hybrid strategy are hashed with XXH3
+        assert!(lines[0].ends_with("  5  xxh3"));
+        // Whitespace in the path stays inside the quotes, on one line
+        assert!(lines[1].starts_with(&quoted(&later)));
+        assert!(lines[1].ends_with("  11  xxh3"));
+
+        let mut json = Vec::new();
+        processor
+            .export_manifest(&mut json, ManifestFormat::Json)
+            .unwrap();
+        let manifest: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(manifest["version"], MANIFEST_VERSION);
+        let entries = manifest["entries"].as_array().unwrap();
+        assert_eq!(entries[0]["path"], earlier.to_string_lossy().as_ref());
+        assert_eq!(entries[1]["path"], later.to_string_lossy().as_ref());
+        assert_eq!(entries[1]["size"], 11);
+        assert_eq!(entries[1]["algorithm"], "xxh3");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_export_manifest_reports_file_size_of_linked_files() {
+        let dir = tempdir().unwrap();
+        let target = dir.path().join("release.tar");
+        let link = dir.path().join("current");
+        std::fs::write(&target, vec![0u8; 4096]).unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let mut processor = FileEventProcessor::new();
+        processor.set_symlink_hash_mode(SymlinkHashMode::HashLinkPath);
+        let hashed = processor
+            .process_event(file_event(&link, SystemEventType::Created))
+            .await
+            .unwrap()
+            .hash
+            .unwrap();
+        // What was hashed is the link path, not the 4 KiB behind it
+        assert_eq!(hashed.size as usize, target.as_os_str().len());
+
+        let mut json = Vec::new();
+        processor
+            .export_manifest(&mut json, ManifestFormat::Json)
+            .unwrap();
+        let manifest: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        let entries = manifest["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["path"], link.to_string_lossy().as_ref());
+        assert_eq!(entries[0]["size"], 4096);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_symlink_hash_modes() {
+        let dir = tempdir().unwrap();
+        let first = dir.path().join("first.txt");
+        let second = dir.path().join("second.txt");
+        let link = dir.path().join("current.txt");
+        std::fs::write(&first, b"same content").unwrap();
+        std::fs::write(&second, b"same content").unwrap();
+
+        let repoint = |target: &Path| {
+            let _ = std::fs::remove_file(&link);
+            std::os::unix::fs::symlink(target, &link).unwrap();
+        };
+        async fn hash_of(processor: &FileEventProcessor, path: &Path) -> HashResult {
+            let event = file_event(path, SystemEventType::Modified);
+            processor.process_event(event).await.unwrap().hash.unwrap()
+        }
+
+        let following = FileEventProcessor::new();
+        let mut by_link = FileEventProcessor::new();
+        by_link.set_symlink_hash_mode(SymlinkHashMode::HashLinkPath);
+
+        repoint(&first);
+        let followed_first = hash_of(&following, &link).await;
+        let linked_first = hash_of(&by_link, &link).await;
+
+        repoint(&second);
+        let followed_second = hash_of(&following, &link).await;
+        let linked_second = hash_of(&by_link, &link).await;
+
+        // Following the link only sees content; hashing the link sees the repoint
+        assert_eq!(followed_first, followed_second);
+        assert_ne!(linked_first, linked_second);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_move_transfers_cache_by_identity() {
+        let dir = tempdir().unwrap();
+        let old_path = dir.path().join("before.txt");
+        let new_path = dir.path().join("after.txt");
+        std::fs::write(&old_path, b"identity tracked content").unwrap();
+
+        let processor = FileEventProcessor::new();
+        let created = processor
+            .process_event(file_event(&old_path, SystemEventType::Created))
+            .await
+            .unwrap();
+        let original_hash = created.hash.expect("created file should be hashed");
+
+        std::fs::rename(&old_path, &new_path).unwrap();
+        let moved = processor
+            .process_event(file_event(&new_path, SystemEventType::Moved))
+            .await
+            .unwrap();
+
+        assert_eq!(moved.hash, Some(original_hash));
+        assert!(processor.hash_cache.contains_key(&new_path));
+        assert!(!processor.hash_cache.contains_key(&old_path));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_move_transfers_cache_of_file_over_4gib() {
+        let dir = tempdir().unwrap();
+        let old_path = dir.path().join("disk.img");
+        let new_path = dir.path().join("disk.img.bak");
+        std::fs::write(&old_path, b"stands in for a large image").unwrap();
+
+        // The reported size is what identity transfer compares, and it no
+        // longer fits the hash's 32-bit size
+        let huge = |mut event: SystemEvent| {
+            event.size = 5 << 30;
+            event
+        };
+
+        let processor = FileEventProcessor::new();
+        let created = processor
+            .process_event(huge(file_event(&old_path, SystemEventType::Created)))
+            .await
+            .unwrap();
+
+        std::fs::rename(&old_path, &new_path).unwrap();
+        let moved = processor
+            .process_event(huge(file_event(&new_path, SystemEventType::Moved)))
+            .await
+            .unwrap();
+
+        assert!(moved.hash.is_some());
+        assert_eq!(moved.hash, created.hash);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_hardlinks_share_identity() {
+        let dir = tempdir().unwrap();
+        let original = dir.path().join("original.txt");
+        let link = dir.path().join("link.txt");
+        std::fs::write(&original, b"shared content").unwrap();
+        std::fs::hard_link(&original, &link).unwrap();
+
+        let original_event = file_event(&original, SystemEventType::Created);
+        let link_event = file_event(&link, SystemEventType::Created);
+        assert_eq!(
+            original_event.metadata.as_ref().unwrap().file_id,
+            link_event.metadata.as_ref().unwrap().file_id
+        );
+
+        let processor = FileEventProcessor::new();
+        let first = processor.process_event(original_event).await.unwrap();
+        let second = processor.process_event(link_event).await.unwrap();
+        assert_eq!(first.hash, second.hash);
+        assert!(processor.hash_cache.contains_key(&link));
+    }
 }
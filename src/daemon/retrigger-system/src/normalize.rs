@@ -0,0 +1,265 @@
+//! Event-type normalization across platforms
+//!
+//! Native backends describe the same logical change differently: inotify and
+//! ReadDirectoryChangesW report a new file as a create followed by separate
+//! modifies, while FSEvents collapses flags and may repeat `ItemCreated` for
+//! a path that already existed. With [`EventNormalization::Canonical`] events
+//! are rewritten so consumers see one vocabulary everywhere:
+//!
+//! | Platform | Native sequence                                  | Raw                 | Canonical         |
+//! |----------|--------------------------------------------------|---------------------|-------------------|
+//! | Linux    | `IN_CREATE`, then `IN_MODIFY` within the window  | Created, Modified   | Created, Created  |
+//! | Windows  | `FILE_ACTION_ADDED`, then `FILE_ACTION_MODIFIED` | Created, Modified   | Created, Created  |
+//! | macOS    | `ItemCreated \| ItemModified` (collapsed)        | Created             | Created           |
+//! | macOS    | `ItemCreated` for a path already reported        | Created             | Modified          |
+//! | All      | Create for a path that is already gone           | Created             | dropped           |
+//! | All      | Delete following a dropped create                | Deleted             | dropped           |
+//!
+//! The window is [`CREATE_FOLD_WINDOW_NS`] measured on event timestamps. All
+//! other event types pass through unchanged. Use [`EventNormalization::Raw`]
+//! to receive the platform's own event types.
+//!
+//! Canonical mode has two costs Raw avoids. Every `Created` is checked with a
+//! `stat` of its path to spot creates for files that are already gone, one
+//! extra syscall per create, which shows on trees that create files by the
+//! thousand (build output, package installs). And the state of each path is
+//! remembered for [`PATH_STATE_RETENTION_NS`] after its last event; entries
+//! older than that are swept, so a repeated create for a path last seen
+//! earlier is reported as `Created` again.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::SystemEventType;
+
+/// Modifies arriving this soon after a create are reported as part of it
+pub const CREATE_FOLD_WINDOW_NS: u64 = 100_000_000;
+
+/// How long a path's state is remembered after its last event
+pub const PATH_STATE_RETENTION_NS: u64 = 30_000_000_000;
+
+/// How native event types are mapped before delivery
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventNormalization {
+    /// Deliver event types exactly as the platform reports them
+    Raw,
+    /// Rewrite platform-specific sequences to the canonical set (see module docs)
+    #[default]
+    Canonical,
+}
+
+/// What the normalizer last surfaced for a path, and when
+#[derive(Debug, Clone, Copy)]
+enum PathState {
+    /// Created at the given timestamp
+    Created(u64),
+    /// Seen with any other live event type
+    Present(u64),
+    /// Create dropped because the path was already gone
+    Vanished(u64),
+}
+
+impl PathState {
+    fn timestamp(self) -> u64 {
+        match self {
+            PathState::Created(at) | PathState::Present(at) | PathState::Vanished(at) => at,
+        }
+    }
+}
+
+/// Stateful mapper from native to canonical event types
+#[derive(Debug, Default)]
+pub struct EventNormalizer {
+    paths: DashMap<PathBuf, PathState>,
+    /// Event timestamp of the last sweep of expired paths
+    last_sweep_ns: AtomicU64,
+}
+
+impl EventNormalizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map a native event type, returning `None` if the event should be dropped
+    pub fn normalize(
+        &self,
+        mode: EventNormalization,
+        path: &Path,
+        event_type: SystemEventType,
+        timestamp: u64,
+    ) -> Option<SystemEventType> {
+        if mode == EventNormalization::Raw {
+            return Some(event_type);
+        }
+        self.normalize_with(path, event_type, timestamp, || path.exists())
+    }
+
+    fn normalize_with(
+        &self,
+        path: &Path,
+        event_type: SystemEventType,
+        timestamp: u64,
+        exists: impl FnOnce() -> bool,
+    ) -> Option<SystemEventType> {
+        self.sweep(timestamp);
+        let previous = self.paths.get(path).map(|state| *state);
+
+        match event_type {
+            SystemEventType::Created => {
+                if !exists() {
+                    self.paths
+                        .insert(path.to_path_buf(), PathState::Vanished(timestamp));
+                    return None;
+                }
+                match previous {
+                    Some(PathState::Created(_)) | Some(PathState::Present(_)) => {
+                        self.paths
+                            .insert(path.to_path_buf(), PathState::Present(timestamp));
+                        Some(SystemEventType::Modified)
+                    }
+                    _ => {
+                        self.paths
+                            .insert(path.to_path_buf(), PathState::Created(timestamp));
+                        Some(SystemEventType::Created)
+                    }
+                }
+            }
+            SystemEventType::Modified => match previous {
+                Some(PathState::Created(created_at))
+                    if timestamp.saturating_sub(created_at) <= CREATE_FOLD_WINDOW_NS =>
+                {
+                    Some(SystemEventType::Created)
+                }
+                _ => {
+                    self.paths
+                        .insert(path.to_path_buf(), PathState::Present(timestamp));
+                    Some(SystemEventType::Modified)
+                }
+            },
+            SystemEventType::Deleted => {
+                self.paths.remove(path);
+                match previous {
+                    Some(PathState::Vanished(_)) => None,
+                    _ => Some(SystemEventType::Deleted),
+                }
+            }
+            SystemEventType::Moved => {
+                // Both sides of a rename are reported as `Moved`; the old
+                // path's state is stale either way
+                self.paths.remove(path);
+                Some(SystemEventType::Moved)
+            }
+            SystemEventType::MetadataChanged => Some(SystemEventType::MetadataChanged),
+            SystemEventType::StabilizedModified => Some(SystemEventType::StabilizedModified),
+            SystemEventType::Heartbeat => Some(SystemEventType::Heartbeat),
+        }
+    }
+
+    /// Forget all tracked paths
+    pub fn clear(&self) {
+        self.paths.clear();
+    }
+
+    /// Paths whose state is currently remembered
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Drop paths with no event for [`PATH_STATE_RETENTION_NS`], at most once
+    /// per that period
+    fn sweep(&self, now_ns: u64) {
+        let last_sweep = self.last_sweep_ns.load(Ordering::Relaxed);
+        if now_ns.saturating_sub(last_sweep) < PATH_STATE_RETENTION_NS {
+            return;
+        }
+        self.last_sweep_ns.store(now_ns, Ordering::Relaxed);
+        self.paths
+            .retain(|_, state| now_ns.saturating_sub(state.timestamp()) < PATH_STATE_RETENTION_NS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: u64 = 1_000_000;
+
+    #[test]
+    fn test_create_then_modify_folds_into_created() {
+        let normalizer = EventNormalizer::new();
+        let path = Path::new("/w/new.txt");
+
+        let created = normalizer.normalize_with(path, SystemEventType::Created, 0, || true);
+        let written = normalizer.normalize_with(path, SystemEventType::Modified, 5 * MS, || true);
+        let later = normalizer.normalize_with(path, SystemEventType::Modified, 500 * MS, || true);
+
+        assert_eq!(created, Some(SystemEventType::Created));
+        assert_eq!(written, Some(SystemEventType::Created));
+        assert_eq!(later, Some(SystemEventType::Modified));
+    }
+
+    #[test]
+    fn test_repeated_create_becomes_modified() {
+        let normalizer = EventNormalizer::new();
+        let path = Path::new("/w/existing.txt");
+
+        normalizer.normalize_with(path, SystemEventType::Created, 0, || true);
+        let repeated = normalizer.normalize_with(path, SystemEventType::Created, 900 * MS, || true);
+        assert_eq!(repeated, Some(SystemEventType::Modified));
+
+        // After a delete the path can be created afresh
+        normalizer.normalize_with(path, SystemEventType::Deleted, 901 * MS, || false);
+        let recreated =
+            normalizer.normalize_with(path, SystemEventType::Created, 902 * MS, || true);
+        assert_eq!(recreated, Some(SystemEventType::Created));
+    }
+
+    #[test]
+    fn test_vanished_create_is_dropped_with_its_delete() {
+        let normalizer = EventNormalizer::new();
+        let path = Path::new("/w/transient.txt");
+
+        assert_eq!(
+            normalizer.normalize_with(path, SystemEventType::Created, 0, || false),
+            None
+        );
+        assert_eq!(
+            normalizer.normalize_with(path, SystemEventType::Deleted, MS, || false),
+            None
+        );
+    }
+
+    #[test]
+    fn test_idle_paths_are_swept() {
+        let normalizer = EventNormalizer::new();
+        let path = |i: usize| PathBuf::from(format!("/w/file{i}.txt"));
+
+        for i in 0..100 {
+            normalizer.normalize_with(&path(i), SystemEventType::Modified, MS, || true);
+        }
+        assert_eq!(normalizer.len(), 100);
+
+        // One event after the retention period sweeps the idle paths
+        let later = MS + PATH_STATE_RETENTION_NS;
+        normalizer.normalize_with(&path(0), SystemEventType::Created, later, || true);
+        assert_eq!(normalizer.len(), 1);
+    }
+
+    #[test]
+    fn test_raw_passes_through() {
+        let normalizer = EventNormalizer::new();
+        let path = Path::new("/w/missing.txt");
+
+        let event =
+            normalizer.normalize(EventNormalization::Raw, path, SystemEventType::Created, 0);
+        assert_eq!(event, Some(SystemEventType::Created));
+    }
+}
@@ -0,0 +1,221 @@
+//! Precompiled include/exclude glob matching
+//!
+//! Event paths are checked against the watcher's filter and the per-root
+//! patterns on every event, so the globs are compiled into a [`GlobSet`] once
+//! when they are set and each check is a single lookup.
+//!
+//! `*` and `?` do not cross `/`; use `**` to match any number of directories.
+//!
+//! How a path matched by both an include and an exclude is decided is set by
+//! [`PatternPrecedence`].
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+
+/// Which pattern decides when a path matches both an include and an exclude
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternPrecedence {
+    /// Any exclude match rejects the path
+    #[default]
+    ExcludeWins,
+    /// Rules are read in declaration order, includes then excludes, and the
+    /// last one matching the path decides, like gitignore. A leading `!`
+    /// inverts a rule (`\!` is a literal `!`), so
+    /// `exclude = ["build/**", "!build/important.json"]` carves one file out
+    /// of an excluded directory.
+    LastMatchWins,
+    /// Any include match admits the path, even if it is also excluded
+    IncludeWins,
+}
+
+/// `LastMatchWins` rules in evaluation order, each as its glob and whether a
+/// match admits the path
+fn ordered_rules<'a>(
+    include: &'a [String],
+    exclude: &'a [String],
+) -> impl Iterator<Item = (&'a str, bool)> {
+    let rule = |pattern: &'a String, admits: bool| match pattern.strip_prefix('!') {
+        Some(inverted) => (inverted, !admits),
+        None => (pattern.as_str(), admits),
+    };
+    include
+        .iter()
+        .map(move |p| rule(p, true))
+        .chain(exclude.iter().map(move |p| rule(p, false)))
+}
+
+/// Compiled include and exclude globs
+#[derive(Debug, Clone)]
+pub struct PathPatterns {
+    include: GlobSet,
+    exclude: GlobSet,
+    /// No include patterns were given, so everything not excluded passes
+    include_all: bool,
+    precedence: PatternPrecedence,
+    /// `LastMatchWins` only: all rules in [`ordered_rules`] order
+    rules: GlobSet,
+    /// Whether the rule at each index of `rules` admits the path
+    rule_admits: Vec<bool>,
+}
+
+impl PathPatterns {
+    /// Compile the patterns with exclude-wins precedence, failing on the
+    /// first invalid glob
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        Self::with_precedence(include, exclude, PatternPrecedence::ExcludeWins)
+    }
+
+    /// Compile the patterns, failing on the first invalid glob
+    pub fn with_precedence(
+        include: &[String],
+        exclude: &[String],
+        precedence: PatternPrecedence,
+    ) -> Result<Self> {
+        if precedence == PatternPrecedence::LastMatchWins {
+            let (globs, rule_admits): (Vec<&str>, Vec<bool>) =
+                ordered_rules(include, exclude).unzip();
+            return Ok(Self {
+                rules: compile(&globs).context("Invalid pattern")?,
+                rule_admits,
+                precedence,
+                include_all: include.is_empty(),
+                ..Self::default()
+            });
+        }
+
+        Ok(Self {
+            include: compile(include).context("Invalid include pattern")?,
+            exclude: compile(exclude).context("Invalid exclude pattern")?,
+            include_all: include.is_empty(),
+            precedence,
+            ..Self::default()
+        })
+    }
+
+    /// Whether `path` passes the patterns. A path no pattern matches passes
+    /// only if there are no include patterns.
+    pub fn admits(&self, path: &Path) -> bool {
+        match self.precedence {
+            PatternPrecedence::ExcludeWins => {
+                if self.exclude.is_match(path) {
+                    return false;
+                }
+                self.include_all || self.include.is_match(path)
+            }
+            PatternPrecedence::IncludeWins => {
+                if !self.include_all && self.include.is_match(path) {
+                    return true;
+                }
+                self.include_all && !self.exclude.is_match(path)
+            }
+            PatternPrecedence::LastMatchWins => match self.rules.matches(path).into_iter().max() {
+                Some(rule) => self.rule_admits[rule],
+                None => self.include_all,
+            },
+        }
+    }
+}
+
+impl Default for PathPatterns {
+    fn default() -> Self {
+        Self {
+            include: GlobSet::empty(),
+            exclude: GlobSet::empty(),
+            include_all: true,
+            precedence: PatternPrecedence::ExcludeWins,
+            rules: GlobSet::empty(),
+            rule_admits: Vec::new(),
+        }
+    }
+}
+
+fn compile<S: AsRef<str>>(patterns: &[S]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let pattern = pattern.as_ref();
+        let glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .with_context(|| format!("{pattern:?}"))?;
+        builder.add(glob);
+    }
+    Ok(builder.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(include: &[&str], exclude: &[&str]) -> PathPatterns {
+        let owned = |p: &[&str]| p.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        PathPatterns::new(&owned(include), &owned(exclude)).unwrap()
+    }
+
+    #[test]
+    fn test_include_and_exclude() {
+        let filter = patterns(&["**/*.rs", "**/Cargo.toml"], &["**/target/**", "**/.*"]);
+
+        assert!(filter.admits(Path::new("/p/src/lib.rs")));
+        assert!(filter.admits(Path::new("/p/Cargo.toml")));
+        assert!(!filter.admits(Path::new("/p/README.md")));
+        assert!(!filter.admits(Path::new("/p/target/debug/build.rs")));
+        // Hidden files only; a dot elsewhere in the path is not excluded
+        assert!(!filter.admits(Path::new("/p/src/.hidden.rs")));
+        assert!(filter.admits(Path::new("/p/src/mod.v2/lib.rs")));
+    }
+
+    #[test]
+    fn test_single_star_stays_in_one_component() {
+        let filter = patterns(&["/p/*.rs"], &[]);
+
+        assert!(filter.admits(Path::new("/p/lib.rs")));
+        assert!(!filter.admits(Path::new("/p/src/lib.rs")));
+        assert!(PathPatterns::default().admits(Path::new("/anything")));
+    }
+
+    #[test]
+    fn test_precedence_modes() {
+        let owned = |p: &[&str]| p.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let include = owned(&["/p/**/*.json", "/p/build/important.json"]);
+        let exclude = owned(&["/p/build/**"]);
+        let with =
+            |precedence| PathPatterns::with_precedence(&include, &exclude, precedence).unwrap();
+        let important = Path::new("/p/build/important.json");
+        let other = Path::new("/p/build/other.json");
+
+        let exclude_wins = with(PatternPrecedence::ExcludeWins);
+        assert!(!exclude_wins.admits(important));
+        assert!(exclude_wins.admits(Path::new("/p/package.json")));
+
+        let include_wins = with(PatternPrecedence::IncludeWins);
+        assert!(include_wins.admits(important));
+        assert!(include_wins.admits(other));
+        assert!(!include_wins.admits(Path::new("/p/README.md")));
+
+        // Excludes come after includes, so the exclude wins for both files...
+        let last_match = with(PatternPrecedence::LastMatchWins);
+        assert!(!last_match.admits(important));
+        assert!(last_match.admits(Path::new("/p/package.json")));
+        assert!(!last_match.admits(Path::new("/p/README.md")));
+
+        // ...until a later negated exclude re-includes one of them
+        let carve_out = owned(&["/p/build/**", "!/p/build/important.json", "/p/**/*.bak"]);
+        let last_match =
+            PathPatterns::with_precedence(&[], &carve_out, PatternPrecedence::LastMatchWins)
+                .unwrap();
+        assert!(last_match.admits(important));
+        assert!(!last_match.admits(other));
+        assert!(!last_match.admits(Path::new("/p/build/important.json.bak")));
+        assert!(last_match.admits(Path::new("/p/src/lib.rs")));
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_rejected() {
+        let error = PathPatterns::new(&[], &["src/[".to_string()]).unwrap_err();
+        assert!(format!("{error:#}").contains("src/["));
+    }
+}
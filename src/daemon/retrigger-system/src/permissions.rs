@@ -0,0 +1,324 @@
+//! Permission and ownership change detection
+//!
+//! A `chmod` or `chown` reaches the watcher as a plain `MetadataChanged`,
+//! indistinguishable from an mtime touch. [`PermissionTracker`] remembers each
+//! file's mode, owner and content stamp (size and mtime) as of its last
+//! event. When a `MetadataChanged` leaves the content stamp alone but changes
+//! the mode or owner, it reports a [`PermissionDelta`] listing exactly what
+//! changed, so a consumer can alert on, say, a file becoming world-writable
+//! without re-statting anything itself.
+//!
+//! A delta needs a previous state to compare against, so the first event for
+//! a file the tracker has not seen only records its attributes: a `chmod`
+//! that is the first change to an untouched file reports no delta. The same
+//! goes for a file the tracker has forgotten. Paths are forgotten when they
+//! are deleted and, with a quota set, oldest first once it is exceeded.
+//!
+//! Attributes are only known on Unix; elsewhere no delta is ever reported.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::SystemEventType;
+
+/// Mode bits and owner of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilePermissions {
+    /// Permission bits, `st_mode & 0o7777`
+    pub mode: u32,
+    pub uid: u32,
+    pub gid: u32,
+}
+
+impl FilePermissions {
+    #[cfg(unix)]
+    pub fn from_metadata(metadata: &std::fs::Metadata) -> Option<Self> {
+        use std::os::unix::fs::MetadataExt;
+        Some(Self {
+            mode: metadata.mode() & 0o7777,
+            uid: metadata.uid(),
+            gid: metadata.gid(),
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn from_metadata(_metadata: &std::fs::Metadata) -> Option<Self> {
+        None
+    }
+}
+
+/// An attribute's value before and after a change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Change<T> {
+    pub old: T,
+    pub new: T,
+}
+
+/// What a permission- or ownership-only change altered; unchanged
+/// attributes are `None`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionDelta {
+    pub mode: Option<Change<u32>>,
+    pub uid: Option<Change<u32>>,
+    pub gid: Option<Change<u32>>,
+}
+
+impl PermissionDelta {
+    /// Compare two permission sets; `None` if they are identical
+    pub fn between(old: &FilePermissions, new: &FilePermissions) -> Option<Self> {
+        let change = |old: u32, new: u32| (old != new).then_some(Change { old, new });
+        let delta = Self {
+            mode: change(old.mode, new.mode),
+            uid: change(old.uid, new.uid),
+            gid: change(old.gid, new.gid),
+        };
+        (delta != Self::default()).then_some(delta)
+    }
+
+    /// Whether the change made the file writable by anyone
+    pub fn became_world_writable(&self) -> bool {
+        self.mode
+            .is_some_and(|mode| mode.old & 0o002 == 0 && mode.new & 0o002 != 0)
+    }
+}
+
+/// Everything the tracker remembers about a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileAttributes {
+    pub permissions: FilePermissions,
+    pub size: u64,
+    /// Modification time in nanoseconds since the Unix epoch
+    pub mtime_ns: i128,
+}
+
+impl FileAttributes {
+    #[cfg(unix)]
+    pub fn from_metadata(metadata: &std::fs::Metadata) -> Option<Self> {
+        use std::os::unix::fs::MetadataExt;
+        Some(Self {
+            permissions: FilePermissions::from_metadata(metadata)?,
+            size: metadata.size(),
+            mtime_ns: metadata.mtime() as i128 * 1_000_000_000 + metadata.mtime_nsec() as i128,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn from_metadata(_metadata: &std::fs::Metadata) -> Option<Self> {
+        None
+    }
+}
+
+/// Per-path attribute memory for reporting permission deltas
+#[derive(Debug, Default)]
+pub struct PermissionTracker {
+    /// Attributes and the sequence number of the event that recorded them
+    attributes: DashMap<PathBuf, (FileAttributes, u64)>,
+    /// Cap on remembered paths; 0 is unlimited
+    max_entries: usize,
+    next_seq: AtomicU64,
+}
+
+impl PermissionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A tracker that remembers at most `max_entries` paths, forgetting the
+    /// least recently observed beyond that; 0 is unlimited
+    pub fn with_max_entries(max_entries: usize) -> Self {
+        Self {
+            max_entries,
+            ..Self::default()
+        }
+    }
+
+    /// Record `path`'s attributes after an event. `current` is `None` when
+    /// the path could not be stat'ed; it and `Deleted` events forget the path.
+    ///
+    /// Returns the delta for a `MetadataChanged` event that changed only
+    /// permissions or ownership; content changes and other event types
+    /// return `None`, as does the first event for a path.
+    pub fn observe(
+        &self,
+        path: &Path,
+        event_type: SystemEventType,
+        current: Option<FileAttributes>,
+    ) -> Option<PermissionDelta> {
+        let current = match current {
+            Some(current) if event_type != SystemEventType::Deleted => current,
+            _ => {
+                self.attributes.remove(path);
+                return None;
+            }
+        };
+
+        let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+        let previous = self.attributes.insert(path.to_path_buf(), (current, seq));
+        if previous.is_none() {
+            self.evict();
+        }
+        let (previous, _) = previous?;
+        if event_type != SystemEventType::MetadataChanged
+            || previous.size != current.size
+            || previous.mtime_ns != current.mtime_ns
+        {
+            return None;
+        }
+        PermissionDelta::between(&previous.permissions, &current.permissions)
+    }
+
+    /// Paths currently remembered
+    pub fn len(&self) -> usize {
+        self.attributes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+    }
+
+    /// Forget all tracked paths
+    pub fn clear(&self) {
+        self.attributes.clear();
+    }
+
+    fn evict(&self) {
+        let max_entries = self.max_entries;
+        let excess = self.attributes.len().saturating_sub(max_entries);
+        if max_entries == 0 || excess == 0 {
+            return;
+        }
+        // Trim a tenth below the quota so the sort is not repeated on every
+        // new path while the tracker sits at the limit
+        let mut by_age: Vec<(u64, PathBuf)> = self
+            .attributes
+            .iter()
+            .map(|entry| (entry.value().1, entry.key().clone()))
+            .collect();
+        by_age.sort_unstable();
+        for (_, path) in by_age.into_iter().take(excess + max_entries / 10) {
+            self.attributes.remove(&path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attributes(mode: u32, uid: u32, mtime_ns: i128) -> Option<FileAttributes> {
+        Some(FileAttributes {
+            permissions: FilePermissions {
+                mode,
+                uid,
+                gid: 100,
+            },
+            size: 10,
+            mtime_ns,
+        })
+    }
+
+    #[test]
+    fn test_permission_only_changes_report_delta() {
+        let tracker = PermissionTracker::new();
+        let path = Path::new("/project/deploy.sh");
+        let metadata_changed = SystemEventType::MetadataChanged;
+
+        // Nothing to compare against yet
+        assert_eq!(
+            tracker.observe(path, SystemEventType::Created, attributes(0o644, 0, 1)),
+            None
+        );
+
+        let delta = tracker
+            .observe(path, metadata_changed, attributes(0o666, 1000, 1))
+            .unwrap();
+        assert_eq!(
+            delta.mode,
+            Some(Change {
+                old: 0o644,
+                new: 0o666
+            })
+        );
+        assert_eq!(delta.uid, Some(Change { old: 0, new: 1000 }));
+        assert_eq!(delta.gid, None);
+        assert!(delta.became_world_writable());
+
+        // A touch moves mtime, so it is not a permission-only change
+        assert_eq!(
+            tracker.observe(path, metadata_changed, attributes(0o644, 1000, 2)),
+            None
+        );
+        assert_eq!(
+            tracker.observe(path, metadata_changed, attributes(0o644, 1000, 2)),
+            None
+        );
+
+        // A delete forgets the path
+        assert_eq!(tracker.observe(path, SystemEventType::Deleted, None), None);
+        assert_eq!(
+            tracker.observe(path, metadata_changed, attributes(0o600, 1000, 2)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_deletes_and_quota_forget_paths() {
+        let tracker = PermissionTracker::with_max_entries(10);
+        let path = |i: usize| PathBuf::from(format!("/project/file{i}.sh"));
+
+        // A delete forgets the path even if a new file is already there
+        tracker.observe(&path(0), SystemEventType::Created, attributes(0o644, 0, 1));
+        tracker.observe(&path(0), SystemEventType::Deleted, attributes(0o644, 0, 1));
+        assert!(tracker.is_empty());
+
+        for i in 0..100 {
+            tracker.observe(&path(i), SystemEventType::Created, attributes(0o644, 0, 1));
+        }
+        assert!(tracker.len() <= 10);
+
+        // The most recent paths are kept; a forgotten one starts over
+        let recent = tracker.observe(
+            &path(99),
+            SystemEventType::MetadataChanged,
+            attributes(0o755, 0, 1),
+        );
+        assert!(recent.is_some());
+        let forgotten = tracker.observe(
+            &path(0),
+            SystemEventType::MetadataChanged,
+            attributes(0o755, 0, 1),
+        );
+        assert_eq!(forgotten, None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_chmod_is_detected_from_disk() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, b"secret = true").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+        let stat = || FileAttributes::from_metadata(&std::fs::metadata(&path).unwrap());
+        let tracker = PermissionTracker::new();
+        tracker.observe(&path, SystemEventType::Created, stat());
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o646)).unwrap();
+        let delta = tracker
+            .observe(&path, SystemEventType::MetadataChanged, stat())
+            .unwrap();
+        assert_eq!(
+            delta.mode,
+            Some(Change {
+                old: 0o600,
+                new: 0o646
+            })
+        );
+        assert!(delta.became_world_writable());
+    }
+}
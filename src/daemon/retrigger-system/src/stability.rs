@@ -0,0 +1,240 @@
+//! Trailing-edge timers and stable-file detection
+//!
+//! [`TrailingTimer`] keeps one deadline per path that every new event pushes
+//! back, so an entry only fires once its path has been quiet for the whole
+//! window. This is the trailing-edge timer behind debouncing; the caller
+//! decides what firing means.
+//!
+//! [`StabilityTracker`] uses it for `stability_window_ms`: once a file has
+//! gone unmodified for the window, a synthetic
+//! [`SystemEventType::StabilizedModified`] is emitted for it. Unlike
+//! `debounce_ms`, which limits how often a path is reported, this reports
+//! when a path has stopped changing, e.g. so a deploy tool never acts on a
+//! half-written file.
+
+use std::path::{Path, PathBuf};
+
+use dashmap::DashMap;
+
+use crate::{SystemEvent, SystemEventType};
+
+/// Per-path deadlines that are pushed back by every touch
+#[derive(Debug)]
+pub struct TrailingTimer<T> {
+    pending: DashMap<PathBuf, (u64, T)>,
+}
+
+impl<T> Default for TrailingTimer<T> {
+    fn default() -> Self {
+        Self {
+            pending: DashMap::new(),
+        }
+    }
+}
+
+impl<T> TrailingTimer<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store `value` for `path` and restart its window at `now_ns`
+    pub fn touch(&self, path: &Path, value: T, now_ns: u64, window_ns: u64) {
+        self.pending.insert(
+            path.to_path_buf(),
+            (now_ns.saturating_add(window_ns), value),
+        );
+    }
+
+    /// Drop the pending entry for `path`
+    pub fn cancel(&self, path: &Path) -> Option<T> {
+        self.pending.remove(path).map(|(_, (_, value))| value)
+    }
+
+    /// Remove and return entries whose window has elapsed, earliest first
+    pub fn take_due(&self, now_ns: u64) -> Vec<T> {
+        let due: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|entry| entry.0 <= now_ns)
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        let mut fired: Vec<(u64, T)> = due
+            .iter()
+            // Re-check under the shard lock: the path may have been touched since
+            .filter_map(|path| {
+                self.pending
+                    .remove_if(path, |_, (deadline, _)| *deadline <= now_ns)
+            })
+            .map(|(_, entry)| entry)
+            .collect();
+        fired.sort_by_key(|(deadline, _)| *deadline);
+        fired.into_iter().map(|(_, value)| value).collect()
+    }
+
+    /// Number of paths waiting for their window to elapse
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Drop all pending entries
+    pub fn clear(&self) {
+        self.pending.clear();
+    }
+}
+
+/// Reports files that have stopped changing
+#[derive(Debug, Default)]
+pub struct StabilityTracker {
+    timer: TrailingTimer<SystemEvent>,
+}
+
+impl StabilityTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track a delivered event; any change restarts its path's window
+    pub fn observe(&self, event: &SystemEvent, now_ns: u64, window_ns: u64) {
+        self.observe_with(event, now_ns, window_ns, || event.path.exists())
+    }
+
+    fn observe_with(
+        &self,
+        event: &SystemEvent,
+        now_ns: u64,
+        window_ns: u64,
+        exists: impl FnOnce() -> bool,
+    ) {
+        if event.is_directory {
+            return;
+        }
+
+        match event.event_type {
+            SystemEventType::Created
+            | SystemEventType::Modified
+            | SystemEventType::MetadataChanged => {
+                self.timer
+                    .touch(&event.path, event.clone(), now_ns, window_ns);
+            }
+            SystemEventType::Deleted => {
+                self.timer.cancel(&event.path);
+            }
+            // Both sides of a rename arrive as `Moved`; only the side that
+            // now exists can settle
+            SystemEventType::Moved => {
+                if exists() {
+                    self.timer
+                        .touch(&event.path, event.clone(), now_ns, window_ns);
+                } else {
+                    self.timer.cancel(&event.path);
+                }
+            }
+            SystemEventType::StabilizedModified | SystemEventType::Heartbeat => {}
+        }
+    }
+
+    /// `StabilizedModified` events for files quiet for their whole window
+    ///
+    /// Each file is re-stat'ed so the event carries its settled size; files
+    /// that disappeared without a delete event are dropped.
+    pub fn take_stable(&self, now_ns: u64) -> Vec<SystemEvent> {
+        self.timer
+            .take_due(now_ns)
+            .into_iter()
+            .filter_map(|mut event| {
+                let metadata = std::fs::metadata(&event.path).ok()?;
+                event.event_type = SystemEventType::StabilizedModified;
+                event.timestamp = now_ns;
+                event.size = metadata.len();
+                Some(event)
+            })
+            .collect()
+    }
+
+    /// Number of files not yet stable
+    pub fn pending(&self) -> usize {
+        self.timer.len()
+    }
+
+    /// Forget all tracked files
+    pub fn clear(&self) {
+        self.timer.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: u64 = 1_000_000;
+
+    #[test]
+    fn test_trailing_timer_fires_after_quiet_window() {
+        let timer = TrailingTimer::new();
+        let path = Path::new("/w/a.txt");
+
+        timer.touch(path, 1, 0, 100 * MS);
+        timer.touch(path, 2, 80 * MS, 100 * MS);
+        timer.touch(Path::new("/w/b.txt"), 3, 10 * MS, 100 * MS);
+
+        // b's window elapsed; a was pushed back by its second touch
+        assert_eq!(timer.take_due(150 * MS), vec![3]);
+        assert_eq!(timer.take_due(179 * MS), Vec::<i32>::new());
+        assert_eq!(timer.take_due(180 * MS), vec![2]);
+        assert!(timer.is_empty());
+    }
+
+    #[test]
+    fn test_stable_file_emits_once_after_writes_stop() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundle.js");
+        std::fs::write(&path, b"partial").unwrap();
+
+        let tracker = StabilityTracker::new();
+        let event = |event_type| SystemEvent {
+            path: path.clone(),
+            event_type,
+            timestamp: 0,
+            size: 0,
+            is_directory: false,
+            metadata: None,
+        };
+
+        tracker.observe(&event(SystemEventType::Created), 0, 100 * MS);
+        tracker.observe(&event(SystemEventType::Modified), 50 * MS, 100 * MS);
+        std::fs::write(&path, b"partial and complete").unwrap();
+        assert!(tracker.take_stable(120 * MS).is_empty());
+
+        let stable = tracker.take_stable(150 * MS);
+        assert_eq!(stable.len(), 1);
+        assert_eq!(stable[0].event_type, SystemEventType::StabilizedModified);
+        assert_eq!(stable[0].size, 20);
+        assert!(tracker.take_stable(500 * MS).is_empty());
+    }
+
+    #[test]
+    fn test_delete_and_move_away_cancel() {
+        let tracker = StabilityTracker::new();
+        let event = |path: &str, event_type| SystemEvent {
+            path: PathBuf::from(path),
+            event_type,
+            timestamp: 0,
+            size: 0,
+            is_directory: false,
+            metadata: None,
+        };
+
+        tracker.observe(&event("/w/a.txt", SystemEventType::Modified), 0, MS);
+        tracker.observe(&event("/w/a.txt", SystemEventType::Deleted), 0, MS);
+        tracker.observe(&event("/w/b.txt", SystemEventType::Modified), 0, MS);
+        tracker.observe_with(&event("/w/b.txt", SystemEventType::Moved), 0, MS, || false);
+        tracker.observe_with(&event("/w/c.txt", SystemEventType::Moved), 0, MS, || true);
+
+        assert_eq!(tracker.pending(), 1);
+    }
+}
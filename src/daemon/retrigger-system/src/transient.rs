@@ -0,0 +1,164 @@
+//! Suppression of files that exist only briefly
+//!
+//! Editors and build tools create lock and scratch files (`.#main.rs`,
+//! `*.tmp`) and delete them again within milliseconds, often thousands of
+//! times a session. Each such file costs a `Created` and a `Deleted` that no
+//! consumer cares about, since the file never meaningfully existed.
+//!
+//! With `transient_window_ms` set, [`TransientFilter`] holds every `Created`
+//! for the window. A `Deleted` for the same path before it elapses cancels
+//! both; otherwise the `Created` is released once the window elapses, or as
+//! soon as any other event arrives for its path, so per-path order is kept.
+//! Holding delays every creation by up to the window, which is why this is
+//! opt-in.
+//!
+//! Pairs are matched before the size and path filters run, so a filter that
+//! would drop the `Deleted` (e.g. `min_file_size`) cannot keep its `Created`
+//! alive. Each suppressed pair is counted as two
+//! [`DropReason::Transient`](crate::DropReason::Transient) drops.
+
+use crate::stability::TrailingTimer;
+use crate::{SystemEvent, SystemEventType};
+
+/// What became of an event offered to [`TransientFilter::observe`]
+#[derive(Debug, Clone)]
+pub enum TransientOutcome {
+    /// Deliver these now, in order; a held `Created` for the same path comes
+    /// before the event itself
+    Release(Vec<SystemEvent>),
+    /// A `Created`, held until its window elapses
+    Held,
+    /// A `Deleted` that cancelled its path's held `Created`; neither is
+    /// delivered
+    Suppressed,
+}
+
+/// Holds `Created` events until they outlive the transient window
+#[derive(Debug, Default)]
+pub struct TransientFilter {
+    held: TrailingTimer<SystemEvent>,
+}
+
+impl TransientFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Offer an event at `now_ns`; a `Created` is held for `window_ns`
+    ///
+    /// A second `Created` for a held path replaces the first and restarts
+    /// the window.
+    pub fn observe(&self, event: SystemEvent, now_ns: u64, window_ns: u64) -> TransientOutcome {
+        match event.event_type {
+            SystemEventType::Created => {
+                let path = event.path.clone();
+                self.held.touch(&path, event, now_ns, window_ns);
+                TransientOutcome::Held
+            }
+            SystemEventType::Deleted if self.held.cancel(&event.path).is_some() => {
+                TransientOutcome::Suppressed
+            }
+            SystemEventType::Heartbeat => TransientOutcome::Release(vec![event]),
+            _ => {
+                let mut ready: Vec<SystemEvent> =
+                    self.held.cancel(&event.path).into_iter().collect();
+                ready.push(event);
+                TransientOutcome::Release(ready)
+            }
+        }
+    }
+
+    /// Held `Created` events whose window elapsed without a delete,
+    /// earliest first
+    pub fn take_due(&self, now_ns: u64) -> Vec<SystemEvent> {
+        self.held.take_due(now_ns)
+    }
+
+    /// Number of `Created` events being held
+    pub fn held(&self) -> usize {
+        self.held.len()
+    }
+
+    /// Forget all held events
+    pub fn clear(&self) {
+        self.held.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const MS: u64 = 1_000_000;
+
+    fn event(path: &str, event_type: SystemEventType) -> SystemEvent {
+        SystemEvent {
+            path: PathBuf::from(path),
+            event_type,
+            timestamp: 0,
+            size: 0,
+            is_directory: false,
+            metadata: None,
+        }
+    }
+
+    fn types(outcome: TransientOutcome) -> Vec<SystemEventType> {
+        match outcome {
+            TransientOutcome::Release(events) => {
+                events.into_iter().map(|event| event.event_type).collect()
+            }
+            other => panic!("expected a release, got {other:?}"),
+        }
+    }
+
+    /// Offer an event at `at_ms` with a 100ms window
+    fn offer(
+        filter: &TransientFilter,
+        path: &str,
+        event_type: SystemEventType,
+        at_ms: u64,
+    ) -> TransientOutcome {
+        filter.observe(event(path, event_type), at_ms * MS, 100 * MS)
+    }
+
+    #[test]
+    fn test_create_then_delete_is_suppressed() {
+        let filter = TransientFilter::new();
+
+        let outcome = offer(&filter, "/w/.#lock", SystemEventType::Created, 0);
+        assert!(matches!(outcome, TransientOutcome::Held));
+        let outcome = offer(&filter, "/w/.#lock", SystemEventType::Deleted, 40);
+        assert!(matches!(outcome, TransientOutcome::Suppressed));
+        assert_eq!(filter.held(), 0);
+
+        // A delete with nothing held passes through
+        let outcome = offer(&filter, "/w/.#lock", SystemEventType::Deleted, 50);
+        assert_eq!(types(outcome), vec![SystemEventType::Deleted]);
+    }
+
+    #[test]
+    fn test_created_released_after_window_or_before_later_event() {
+        let filter = TransientFilter::new();
+
+        offer(&filter, "/w/a.rs", SystemEventType::Created, 0);
+        offer(&filter, "/w/b.rs", SystemEventType::Created, 10);
+        assert!(filter.take_due(99 * MS).is_empty());
+
+        let due = filter.take_due(100 * MS);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].path, PathBuf::from("/w/a.rs"));
+
+        // A write to a held file releases its creation first
+        let outcome = offer(&filter, "/w/b.rs", SystemEventType::Modified, 105);
+        assert_eq!(
+            types(outcome),
+            vec![SystemEventType::Created, SystemEventType::Modified]
+        );
+
+        // ... so a later delete is an ordinary one
+        let outcome = offer(&filter, "/w/b.rs", SystemEventType::Deleted, 106);
+        assert_eq!(types(outcome), vec![SystemEventType::Deleted]);
+        assert!(filter.take_due(u64::MAX).is_empty());
+    }
+}
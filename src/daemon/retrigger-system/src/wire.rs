@@ -0,0 +1,464 @@
+//! Compact binary wire format for file events
+//!
+//! Fixed-layout encoding of `EnhancedFileEvent` used by the zero-copy IPC ring
+//! and available to custom transports. Every record is exactly
+//! `SERIALIZED_EVENT_SIZE` bytes so it can be `memcpy`'d into slots.
+//!
+//! # Layout (version 2)
+//!
+//! All integers are little-endian. `SerializedFileEvent` is `#[repr(C)]` with
+//! no padding, so on little-endian targets (x86_64, aarch64) the in-memory
+//! struct and `to_bytes()` output are byte-identical.
+//!
+//! | Offset | Size | Field          | Notes                                    |
+//! |--------|------|----------------|------------------------------------------|
+//! | 0      | 8    | `timestamp`    | Event timestamp (ns)                     |
+//! | 8      | 4    | `event_type`   | 0=created 1=modified 2=deleted 3=moved 4=metadata_changed 5=stabilized_modified 6=heartbeat |
+//! | 12     | 4    | `path_len`     | Number of valid bytes in `path_data`     |
+//! | 16     | 8    | `size`         | File size in bytes                       |
+//! | 24     | 4    | `is_directory` | 0 or 1                                   |
+//! | 28     | 4    | `hash_present` | 0 or 1                                   |
+//! | 32     | 8    | `hash_value`   | Truncated 64-bit hash, 0 when absent     |
+//! | 40     | 8    | `events_processed` | Heartbeats only, 0 otherwise         |
+//! | 48     | 512  | `path_data`    | Raw path bytes, zero padded              |
+//!
+//! # Versioning
+//!
+//! Records carry no version of their own; the container announces
+//! `WIRE_FORMAT_VERSION` once (the IPC ring stores it in its header). Any
+//! change to the table above bumps the version.
+//!
+//! Version 2 added event types 5 (`stabilized_modified`) and 6
+//! (`heartbeat`), and the `events_processed` field. Heartbeats are not about
+//! a file: they have an empty path and a `size` of 0, and carry the number of
+//! events the daemon has processed in `events_processed`.
+//!
+//! Paths are encoded as raw OS bytes on Unix, so non-UTF-8 paths round-trip.
+//! On other platforms they are encoded as lossy UTF-8. Paths longer than
+//! `MAX_WIRE_PATH_LEN` bytes are truncated. `processing_time_ns`, the hash's
+//! `is_incremental` flag and its full `digest` are not transmitted, so
+//! `HashResult::to_hex` on a decoded event yields only the truncated hash.
+//!
+//! # Untrusted input
+//!
+//! Records read from shared memory were written by another process and may
+//! hold anything. Decoding never panics: [`SerializedFileEvent::validate`]
+//! reports out-of-range fields, [`SerializedFileEvent::decode`] returns `None`
+//! for such records, and the `From` conversion builds a best-effort event
+//! (path clamped to the buffer, unknown event types read as `Modified`, any
+//! non-zero flag read as set). `fuzz/` holds a cargo-fuzz target over
+//! arbitrary records.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use retrigger_core::HashResult;
+
+use crate::{EnhancedFileEvent, SystemEvent, SystemEventType};
+
+/// Wire format version, bumped on any layout change
+pub const WIRE_FORMAT_VERSION: u32 = 2;
+
+/// Size in bytes of one serialized event record
+pub const SERIALIZED_EVENT_SIZE: usize = 560;
+
+/// Capacity of the fixed path buffer
+pub const PATH_BUFFER_SIZE: usize = 512;
+
+/// Longest path that is transmitted without truncation (one byte is kept as
+/// a null terminator for C consumers)
+pub const MAX_WIRE_PATH_LEN: usize = PATH_BUFFER_SIZE - 1;
+
+const TIMESTAMP_OFFSET: usize = 0;
+const EVENT_TYPE_OFFSET: usize = 8;
+const PATH_LEN_OFFSET: usize = 12;
+const SIZE_OFFSET: usize = 16;
+const IS_DIRECTORY_OFFSET: usize = 24;
+const HASH_PRESENT_OFFSET: usize = 28;
+const HASH_VALUE_OFFSET: usize = 32;
+const EVENTS_PROCESSED_OFFSET: usize = 40;
+const PATH_DATA_OFFSET: usize = 48;
+
+/// Serialized file event for cross-process communication
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct SerializedFileEvent {
+    pub timestamp: u64,
+    pub event_type: u32,
+    pub path_len: u32,
+    pub size: u64,
+    pub is_directory: u32,
+    pub hash_present: u32,
+    pub hash_value: u64,
+    pub events_processed: u64,
+    pub path_data: [u8; PATH_BUFFER_SIZE],
+}
+
+// The layout is part of the public contract
+const _: () = assert!(std::mem::size_of::<SerializedFileEvent>() == SERIALIZED_EVENT_SIZE);
+
+impl SerializedFileEvent {
+    /// Encode into the little-endian wire representation
+    pub fn to_bytes(&self) -> [u8; SERIALIZED_EVENT_SIZE] {
+        let mut buf = [0u8; SERIALIZED_EVENT_SIZE];
+        buf[TIMESTAMP_OFFSET..TIMESTAMP_OFFSET + 8].copy_from_slice(&self.timestamp.to_le_bytes());
+        buf[EVENT_TYPE_OFFSET..EVENT_TYPE_OFFSET + 4]
+            .copy_from_slice(&self.event_type.to_le_bytes());
+        buf[PATH_LEN_OFFSET..PATH_LEN_OFFSET + 4].copy_from_slice(&self.path_len.to_le_bytes());
+        buf[SIZE_OFFSET..SIZE_OFFSET + 8].copy_from_slice(&self.size.to_le_bytes());
+        buf[IS_DIRECTORY_OFFSET..IS_DIRECTORY_OFFSET + 4]
+            .copy_from_slice(&self.is_directory.to_le_bytes());
+        buf[HASH_PRESENT_OFFSET..HASH_PRESENT_OFFSET + 4]
+            .copy_from_slice(&self.hash_present.to_le_bytes());
+        buf[HASH_VALUE_OFFSET..HASH_VALUE_OFFSET + 8]
+            .copy_from_slice(&self.hash_value.to_le_bytes());
+        buf[EVENTS_PROCESSED_OFFSET..EVENTS_PROCESSED_OFFSET + 8]
+            .copy_from_slice(&self.events_processed.to_le_bytes());
+        buf[PATH_DATA_OFFSET..].copy_from_slice(&self.path_data);
+        buf
+    }
+
+    /// Check that every field is in range for the current wire format
+    pub fn validate(&self) -> Result<()> {
+        if self.path_len as usize > MAX_WIRE_PATH_LEN {
+            anyhow::bail!(
+                "Path length {} exceeds {} bytes",
+                self.path_len,
+                MAX_WIRE_PATH_LEN
+            );
+        }
+        if event_type_from_wire(self.event_type).is_none() {
+            anyhow::bail!("Unknown event type {}", self.event_type);
+        }
+        if self.is_directory > 1 {
+            anyhow::bail!("Invalid is_directory flag {}", self.is_directory);
+        }
+        if self.hash_present > 1 {
+            anyhow::bail!("Invalid hash_present flag {}", self.hash_present);
+        }
+        Ok(())
+    }
+
+    /// The event this record holds, or `None` if it fails [`Self::validate`]
+    pub fn decode(&self) -> Option<EnhancedFileEvent> {
+        self.validate().ok()?;
+        Some(EnhancedFileEvent::from(self))
+    }
+
+    /// Decode from the little-endian wire representation
+    ///
+    /// Only the length is checked; see [`Self::validate`] for the fields.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < SERIALIZED_EVENT_SIZE {
+            anyhow::bail!(
+                "Serialized event too short: {} bytes (expected {})",
+                bytes.len(),
+                SERIALIZED_EVENT_SIZE
+            );
+        }
+
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+
+        let mut path_data = [0u8; PATH_BUFFER_SIZE];
+        path_data.copy_from_slice(&bytes[PATH_DATA_OFFSET..SERIALIZED_EVENT_SIZE]);
+
+        Ok(Self {
+            timestamp: u64_at(TIMESTAMP_OFFSET),
+            event_type: u32_at(EVENT_TYPE_OFFSET),
+            path_len: u32_at(PATH_LEN_OFFSET),
+            size: u64_at(SIZE_OFFSET),
+            is_directory: u32_at(IS_DIRECTORY_OFFSET),
+            hash_present: u32_at(HASH_PRESENT_OFFSET),
+            hash_value: u64_at(HASH_VALUE_OFFSET),
+            events_processed: u64_at(EVENTS_PROCESSED_OFFSET),
+            path_data,
+        })
+    }
+}
+
+impl From<&EnhancedFileEvent> for SerializedFileEvent {
+    fn from(event: &EnhancedFileEvent) -> Self {
+        let path_bytes = path_to_bytes(&event.system_event.path);
+        let path_len = std::cmp::min(path_bytes.len(), MAX_WIRE_PATH_LEN);
+
+        let mut path_data = [0u8; PATH_BUFFER_SIZE];
+        path_data[..path_len].copy_from_slice(&path_bytes[..path_len]);
+
+        let event_type = match event.system_event.event_type {
+            SystemEventType::Created => 0,
+            SystemEventType::Modified => 1,
+            SystemEventType::Deleted => 2,
+            SystemEventType::Moved => 3,
+            SystemEventType::MetadataChanged => 4,
+            SystemEventType::StabilizedModified => 5,
+            SystemEventType::Heartbeat => 6,
+        };
+
+        Self {
+            timestamp: event.system_event.timestamp,
+            event_type,
+            path_len: path_len as u32,
+            size: event.system_event.size,
+            is_directory: if event.system_event.is_directory {
+                1
+            } else {
+                0
+            },
+            hash_present: if event.hash.is_some() { 1 } else { 0 },
+            hash_value: event.hash.as_ref().map(|h| h.hash).unwrap_or(0),
+            events_processed: event.events_processed.unwrap_or(0),
+            path_data,
+        }
+    }
+}
+
+fn event_type_from_wire(event_type: u32) -> Option<SystemEventType> {
+    match event_type {
+        0 => Some(SystemEventType::Created),
+        1 => Some(SystemEventType::Modified),
+        2 => Some(SystemEventType::Deleted),
+        3 => Some(SystemEventType::Moved),
+        4 => Some(SystemEventType::MetadataChanged),
+        5 => Some(SystemEventType::StabilizedModified),
+        6 => Some(SystemEventType::Heartbeat),
+        _ => None,
+    }
+}
+
+/// Best-effort conversion that never panics, even on malformed records; use
+/// [`SerializedFileEvent::decode`] to reject them instead
+impl From<&SerializedFileEvent> for EnhancedFileEvent {
+    fn from(ser: &SerializedFileEvent) -> Self {
+        let path_len = (ser.path_len as usize).min(PATH_BUFFER_SIZE);
+        let path = path_from_bytes(&ser.path_data[..path_len]);
+
+        let event_type =
+            event_type_from_wire(ser.event_type).unwrap_or(SystemEventType::Modified);
+
+        let system_event = SystemEvent {
+            path,
+            event_type,
+            timestamp: ser.timestamp,
+            size: ser.size,
+            is_directory: ser.is_directory != 0,
+            metadata: None,
+        };
+
+        let hash = if ser.hash_present != 0 {
+            Some(HashResult {
+                hash: ser.hash_value,
+                size: ser.size as u32,
+                is_incremental: false,
+                digest: None,
+            })
+        } else {
+            None
+        };
+
+        let events_processed =
+            (event_type == SystemEventType::Heartbeat).then_some(ser.events_processed);
+
+        EnhancedFileEvent {
+            system_event,
+            hash,
+            processing_time_ns: 0, // Will be set by consumer if needed
+            metadata: None,
+            events_processed,
+        }
+    }
+}
+
+impl EnhancedFileEvent {
+    /// Encode this event in the fixed-size wire format (see `wire` module docs)
+    pub fn to_bytes(&self) -> [u8; SERIALIZED_EVENT_SIZE] {
+        SerializedFileEvent::from(self).to_bytes()
+    }
+
+    /// Decode an event previously produced by `to_bytes`
+    ///
+    /// Fails on records with out-of-range fields rather than guessing.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let ser = SerializedFileEvent::from_bytes(bytes)?;
+        ser.validate()?;
+        Ok(EnhancedFileEvent::from(&ser))
+    }
+}
+
+#[cfg(unix)]
+fn path_to_bytes(path: &std::path::Path) -> std::borrow::Cow<'_, [u8]> {
+    use std::os::unix::ffi::OsStrExt;
+    std::borrow::Cow::Borrowed(path.as_os_str().as_bytes())
+}
+
+#[cfg(not(unix))]
+fn path_to_bytes(path: &std::path::Path) -> std::borrow::Cow<'_, [u8]> {
+    match path.to_string_lossy() {
+        std::borrow::Cow::Borrowed(s) => std::borrow::Cow::Borrowed(s.as_bytes()),
+        std::borrow::Cow::Owned(s) => std::borrow::Cow::Owned(s.into_bytes()),
+    }
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_event(path: PathBuf, hash: Option<HashResult>) -> EnhancedFileEvent {
+        EnhancedFileEvent {
+            system_event: SystemEvent {
+                path,
+                event_type: SystemEventType::Moved,
+                timestamp: 0x0102_0304_0506_0708,
+                size: 4096,
+                is_directory: false,
+                metadata: None,
+            },
+            hash,
+            processing_time_ns: 0,
+            metadata: None,
+            events_processed: None,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let event = sample_event(
+            PathBuf::from("/project/src/main.rs"),
+            Some(HashResult {
+                hash: 0xDEAD_BEEF_CAFE_F00D,
+                size: 4096,
+                is_incremental: false,
+                digest: None,
+            }),
+        );
+
+        let bytes = event.to_bytes();
+        assert_eq!(bytes.len(), SERIALIZED_EVENT_SIZE);
+        assert_eq!(&bytes[..8], &0x0102_0304_0506_0708u64.to_le_bytes());
+
+        let decoded = EnhancedFileEvent::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.system_event.path, event.system_event.path);
+        assert_eq!(decoded.system_event.event_type, SystemEventType::Moved);
+        assert_eq!(decoded.system_event.timestamp, event.system_event.timestamp);
+        assert_eq!(decoded.system_event.size, 4096);
+        assert_eq!(decoded.hash, event.hash);
+    }
+
+    #[test]
+    fn test_round_trip_without_hash() {
+        let event = sample_event(PathBuf::from("/tmp/file.txt"), None);
+        let decoded = EnhancedFileEvent::from_bytes(&event.to_bytes()).unwrap();
+        assert!(decoded.hash.is_none());
+    }
+
+    #[test]
+    fn test_max_length_path() {
+        let exact = PathBuf::from(format!("/{}", "a".repeat(MAX_WIRE_PATH_LEN - 1)));
+        let decoded =
+            EnhancedFileEvent::from_bytes(&sample_event(exact.clone(), None).to_bytes()).unwrap();
+        assert_eq!(decoded.system_event.path, exact);
+
+        let long = PathBuf::from(format!("/{}", "b".repeat(PATH_BUFFER_SIZE * 2)));
+        let decoded = EnhancedFileEvent::from_bytes(&sample_event(long, None).to_bytes()).unwrap();
+        assert_eq!(
+            decoded.system_event.path.as_os_str().len(),
+            MAX_WIRE_PATH_LEN
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_path() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let raw = b"/tmp/caf\xe9.txt";
+        let path = PathBuf::from(std::ffi::OsStr::from_bytes(raw));
+        let decoded = EnhancedFileEvent::from_bytes(&sample_event(path, None).to_bytes()).unwrap();
+        assert_eq!(decoded.system_event.path.as_os_str().as_bytes(), raw);
+    }
+
+    #[test]
+    fn test_heartbeat_count_has_its_own_field() {
+        let mut heartbeat = sample_event(PathBuf::new(), None);
+        heartbeat.system_event.event_type = SystemEventType::Heartbeat;
+        heartbeat.system_event.size = 0;
+        heartbeat.events_processed = Some(1_234);
+
+        let decoded = EnhancedFileEvent::from_bytes(&heartbeat.to_bytes()).unwrap();
+        assert_eq!(decoded.system_event.event_type, SystemEventType::Heartbeat);
+        assert_eq!(decoded.system_event.size, 0);
+        assert_eq!(decoded.events_processed, Some(1_234));
+
+        // Only heartbeats carry a count
+        let file = sample_event(PathBuf::from("/f"), None);
+        let decoded = EnhancedFileEvent::from_bytes(&file.to_bytes()).unwrap();
+        assert_eq!(decoded.events_processed, None);
+    }
+
+    #[test]
+    fn test_short_buffer_rejected() {
+        assert!(EnhancedFileEvent::from_bytes(&[0u8; 16]).is_err());
+    }
+
+    #[test]
+    fn test_malformed_records_do_not_panic() {
+        let valid = SerializedFileEvent::from(&sample_event(PathBuf::from("/ok"), None));
+        assert!(valid.decode().is_some());
+
+        let mut oversized = valid.clone();
+        oversized.path_len = u32::MAX;
+        assert!(oversized.validate().is_err());
+        assert!(oversized.decode().is_none());
+        assert!(EnhancedFileEvent::from_bytes(&oversized.to_bytes()).is_err());
+        // Best effort: the whole buffer, no out-of-bounds slice
+        let event = EnhancedFileEvent::from(&oversized);
+        assert_eq!(event.system_event.path.as_os_str().len(), PATH_BUFFER_SIZE);
+
+        let mut unknown_type = valid.clone();
+        unknown_type.event_type = 99;
+        assert!(unknown_type.decode().is_none());
+        let event = EnhancedFileEvent::from(&unknown_type);
+        assert_eq!(event.system_event.event_type, SystemEventType::Modified);
+
+        let mut bad_flags = valid.clone();
+        bad_flags.is_directory = 7;
+        bad_flags.hash_present = 2;
+        assert!(bad_flags.validate().is_err());
+        let event = EnhancedFileEvent::from(&bad_flags);
+        assert!(event.system_event.is_directory);
+        assert!(event.hash.is_some());
+
+        let garbage = [0xFFu8; SERIALIZED_EVENT_SIZE];
+        assert!(EnhancedFileEvent::from_bytes(&garbage).is_err());
+        let ser = SerializedFileEvent::from_bytes(&garbage).unwrap();
+        let _ = EnhancedFileEvent::from(&ser);
+    }
+
+    #[test]
+    fn test_in_memory_layout_matches_wire() {
+        let event = sample_event(PathBuf::from("/layout"), None);
+        let ser = SerializedFileEvent::from(&event);
+        let raw = unsafe {
+            std::slice::from_raw_parts(
+                &ser as *const SerializedFileEvent as *const u8,
+                SERIALIZED_EVENT_SIZE,
+            )
+        };
+        if cfg!(target_endian = "little") {
+            assert_eq!(raw, &ser.to_bytes()[..]);
+        }
+    }
+}
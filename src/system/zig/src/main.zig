@@ -189,7 +189,15 @@ pub const FileWatcher = struct {
 
     /// Start watching a directory tree with error handling
     pub fn watch_directory(self: *Self, path: []const u8, recursive: bool) !void {
-        self.impl.watch_directory(path, recursive, &self.event_buffer) catch |err| {
+        return self.watch_directory_with(path, recursive, true, 0);
+    }
+
+    /// Like `watch_directory`; with `cross_filesystem` false a recursive
+    /// watch does not descend into directories on other devices, and with
+    /// `max_entries_per_dir` non-zero it does not descend into directories
+    /// holding more entries than that
+    pub fn watch_directory_with(self: *Self, path: []const u8, recursive: bool, cross_filesystem: bool, max_entries_per_dir: usize) !void {
+        self.impl.watch_directory(path, recursive, cross_filesystem, max_entries_per_dir, &self.event_buffer) catch |err| {
             const context = std.fmt.allocPrint(self.allocator, "Failed to watch directory: {s}", .{path}) catch "watch_directory";
             defer if (!std.mem.eql(u8, context, "watch_directory")) self.allocator.free(context);
 
@@ -377,9 +385,15 @@ export fn fw_watcher_destroy(watcher: *FileWatcher) void {
     allocator.destroy(watcher);
 }
 
-export fn fw_watcher_watch_directory(watcher: *FileWatcher, path: [*:0]const u8, recursive: bool) c_int {
+export fn fw_watcher_watch_directory(watcher: *FileWatcher, path: [*:0]const u8, recursive: bool, cross_filesystem: bool, max_entries_per_dir: usize) c_int {
+    const path_slice = std.mem.span(path);
+    watcher.watch_directory_with(path_slice, recursive, cross_filesystem, max_entries_per_dir) catch return -1;
+    return 0;
+}
+
+export fn fw_watcher_unwatch_directory(watcher: *FileWatcher, path: [*:0]const u8) c_int {
     const path_slice = std.mem.span(path);
-    watcher.watch_directory(path_slice, recursive) catch return -1;
+    watcher.unwatch_directory(path_slice) catch return -1;
     return 0;
 }
 
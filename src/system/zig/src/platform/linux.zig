@@ -167,7 +167,7 @@ pub const LinuxWatcher = struct {
         }
     }
 
-    pub fn watch_directory(self: *Self, path: []const u8, recursive: bool, event_buffer: *EventRingBuffer) !void {
+    pub fn watch_directory(self: *Self, path: []const u8, recursive: bool, cross_filesystem: bool, max_entries_per_dir: usize, event_buffer: *EventRingBuffer) !void {
         // Store reference to event buffer for use in event processing
         self.event_buffer = event_buffer;
         // Use inotify for directory watching
@@ -186,9 +186,14 @@ pub const LinuxWatcher = struct {
         const owned_path = try self.path_allocator.dupe(u8, path);
         try self.watch_descriptors.put(owned_path, @intCast(wd));
 
-        // If recursive, walk the directory tree
+        // If recursive, walk the directory tree, staying on the root's
+        // device unless crossing filesystems was requested
         if (recursive) {
-            try self.watch_directory_recursive(path, mask);
+            const root_dev: ?u64 = if (cross_filesystem) null else blk: {
+                const stat = std.posix.fstatat(std.posix.AT.FDCWD, path, 0) catch break :blk null;
+                break :blk @intCast(stat.dev);
+            };
+            try self.watch_directory_recursive(path, mask, root_dev, max_entries_per_dir);
         }
 
         // Try to add fanotify mark if available (requires root/CAP_SYS_ADMIN)
@@ -206,10 +211,26 @@ pub const LinuxWatcher = struct {
         }
     }
 
-    fn watch_directory_recursive(self: *Self, base_path: []const u8, mask: u32) !void {
+    /// Add watches below `base_path`; with `root_dev` set, directories on
+    /// other devices (mount points) are skipped, like `find -xdev`. With
+    /// `max_entries_per_dir` non-zero, a directory holding more entries than
+    /// that is watched but not descended into.
+    fn watch_directory_recursive(self: *Self, base_path: []const u8, mask: u32, root_dev: ?u64, max_entries_per_dir: usize) !void {
         var dir = std.fs.cwd().openDir(base_path, .{ .iterate = true }) catch return;
         defer dir.close();
 
+        if (max_entries_per_dir > 0) {
+            var count: usize = 0;
+            var counter = dir.iterate();
+            while (try counter.next()) |_| {
+                count += 1;
+                if (count > max_entries_per_dir) {
+                    std.log.warn("Not descending into {s}: more than {} entries", .{ base_path, max_entries_per_dir });
+                    return;
+                }
+            }
+        }
+
         var iterator = dir.iterate();
         while (try iterator.next()) |entry| {
             if (entry.kind == .directory) {
@@ -217,6 +238,14 @@ pub const LinuxWatcher = struct {
                 var path_buffer: [c.PATH_MAX]u8 = undefined;
                 const full_path = try std.fmt.bufPrint(&path_buffer, "{s}/{s}", .{ base_path, entry.name });
 
+                if (root_dev) |dev| {
+                    const stat = std.posix.fstatat(dir.fd, entry.name, 0) catch continue;
+                    if (@as(u64, @intCast(stat.dev)) != dev) {
+                        std.log.info("Not crossing filesystem boundary at {s}", .{full_path});
+                        continue;
+                    }
+                }
+
                 // Create null-terminated string for inotify_add_watch
                 const full_path_z = try self.path_allocator.dupeZ(u8, full_path);
                 defer self.path_allocator.free(full_path_z);
@@ -226,17 +255,38 @@ pub const LinuxWatcher = struct {
                     try self.watch_descriptors.put(owned_path, @intCast(wd));
 
                     // Recurse into subdirectory
-                    try self.watch_directory_recursive(full_path, mask);
+                    try self.watch_directory_recursive(full_path, mask, root_dev, max_entries_per_dir);
                 }
             }
         }
     }
 
+    /// Remove the watch on `path` and the watches a recursive watch added
+    /// below it
     pub fn unwatch_directory(self: *Self, path: []const u8) !void {
-        if (self.watch_descriptors.get(path)) |wd| {
-            _ = linux.inotify_rm_watch(self.inotify_fd, @intCast(wd));
-            _ = self.watch_descriptors.remove(path);
-            self.path_allocator.free(path);
+        var below = std.ArrayList([]const u8){};
+        defer below.deinit(self.path_allocator);
+
+        var iterator = self.watch_descriptors.iterator();
+        while (iterator.next()) |entry| {
+            const watched = entry.key_ptr.*;
+            if (watched.len > path.len and std.mem.startsWith(u8, watched, path) and watched[path.len] == '/') {
+                try below.append(self.path_allocator, watched);
+            }
+        }
+
+        for (below.items) |watched| {
+            self.remove_watch(watched);
+        }
+        self.remove_watch(path);
+    }
+
+    /// Drop one watch descriptor, freeing the map's copy of its path (never
+    /// the caller's)
+    fn remove_watch(self: *Self, path: []const u8) void {
+        if (self.watch_descriptors.fetchRemove(path)) |removed| {
+            _ = linux.inotify_rm_watch(self.inotify_fd, removed.value);
+            self.path_allocator.free(removed.key);
         }
     }
 
@@ -674,7 +674,12 @@ pub const MacOSWatcher = struct {
         }
     }
 
-    pub fn watch_directory(self: *Self, path: []const u8, recursive: bool, event_buffer: *EventRingBuffer) !void {
+    pub fn watch_directory(self: *Self, path: []const u8, recursive: bool, cross_filesystem: bool, max_entries_per_dir: usize, event_buffer: *EventRingBuffer) !void {
+        // FSEvents watches whole subtrees. The Rust layer walks the tree for
+        // mount points (by st_dev) and oversized directories and drops
+        // events below them
+        _ = cross_filesystem;
+        _ = max_entries_per_dir;
         std.log.info("macOS watch_directory called for: {s} (recursive: {})", .{ path, recursive });
 
         // Store reference to event buffer for use in event processing
@@ -243,7 +243,13 @@ pub const WindowsWatcher = struct {
         }
     }
 
-    pub fn watch_directory(self: *Self, path: []const u8, recursive: bool, event_buffer: *EventRingBuffer) !void {
+    pub fn watch_directory(self: *Self, path: []const u8, recursive: bool, cross_filesystem: bool, max_entries_per_dir: usize, event_buffer: *EventRingBuffer) !void {
+        // ReadDirectoryChangesW watches whole subtrees. The Rust layer drops
+        // events below oversized directories, but it cannot detect mount
+        // points on Windows, so volumes mounted into folders are watched
+        // regardless of cross_filesystem
+        _ = cross_filesystem;
+        _ = max_entries_per_dir;
         self.event_buffer = event_buffer;
 
         // Try eBPF first if available (more efficient)
@@ -0,0 +1,96 @@
+#!/usr/bin/env node
+/**
+ * String vs BigInt event field benchmark
+ * Drains a burst of file events through the Node bindings with the default
+ * string fields and with `{ bigint: true }`, comparing per-event cost
+ */
+
+const fs = require('fs');
+const path = require('path');
+const os = require('os');
+const { performance } = require('perf_hooks');
+
+const BURST_SIZE = parseInt(process.env.BURST_SIZE || '5000', 10);
+const DRAIN_IDLE_MS = 500;
+
+async function runBurst(bigint) {
+    const { RetriggerWrapper } = require('../../src/bindings/nodejs');
+    const label = bigint ? 'bigint' : 'string';
+    const testDir = path.join(os.tmpdir(), `retrigger_bigint_bench_${label}_${Date.now()}`);
+    await fs.promises.mkdir(testDir, { recursive: true });
+
+    try {
+        const watcher = new RetriggerWrapper({ bigint });
+        await watcher.watchDirectory(testDir, { recursive: true });
+        await watcher.start();
+
+        for (let i = 0; i < BURST_SIZE; i++) {
+            fs.writeFileSync(path.join(testDir, `file_${i}.txt`), `content ${i}`);
+        }
+
+        if (global.gc) global.gc();
+        const heapBefore = process.memoryUsage().heapUsed;
+
+        let events = 0;
+        let pollTime = 0;
+        let lastEventAt = performance.now();
+
+        while (performance.now() - lastEventAt < DRAIN_IDLE_MS) {
+            const start = performance.now();
+            const event = await watcher.pollEvent();
+            if (event) {
+                pollTime += performance.now() - start;
+                events++;
+                lastEventAt = performance.now();
+            } else {
+                await new Promise(resolve => setImmediate(resolve));
+            }
+        }
+
+        const heapDeltaMB = (process.memoryUsage().heapUsed - heapBefore) / (1024 * 1024);
+
+        return {
+            mode: label,
+            events,
+            totalMs: pollTime,
+            usPerEvent: events > 0 ? (pollTime * 1000) / events : 0,
+            heapDeltaMB,
+        };
+    } finally {
+        await fs.promises.rm(testDir, { recursive: true, force: true });
+    }
+}
+
+async function main() {
+    console.log(`Event field representation benchmark (${BURST_SIZE} file burst)`);
+    console.log('='.repeat(60));
+
+    const results = [];
+    for (const bigint of [false, true]) {
+        results.push(await runBurst(bigint));
+    }
+
+    for (const r of results) {
+        console.log(
+            `${r.mode.padEnd(8)} ${String(r.events).padStart(7)} events  ` +
+            `${r.usPerEvent.toFixed(2).padStart(8)} µs/event  ` +
+            `${r.heapDeltaMB.toFixed(2).padStart(7)} MB heap`
+        );
+    }
+
+    const [strings, bigints] = results;
+    if (strings.usPerEvent > 0 && bigints.usPerEvent > 0) {
+        const speedup = strings.usPerEvent / bigints.usPerEvent;
+        console.log(`\nString/BigInt per-event cost ratio: ${speedup.toFixed(2)}x`);
+    }
+    console.log('Run with --expose-gc for more stable heap numbers');
+}
+
+if (require.main === module) {
+    main().catch(error => {
+        console.error('Benchmark failed:', error);
+        process.exit(1);
+    });
+}
+
+module.exports = { runBurst };
@@ -67,7 +67,7 @@ structured = false
 [patterns]
 include = ["**/*.txt", "**/*.js"]
 exclude = ["**/.DS_Store", "**/.*"]
-max_file_size = 1048576
+max_event_size = 1048576
 ignore_binary = true
 `);
 
@@ -36,6 +36,7 @@ async fn demonstrate_enhanced_processor() -> Result<(), Box<dyn std::error::Erro
         max_entries: 10_000,
         ttl_seconds: 1800, // 30 minutes
         enable_hierarchy: true,
+        max_bytes: None,
     };
     
     let processor = FileEventProcessor::with_config(cache_config);